@@ -0,0 +1,52 @@
+#![no_main]
+
+use arbitrary::Arbitrary;
+use hype_watch::program::*;
+use libfuzzer_sys::fuzz_target;
+
+#[derive(Arbitrary, Debug)]
+struct Input {
+    init_price: f64,
+    max_supply: u64,
+    fee_rate: f64,
+    min_fee: f64,
+    fee_ratio: f64,
+    decimals: u8,
+    supply: u64,
+    amount: u64,
+    referral: Option<(f64, f64)>,
+    upline_ratios: [f64; 2],
+}
+
+// Any root parameters, including ones initialize_root would never be given,
+// must produce an error or a quote that keeps the economic bounds.
+fuzz_target!(|input: Input| {
+    let params = CurveParams {
+        init_price: input.init_price,
+        max_supply: input.max_supply,
+        fee_rate: input.fee_rate,
+        min_fee: input.min_fee,
+        fee_ratio: input.fee_ratio,
+        base_crncy_decs_factor: 10u32.pow((input.decimals % 10) as u32),
+    };
+    if let Ok(quote) = quote_mint(&params, input.supply, input.amount) {
+        assert_eq!(
+            quote.check_fees().is_ok(),
+            quote.fees <= quote.base_crncy_amount
+        );
+        let back = quote_burn(&params, input.supply + input.amount, input.amount).unwrap();
+        assert_eq!(back.base_crncy_amount, quote.base_crncy_amount);
+        let split = split_fees(quote.fees, params.fee_ratio, input.referral)
+            .with_upline_fees(&input.upline_ratios);
+        assert!(split.total() <= quote.fees);
+    }
+    if let Ok(quote) = quote_burn(&params, input.supply, input.amount) {
+        assert_eq!(
+            quote.check_fees().is_ok(),
+            quote.fees <= quote.base_crncy_amount
+        );
+        let split = split_fees(quote.fees, params.fee_ratio, input.referral)
+            .with_upline_fees(&input.upline_ratios);
+        assert!(split.total() <= quote.fees);
+    }
+});
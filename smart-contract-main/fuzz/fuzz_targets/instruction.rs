@@ -0,0 +1,86 @@
+#![no_main]
+
+#[path = "../../tests/common/input.rs"]
+mod input;
+
+use arbitrary::Arbitrary;
+use hype_watch::program::*;
+use input::{process, RawAccount};
+use libfuzzer_sys::fuzz_target;
+use solana_program::{pubkey::Pubkey, system_program};
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+#[derive(Arbitrary, Debug)]
+enum Key {
+    Known(u8),
+    Raw([u8; 32]),
+}
+
+#[derive(Arbitrary, Debug)]
+struct Account {
+    key: Key,
+    owner: Key,
+    is_signer: bool,
+    is_writable: bool,
+    lamports: u64,
+    data: Vec<u8>,
+}
+
+#[derive(Arbitrary, Debug)]
+struct Input {
+    accounts: Vec<Account>,
+    data: Vec<u8>,
+}
+
+fn program_id() -> Pubkey {
+    Pubkey::new_from_array([0x48; 32])
+}
+
+// Addresses the processors compare against, so inputs get past the first checks
+fn known_keys() -> &'static [Pubkey] {
+    static KEYS: OnceLock<Vec<Pubkey>> = OnceLock::new();
+    KEYS.get_or_init(|| {
+        let program_id = program_id();
+        let (hype_auth, _) = Pubkey::find_program_address(&[HYPE_SEED], &program_id);
+        let root_seed = unsafe { get_seed_by_tag(1, ROOT_TAG) };
+        let (root, _) =
+            Pubkey::find_program_address(&[&root_seed, hype_auth.as_ref()], &program_id);
+        vec![
+            program_id,
+            hype_auth,
+            root,
+            system_program::ID,
+            Pubkey::from_str("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA").unwrap(),
+            Pubkey::from_str("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb").unwrap(),
+            Pubkey::from_str("ATokenGPvbdGVxr1b2hQsuNNzSXLRtkMGtmUMoJoXEhb").unwrap(),
+            Pubkey::from_str(HOLDER_ADMIN).unwrap(),
+        ]
+    })
+}
+
+fn key(key: &Key) -> Pubkey {
+    match key {
+        Key::Known(i) => known_keys()[*i as usize % known_keys().len()],
+        Key::Raw(bytes) => Pubkey::new_from_array(*bytes),
+    }
+}
+
+// Any instruction data with any account list has to come back as a result;
+// a panic or an out of bounds access is a finding.
+fuzz_target!(|input: Input| {
+    let accounts: Vec<RawAccount> = input
+        .accounts
+        .iter()
+        .take(32)
+        .map(|a| RawAccount {
+            key: key(&a.key),
+            owner: key(&a.owner),
+            is_signer: a.is_signer,
+            is_writable: a.is_writable,
+            lamports: a.lamports,
+            data: a.data.clone(),
+        })
+        .collect();
+    let _ = process(&program_id(), &accounts, &input.data);
+});
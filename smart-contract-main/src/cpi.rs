@@ -0,0 +1,164 @@
+//! Calls into `cpi_trade` for programs that trade on behalf of their own wallets.
+//! Available with the `no-entrypoint` feature.
+use crate::program::*;
+use crate::state::TradeResult;
+use solana_program::{
+    account_info::AccountInfo,
+    instruction::{AccountMeta, Instruction},
+    program::{get_return_data, invoke_signed},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+/// The 14 accounts of `cpi_trade`, in order. `wallet` signs, directly or through
+/// the seeds passed to `buy` and `sell`.
+pub struct TradeAccounts<'a, 'info> {
+    pub wallet: &'a AccountInfo<'info>,
+    pub client: &'a AccountInfo<'info>,
+    pub client_associated_token: &'a AccountInfo<'info>,
+    pub client_associated_hype: &'a AccountInfo<'info>,
+    pub root: &'a AccountInfo<'info>,
+    pub token: &'a AccountInfo<'info>,
+    pub base_crncy_mint: &'a AccountInfo<'info>,
+    pub base_crncy_program_account: &'a AccountInfo<'info>,
+    pub hype_mint: &'a AccountInfo<'info>,
+    pub hype_program_account: &'a AccountInfo<'info>,
+    pub hype_authority: &'a AccountInfo<'info>,
+    pub base_crncy_token_program: &'a AccountInfo<'info>,
+    pub token_2022_program: &'a AccountInfo<'info>,
+    pub system_program: &'a AccountInfo<'info>,
+}
+
+impl<'a, 'info> TradeAccounts<'a, 'info> {
+    fn to_account_infos(&self) -> Vec<AccountInfo<'info>> {
+        vec![
+            self.wallet.clone(),
+            self.client.clone(),
+            self.client_associated_token.clone(),
+            self.client_associated_hype.clone(),
+            self.root.clone(),
+            self.token.clone(),
+            self.base_crncy_mint.clone(),
+            self.base_crncy_program_account.clone(),
+            self.hype_mint.clone(),
+            self.hype_program_account.clone(),
+            self.hype_authority.clone(),
+            self.base_crncy_token_program.clone(),
+            self.token_2022_program.clone(),
+            self.system_program.clone(),
+        ]
+    }
+}
+
+/// `cpi_trade` instruction on the keys of the 14 accounts listed in `TradeAccounts`,
+/// declaring the `TRADE_EXTRA_*` accounts of `extras` (see `next_trade_extras`).
+pub fn trade_instruction(
+    program_id: &Pubkey,
+    accounts: &[Pubkey; 14],
+    side: u32,
+    amount: u64,
+    limit: u64,
+    extras: u32,
+) -> Instruction {
+    let readonly = [6, 10, 11, 12, 13];
+    let account_metas = accounts
+        .iter()
+        .enumerate()
+        .map(|(i, key)| {
+            if readonly.contains(&i) {
+                AccountMeta::new_readonly(*key, false)
+            } else {
+                AccountMeta::new(*key, i == 0)
+            }
+        })
+        .collect();
+    let mut data = vec![0u8; 32];
+    data[0] = 26;
+    data[8..12].copy_from_slice(&side.to_le_bytes());
+    data[12..16].copy_from_slice(&extras.to_le_bytes());
+    data[16..24].copy_from_slice(&amount.to_le_bytes());
+    data[24..32].copy_from_slice(&limit.to_le_bytes());
+    Instruction::new_with_bytes(*program_id, &data, account_metas)
+}
+
+/// Buys `amount` tokens for at most `max_cost` base currency before fees (0 for no limit).
+/// `remaining_accounts` follow the 14 accounts, see `trade`.
+pub fn buy<'info>(
+    program: &AccountInfo<'info>,
+    accounts: &TradeAccounts<'_, 'info>,
+    remaining_accounts: &[AccountInfo<'info>],
+    amount: u64,
+    max_cost: u64,
+    extras: u32,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<TradeResult, ProgramError> {
+    trade(
+        program,
+        accounts,
+        remaining_accounts,
+        BATCH_SIDE_BUY,
+        amount,
+        max_cost,
+        extras,
+        signer_seeds,
+    )
+}
+
+/// Sells `amount` tokens for at least `min_cashout` base currency before fees (0 for no limit).
+/// `remaining_accounts` follow the 14 accounts, see `trade`.
+pub fn sell<'info>(
+    program: &AccountInfo<'info>,
+    accounts: &TradeAccounts<'_, 'info>,
+    remaining_accounts: &[AccountInfo<'info>],
+    amount: u64,
+    min_cashout: u64,
+    extras: u32,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<TradeResult, ProgramError> {
+    trade(
+        program,
+        accounts,
+        remaining_accounts,
+        BATCH_SIDE_SELL,
+        amount,
+        min_cashout,
+        extras,
+        signer_seeds,
+    )
+}
+
+/// Calls `cpi_trade` with `remaining_accounts` after the 14 accounts, keeping their
+/// signer and writable flags: the delegate and referral accounts `cpi_trade` reads,
+/// then the accounts declared in `extras`.
+fn trade<'info>(
+    program: &AccountInfo<'info>,
+    accounts: &TradeAccounts<'_, 'info>,
+    remaining_accounts: &[AccountInfo<'info>],
+    side: u32,
+    amount: u64,
+    limit: u64,
+    extras: u32,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<TradeResult, ProgramError> {
+    let mut account_infos = accounts.to_account_infos();
+    let mut keys = [Pubkey::default(); 14];
+    for (key, account) in keys.iter_mut().zip(account_infos.iter()) {
+        *key = *account.key;
+    }
+    let mut instruction = trade_instruction(program.key, &keys, side, amount, limit, extras);
+    for account in remaining_accounts {
+        instruction.accounts.push(AccountMeta {
+            pubkey: *account.key,
+            is_signer: account.is_signer,
+            is_writable: account.is_writable,
+        });
+        account_infos.push(account.clone());
+    }
+    invoke_signed(&instruction, &account_infos, signer_seeds)?;
+    match get_return_data() {
+        Some((program_id, data)) if program_id == *program.key => {
+            TradeResult::from_bytes(&data).ok_or_else(|| InvalidDataLength.into())
+        }
+        _ => Err(InvalidDataLength.into()),
+    }
+}
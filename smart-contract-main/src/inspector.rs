@@ -0,0 +1,825 @@
+use crate::program::*;
+use crate::state::*;
+use solana_program::{program_error::ProgramError, pubkey::Pubkey};
+use std::convert::TryInto;
+
+// Off-chain decoder for raw hype_watch account data (e.g. `solana account <ADDRESS> --output-file`).
+// Fields are read by the offsets from `state::data_structure`, so the input does not
+// have to be aligned. Anything that does not add up is reported in "warnings".
+
+struct JsonObject {
+    fields: Vec<(String, String)>,
+}
+
+impl JsonObject {
+    fn new() -> Self {
+        JsonObject { fields: Vec::new() }
+    }
+
+    fn raw(&mut self, key: &str, value: String) {
+        self.fields.push((key.to_string(), value));
+    }
+
+    fn num<T: ToString>(&mut self, key: &str, value: T) {
+        self.raw(key, value.to_string());
+    }
+
+    fn float(&mut self, key: &str, value: f64) {
+        self.raw(key, json_float(value));
+    }
+
+    fn str(&mut self, key: &str, value: &str) {
+        self.raw(key, json_string(value));
+    }
+
+    fn pubkey(&mut self, key: &str, value: &Pubkey) {
+        self.raw(key, json_string(&value.to_string()));
+    }
+
+    fn to_json(&self) -> String {
+        let fields: Vec<String> = self
+            .fields
+            .iter()
+            .map(|(k, v)| format!("{}:{}", json_string(k), v))
+            .collect();
+        format!("{{{}}}", fields.join(","))
+    }
+}
+
+fn json_string(value: &str) -> String {
+    let mut res = String::with_capacity(value.len() + 2);
+    res.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => res.push_str("\\\""),
+            '\\' => res.push_str("\\\\"),
+            '\n' => res.push_str("\\n"),
+            '\r' => res.push_str("\\r"),
+            '\t' => res.push_str("\\t"),
+            c if (c as u32) < 0x20 => res.push_str(&format!("\\u{:04x}", c as u32)),
+            c => res.push(c),
+        }
+    }
+    res.push('"');
+    res
+}
+
+fn json_float(value: f64) -> String {
+    if value.is_finite() {
+        format!("{:?}", value)
+    } else {
+        "null".to_string()
+    }
+}
+
+fn json_array(items: &[String]) -> String {
+    format!("[{}]", items.join(","))
+}
+
+fn read_u32(data: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(data[offset..offset + 4].try_into().unwrap())
+}
+
+fn read_u64(data: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap())
+}
+
+fn read_i64(data: &[u8], offset: usize) -> i64 {
+    i64::from_le_bytes(data[offset..offset + 8].try_into().unwrap())
+}
+
+fn read_u128(data: &[u8], offset: usize) -> u128 {
+    u128::from_le_bytes(data[offset..offset + 16].try_into().unwrap())
+}
+
+fn read_f64(data: &[u8], offset: usize) -> f64 {
+    f64::from_le_bytes(data[offset..offset + 8].try_into().unwrap())
+}
+
+fn read_pubkey(data: &[u8], offset: usize) -> Pubkey {
+    Pubkey::new_from_array(data[offset..offset + 32].try_into().unwrap())
+}
+
+/// Decodes a nul-terminated string field and flags garbage after the terminator.
+fn read_string(
+    data: &[u8],
+    offset: usize,
+    length: usize,
+    field: &str,
+    warnings: &mut Vec<String>,
+) -> String {
+    let bytes = &data[offset..offset + length];
+    let end = bytes.iter().position(|c| *c == 0).unwrap_or(length);
+    if bytes[end..].iter().any(|c| *c != 0) {
+        warnings.push(format!("{}: non-zero bytes after nul terminator", field));
+    }
+    match std::str::from_utf8(&bytes[..end]) {
+        Ok(s) => s.to_string(),
+        Err(_) => {
+            warnings.push(format!("{}: not valid utf-8", field));
+            String::from_utf8_lossy(&bytes[..end]).into_owned()
+        }
+    }
+}
+
+fn token_status_name(status: u64) -> Option<&'static str> {
+    match status {
+        s if s == token_status::NOT_CHECKED as u64 => Some("not_checked"),
+        s if s == token_status::VERIFIED as u64 => Some("verified"),
+        s if s == token_status::NOT_VERIFIED as u64 => Some("not_verified"),
+        _ => None,
+    }
+}
+
+fn check_length(
+    kind: &str,
+    data: &[u8],
+    expected: usize,
+    warnings: &mut Vec<String>,
+) -> Result<(), ProgramError> {
+    if data.len() < expected {
+        return Err(InvalidDataLength.into());
+    }
+    if data.len() > expected {
+        warnings.push(format!(
+            "{} account has {} bytes, expected {}",
+            kind,
+            data.len(),
+            expected
+        ));
+    }
+    Ok(())
+}
+
+fn inspect_holder(
+    data: &[u8],
+    obj: &mut JsonObject,
+    warnings: &mut Vec<String>,
+) -> Result<(), ProgramError> {
+    use holder_account_offsets::*;
+    if data.len() < HOLDER_ACCOUNT_SIZE {
+        return Err(InvalidDataLength.into());
+    }
+    let operators_count = read_u32(data, OPERATORS_COUNT);
+    obj.str("type", "holder");
+    obj.num("tag", read_u32(data, TAG));
+    obj.num("version", read_u32(data, VERSION));
+    obj.pubkey("wallet", &read_pubkey(data, WALLET));
+    obj.num("slot", read_u64(data, SLOT));
+    obj.num("time", read_u32(data, TIME));
+    obj.num("operators_count", operators_count);
+
+    let expected = OPERATORS_OFFSET + operators_count as usize * OPERATOR_RECORD_SIZE;
+    let available = (data.len() - OPERATORS_OFFSET) / OPERATOR_RECORD_SIZE;
+    if expected != data.len() {
+        warnings.push(format!(
+            "operators_count {} requires {} bytes, account has {}",
+            operators_count,
+            expected,
+            data.len()
+        ));
+    }
+    let mut operators = Vec::new();
+    for i in 0..(operators_count as usize).min(available) {
+        let offset = OPERATORS_OFFSET + i * OPERATOR_RECORD_SIZE;
+        let mut operator = JsonObject::new();
+        operator.pubkey(
+            "operator_address",
+            &read_pubkey(data, offset + operator_record_offsets::OPERATOR_ADDRESS),
+        );
+        operator.num(
+            "version",
+            read_u32(data, offset + operator_record_offsets::VERSION),
+        );
+        operator.num(
+            "max_networks_count",
+            read_u32(data, offset + operator_record_offsets::MAX_NETWORKS_COUNT),
+        );
+        let name = read_string(
+            data,
+            offset + operator_record_offsets::OPERATOR_NAME,
+            OPERATOR_NAME_STRING_LENGTH,
+            &format!("operators[{}].operator_name", i),
+            warnings,
+        );
+        operator.str("operator_name", &name);
+        operators.push(operator.to_json());
+    }
+    obj.raw("operators", json_array(&operators));
+    Ok(())
+}
+
+fn inspect_root(
+    data: &[u8],
+    obj: &mut JsonObject,
+    warnings: &mut Vec<String>,
+) -> Result<(), ProgramError> {
+    use root_account_offsets::*;
+    if data.len() < ROOT_ACCOUNT_SIZE {
+        return Err(InvalidDataLength.into());
+    }
+    let networks_count = read_u32(data, NETWORKS_COUNT);
+    let max_networks_count = read_u32(data, MAX_NETWORKS_COUNT);
+    let decimals = read_u32(data, DECIMALS);
+    let base_crncy_decs_factor = read_u32(data, BASE_CRNCY_DECS_FACTOR);
+    obj.str("type", "root");
+    obj.num("tag", read_u32(data, TAG));
+    obj.num("version", read_u32(data, VERSION));
+    obj.pubkey("admin", &read_pubkey(data, ADMIN));
+    obj.pubkey("fee_wallet", &read_pubkey(data, FEE_WALLET));
+    obj.pubkey("base_crncy_mint", &read_pubkey(data, BASE_CRNCY_MINT));
+    obj.pubkey(
+        "base_crncy_program_address",
+        &read_pubkey(data, BASE_CRNCY_PROGRAM_ADDRESS),
+    );
+    obj.num("clients_count", read_u64(data, CLIENTS_COUNT));
+    obj.num("tokens_count", read_u64(data, TOKENS_COUNT));
+    obj.num("fees", read_u64(data, FEES));
+    obj.num("networks_count", networks_count);
+    obj.num("base_crncy_decs_factor", base_crncy_decs_factor);
+    obj.num("slot", read_u64(data, SLOT));
+    obj.num("time", read_u32(data, TIME));
+    obj.num("decimals", decimals);
+    obj.num("supply", read_u64(data, SUPPLY));
+    obj.num("tvl", read_u64(data, TVL));
+    obj.num("counter", read_u64(data, COUNTER));
+    obj.num(
+        "all_time_base_crncy_volume",
+        read_u128(data, ALL_TIME_BASE_CRNCY_VOLUME),
+    );
+    obj.num(
+        "all_time_tokens_volume",
+        read_u128(data, ALL_TIME_TOKENS_VOLUME),
+    );
+    obj.num("holder_fees", read_u64(data, HOLDER_FEES));
+    obj.float("init_price", read_f64(data, INIT_PRICE));
+    obj.num("max_supply", read_u64(data, MAX_SUPPLY));
+    obj.float("fee_ratio", read_f64(data, FEE_RATIO));
+    obj.float("fee_rate", read_f64(data, FEE_RATE));
+    obj.float("creation_fee", read_f64(data, CREATION_FEE));
+    obj.num("max_networks_count", max_networks_count);
+    obj.num("creation_time", read_u32(data, CREATION_TIME));
+    obj.float("min_fee", read_f64(data, MIN_FEE));
+    let operator_name = read_string(
+        data,
+        OPERATOR_NANE,
+        OPERATOR_NAME_STRING_LENGTH,
+        "operator_name",
+        warnings,
+    );
+    obj.str("operator_name", &operator_name);
+    obj.num("ref_duration", read_u32(data, REF_DURATION));
+    obj.num("mask", read_u32(data, MASK));
+    obj.float("ref_discount", read_f64(data, REF_DISCOUNT));
+    obj.float("ref_ratio", read_f64(data, REF_RATIO));
+    let url_prefix = read_string(
+        data,
+        URL_PREFIX,
+        URL_PREFIX_STRING_LENGTH,
+        "url_prefix",
+        warnings,
+    );
+    obj.str("url_prefix", &url_prefix);
+    obj.num("ref_fees", read_u64(data, REF_FEES));
+    let ref_depth = read_u32(data, REF_DEPTH);
+    obj.num("ref_depth", ref_depth);
+    obj.num("ref_grace_period", read_u32(data, REF_GRACE_PERIOD));
+    let ref_upline_ratios: Vec<String> = (0..MAX_REF_DEPTH - 1)
+        .map(|i| json_float(read_f64(data, REF_UPLINE_RATIOS + i * 8)))
+        .collect();
+    obj.raw("ref_upline_ratios", json_array(&ref_upline_ratios));
+    let fee_tiers_count = read_u32(data, FEE_TIERS_COUNT);
+    obj.num("fee_tiers_count", fee_tiers_count);
+    let mut fee_tiers = Vec::new();
+    for i in 0..(fee_tiers_count as usize).min(MAX_FEE_TIERS) {
+        let offset = FEE_TIERS + i * FEE_TIER_SIZE;
+        let mut tier = JsonObject::new();
+        tier.num(
+            "min_volume",
+            read_u64(data, offset + fee_tier_offsets::MIN_VOLUME),
+        );
+        tier.float(
+            "fee_rate",
+            read_f64(data, offset + fee_tier_offsets::FEE_RATE),
+        );
+        fee_tiers.push(tier.to_json());
+    }
+    obj.raw("fee_tiers", json_array(&fee_tiers));
+    obj.float("creator_fee_ratio", read_f64(data, CREATOR_FEE_RATIO));
+    obj.num("creator_fees", read_u64(data, CREATOR_FEES));
+    obj.num("retired_tokens_count", read_u64(data, RETIRED_TOKENS_COUNT));
+    obj.num("token_idle_period", read_u32(data, TOKEN_IDLE_PERIOD));
+    obj.num("candle_interval", read_u32(data, CANDLE_INTERVAL));
+    obj.float("switch_fee_rate", read_f64(data, SWITCH_FEE_RATE));
+    obj.num("launch_window", read_u32(data, LAUNCH_WINDOW));
+    obj.num("restricted_window", read_u32(data, RESTRICTED_WINDOW));
+    obj.num("launch_max_buy", read_u64(data, LAUNCH_MAX_BUY));
+    obj.num("buy_cooldown", read_u32(data, BUY_COOLDOWN));
+    obj.num("graduation_reserve", read_u64(data, GRADUATION_RESERVE));
+    obj.float("pool_fee_rate", read_f64(data, POOL_FEE_RATE));
+    obj.num("pool_reserves", read_u64(data, POOL_RESERVES));
+
+    if fee_tiers_count as usize > MAX_FEE_TIERS {
+        warnings.push(format!(
+            "fee_tiers_count {} exceeds {}",
+            fee_tiers_count, MAX_FEE_TIERS
+        ));
+    }
+    if ref_depth == 0 || ref_depth as usize > MAX_REF_DEPTH {
+        warnings.push(format!(
+            "ref_depth {} is outside [1, {}]",
+            ref_depth, MAX_REF_DEPTH
+        ));
+    }
+
+    if 10u64.checked_pow(decimals) != Some(base_crncy_decs_factor as u64) {
+        warnings.push(format!(
+            "base_crncy_decs_factor {} does not match decimals {}",
+            base_crncy_decs_factor, decimals
+        ));
+    }
+    if max_networks_count > 0 && networks_count > max_networks_count {
+        warnings.push(format!(
+            "networks_count {} exceeds max_networks_count {}",
+            networks_count, max_networks_count
+        ));
+    }
+    let expected = NETWORK_RECORDS + networks_count as usize * NETWORK_RECORD_SIZE;
+    let available = (data.len() - NETWORK_RECORDS) / NETWORK_RECORD_SIZE;
+    if expected != data.len() {
+        warnings.push(format!(
+            "networks_count {} requires {} bytes, account has {}",
+            networks_count,
+            expected,
+            data.len()
+        ));
+    }
+    let mut networks = Vec::new();
+    for i in 0..(networks_count as usize).min(available) {
+        let offset = NETWORK_RECORDS + i * NETWORK_RECORD_SIZE;
+        let mut network = JsonObject::new();
+        network.num("id", i);
+        network.num(
+            "max_length",
+            read_u64(data, offset + network_record_offsets::MAX_LENGTH),
+        );
+        network.pubkey(
+            "validator",
+            &read_pubkey(data, offset + network_record_offsets::VALIDATOR),
+        );
+        let descriptor = read_string(
+            data,
+            offset + network_record_offsets::DESCRIPTOR,
+            NETWORK_STRING_LENGTH,
+            &format!("networks[{}].descriptor", i),
+            warnings,
+        );
+        network.str("descriptor", &descriptor);
+        let mask = read_string(
+            data,
+            offset + network_record_offsets::MASK,
+            MASK_STRING_LENGTH,
+            &format!("networks[{}].mask", i),
+            warnings,
+        );
+        network.str("mask", &mask);
+        network.float(
+            "creator_fee_cap",
+            read_f64(data, offset + network_record_offsets::CREATOR_FEE_CAP),
+        );
+        networks.push(network.to_json());
+    }
+    obj.raw("networks", json_array(&networks));
+    Ok(())
+}
+
+fn inspect_token(
+    data: &[u8],
+    obj: &mut JsonObject,
+    warnings: &mut Vec<String>,
+) -> Result<(), ProgramError> {
+    use token_account_offsets::*;
+    check_length("token", data, TOKEN_ACCOUNT_SIZE, warnings)?;
+    let status = read_u64(data, VALIDATION);
+    obj.str("type", "token");
+    obj.num("tag", read_u32(data, TAG));
+    obj.num("version", read_u32(data, VERSION));
+    obj.num("id", read_u64(data, ID));
+    obj.pubkey("mint", &read_pubkey(data, MINT));
+    obj.pubkey("program_address", &read_pubkey(data, PROGRAM_ADDRESS));
+    obj.pubkey("creator", &read_pubkey(data, CREATOR));
+    obj.num("creation_time", read_u32(data, CREATION_TIME));
+    obj.num("time", read_u32(data, TIME));
+    obj.num("supply", read_u64(data, SUPPLY));
+    let address = read_string(data, ADDRESS, ADDRESS_STRING_LENGTH, "address", warnings);
+    obj.str("address", &address);
+    obj.num("network", read_u32(data, NETWORK));
+    obj.num("graduated", read_u32(data, GRADUATED));
+    obj.num("slot", read_u64(data, SLOT));
+    obj.num(
+        "all_time_trades_count",
+        read_u64(data, ALL_TIME_TRADES_COUNT),
+    );
+    obj.num(
+        "all_time_base_crncy_volume",
+        read_u128(data, ALL_TIME_BASE_CRNCY_VOLUME),
+    );
+    obj.num(
+        "all_time_tokens_volume",
+        read_u128(data, ALL_TIME_TOKENS_VOLUME),
+    );
+    match token_status_name(status) {
+        Some(name) => obj.str("status", name),
+        None => {
+            warnings.push(format!("unknown token status {}", status));
+            obj.num("status", status);
+        }
+    }
+    obj.num("creator_fees", read_u64(data, CREATOR_FEES));
+    obj.num(
+        "all_time_creator_fees",
+        read_u64(data, ALL_TIME_CREATOR_FEES),
+    );
+    obj.float("twap_price", read_f64(data, TWAP_PRICE));
+    obj.float("price_cumulative", read_f64(data, PRICE_CUMULATIVE));
+    obj.num("twap_time", read_u32(data, TWAP_TIME));
+    let observation_head = read_u32(data, OBSERVATION_HEAD);
+    let observations_count = read_u32(data, OBSERVATIONS_COUNT);
+    obj.num("observation_head", observation_head);
+    obj.num("observations_count", observations_count);
+    obj.num("price_history", read_u32(data, PRICE_HISTORY));
+    let mut observations = Vec::new();
+    for i in 0..(observations_count as usize).min(TWAP_OBSERVATIONS) {
+        let offset = OBSERVATIONS + i * OBSERVATION_SIZE;
+        let mut observation = JsonObject::new();
+        observation.num("time", read_u32(data, offset + observation_offsets::TIME));
+        observation.float(
+            "price_cumulative",
+            read_f64(data, offset + observation_offsets::PRICE_CUMULATIVE),
+        );
+        observations.push(observation.to_json());
+    }
+    obj.raw("observations", json_array(&observations));
+    if observation_head as usize >= TWAP_OBSERVATIONS {
+        warnings.push(format!(
+            "observation_head {} exceeds {}",
+            observation_head,
+            TWAP_OBSERVATIONS - 1
+        ));
+    }
+    Ok(())
+}
+
+fn inspect_client(
+    data: &[u8],
+    obj: &mut JsonObject,
+    warnings: &mut Vec<String>,
+) -> Result<(), ProgramError> {
+    use client_account_offsets::*;
+    check_length("client", data, CLIENT_ACCOUNT_SIZE, warnings)?;
+    let ref_discount = read_f64(data, REF_DISCOUNT);
+    let ref_ratio = read_f64(data, REF_RATIO);
+    obj.str("type", "client");
+    obj.num("tag", read_u32(data, TAG));
+    obj.num("version", read_u32(data, VERSION));
+    obj.num("id", read_u64(data, ID));
+    obj.pubkey("wallet", &read_pubkey(data, WALLET));
+    obj.num(
+        "all_time_base_crncy_volume",
+        read_u64(data, ALL_TIME_BASE_CRNCY_VOLUME),
+    );
+    obj.num(
+        "all_time_tokens_volume",
+        read_u64(data, ALL_TIME_TOKENS_VOLUME),
+    );
+    obj.num("slot", read_u64(data, SLOT));
+    obj.num("time", read_u32(data, TIME));
+    obj.num("tokens_created", read_u32(data, TOKENS_CREATED));
+    obj.num("ref_stop", read_u32(data, REF_STOP));
+    obj.num(
+        "all_time_trades_count",
+        read_u32(data, ALL_TIME_TRADES_COUNT),
+    );
+    let nickname = read_string(data, NICKNAME, NICKNAME_STRING_LENGTH, "nickname", warnings);
+    obj.str("nickname", &nickname);
+    obj.pubkey("ref_address", &read_pubkey(data, REF_ADDRESS));
+    obj.num("ref_paid", read_u64(data, REF_PAID));
+    obj.float("ref_discount", ref_discount);
+    obj.float("ref_ratio", ref_ratio);
+    if !(0.0..=1.0).contains(&ref_discount) || !(0.0..=1.0).contains(&ref_ratio) {
+        warnings.push("ref_discount and ref_ratio must be within [0, 1]".to_string());
+    }
+    let mut ref_uplines = Vec::new();
+    let mut ref_upline_ratios = Vec::new();
+    for i in 0..MAX_REF_DEPTH - 1 {
+        ref_uplines.push(json_string(
+            &read_pubkey(data, REF_UPLINES + i * 32).to_string(),
+        ));
+        ref_upline_ratios.push(json_float(read_f64(data, REF_UPLINE_RATIOS + i * 8)));
+    }
+    obj.raw("ref_uplines", json_array(&ref_uplines));
+    obj.raw("ref_upline_ratios", json_array(&ref_upline_ratios));
+    obj.num("creation_time", read_u32(data, CREATION_TIME));
+    obj.num("tracks_positions", read_u32(data, TRACKS_POSITIONS));
+    Ok(())
+}
+
+fn inspect_referrer(
+    data: &[u8],
+    obj: &mut JsonObject,
+    warnings: &mut Vec<String>,
+) -> Result<(), ProgramError> {
+    use referrer_account_offsets::*;
+    check_length("referrer", data, REFERRER_ACCOUNT_SIZE, warnings)?;
+    let fees = read_u64(data, FEES);
+    let all_time_fees = read_u64(data, ALL_TIME_FEES);
+    let all_time_claimed = read_u64(data, ALL_TIME_CLAIMED);
+    obj.str("type", "referrer");
+    obj.num("tag", read_u32(data, TAG));
+    obj.num("version", read_u32(data, VERSION));
+    obj.pubkey("wallet", &read_pubkey(data, WALLET));
+    obj.num("fees", fees);
+    obj.num("all_time_fees", all_time_fees);
+    obj.num("all_time_claimed", all_time_claimed);
+    obj.num("slot", read_u64(data, SLOT));
+    obj.num("time", read_u32(data, TIME));
+    if all_time_claimed.checked_add(fees) != Some(all_time_fees) {
+        warnings.push(format!(
+            "fees {} and all_time_claimed {} do not add up to all_time_fees {}",
+            fees, all_time_claimed, all_time_fees
+        ));
+    }
+    Ok(())
+}
+
+fn inspect_ref_code(
+    data: &[u8],
+    obj: &mut JsonObject,
+    warnings: &mut Vec<String>,
+) -> Result<(), ProgramError> {
+    use ref_code_account_offsets::*;
+    check_length("ref_code", data, REF_CODE_ACCOUNT_SIZE, warnings)?;
+    obj.str("type", "ref_code");
+    obj.num("tag", read_u32(data, TAG));
+    obj.num("version", read_u32(data, VERSION));
+    obj.pubkey("wallet", &read_pubkey(data, WALLET));
+    let code = read_string(data, CODE, NICKNAME_STRING_LENGTH, "code", warnings);
+    obj.str("code", &code);
+    obj.num("slot", read_u64(data, SLOT));
+    obj.num("time", read_u32(data, TIME));
+    let raw_code = &data[CODE..CODE + NICKNAME_STRING_LENGTH];
+    if normalize_ref_code(raw_code).map_or(true, |normalized| normalized[..] != *raw_code) {
+        warnings.push("code is not a normalized referral code".to_string());
+    }
+    Ok(())
+}
+
+fn inspect_nickname(
+    data: &[u8],
+    obj: &mut JsonObject,
+    warnings: &mut Vec<String>,
+) -> Result<(), ProgramError> {
+    use nickname_account_offsets::*;
+    check_length("nickname", data, NICKNAME_ACCOUNT_SIZE, warnings)?;
+    obj.str("type", "nickname");
+    obj.num("tag", read_u32(data, TAG));
+    obj.num("version", read_u32(data, VERSION));
+    obj.pubkey("wallet", &read_pubkey(data, WALLET));
+    let nickname = read_string(data, NICKNAME, NICKNAME_STRING_LENGTH, "nickname", warnings);
+    obj.str("nickname", &nickname);
+    obj.num("slot", read_u64(data, SLOT));
+    obj.num("time", read_u32(data, TIME));
+    let raw_nickname = &data[NICKNAME..NICKNAME + NICKNAME_STRING_LENGTH];
+    if normalize_nickname(raw_nickname).map_or(true, |normalized| normalized[..] != *raw_nickname) {
+        warnings.push("nickname is not normalized".to_string());
+    }
+    Ok(())
+}
+
+fn inspect_position(
+    data: &[u8],
+    obj: &mut JsonObject,
+    warnings: &mut Vec<String>,
+) -> Result<(), ProgramError> {
+    use position_account_offsets::*;
+    check_length("position", data, POSITION_ACCOUNT_SIZE, warnings)?;
+    let quantity = read_u64(data, QUANTITY);
+    let cost_basis = read_u64(data, COST_BASIS);
+    obj.str("type", "position");
+    obj.num("tag", read_u32(data, TAG));
+    obj.num("version", read_u32(data, VERSION));
+    obj.pubkey("wallet", &read_pubkey(data, WALLET));
+    obj.pubkey("mint", &read_pubkey(data, MINT));
+    obj.num("quantity", quantity);
+    obj.num("cost_basis", cost_basis);
+    obj.num("total_cost", read_u64(data, TOTAL_COST));
+    obj.num("total_proceeds", read_u64(data, TOTAL_PROCEEDS));
+    obj.num("realized_pnl", read_i64(data, REALIZED_PNL));
+    obj.num("slot", read_u64(data, SLOT));
+    obj.num("time", read_u32(data, TIME));
+    obj.num("trades_count", read_u32(data, TRADES_COUNT));
+    if quantity == 0 && cost_basis != 0 {
+        warnings.push(format!("cost_basis {} without quantity", cost_basis));
+    }
+    Ok(())
+}
+
+fn inspect_delegate(
+    data: &[u8],
+    obj: &mut JsonObject,
+    warnings: &mut Vec<String>,
+) -> Result<(), ProgramError> {
+    use delegate_account_offsets::*;
+    check_length("delegate", data, DELEGATE_ACCOUNT_SIZE, warnings)?;
+    let spend_limit = read_u64(data, SPEND_LIMIT);
+    let spent = read_u64(data, SPENT);
+    obj.str("type", "delegate");
+    obj.num("tag", read_u32(data, TAG));
+    obj.num("version", read_u32(data, VERSION));
+    obj.pubkey("wallet", &read_pubkey(data, WALLET));
+    obj.pubkey("delegate", &read_pubkey(data, DELEGATE));
+    obj.num("spend_limit", spend_limit);
+    obj.num("spent", spent);
+    obj.num("networks", read_u64(data, NETWORKS));
+    obj.num("period", read_u32(data, PERIOD));
+    obj.num("period_start", read_u32(data, PERIOD_START));
+    obj.num("expiry", read_u32(data, EXPIRY));
+    obj.num("bump_seed", read_u32(data, BUMP_SEED));
+    obj.num("slot", read_u64(data, SLOT));
+    obj.num("time", read_u32(data, TIME));
+    obj.num("trades_count", read_u32(data, TRADES_COUNT));
+    if spend_limit > 0 && spent > spend_limit {
+        warnings.push(format!(
+            "spent {} exceeds spend_limit {}",
+            spent, spend_limit
+        ));
+    }
+    Ok(())
+}
+
+fn inspect_launch_allowance(
+    data: &[u8],
+    obj: &mut JsonObject,
+    warnings: &mut Vec<String>,
+) -> Result<(), ProgramError> {
+    use launch_allowance_account_offsets::*;
+    check_length(
+        "launch_allowance",
+        data,
+        LAUNCH_ALLOWANCE_ACCOUNT_SIZE,
+        warnings,
+    )?;
+    obj.str("type", "launch_allowance");
+    obj.num("tag", read_u32(data, TAG));
+    obj.num("version", read_u32(data, VERSION));
+    obj.pubkey("wallet", &read_pubkey(data, WALLET));
+    obj.num("slot", read_u64(data, SLOT));
+    obj.num("time", read_u32(data, TIME));
+    Ok(())
+}
+
+fn inspect_launch_buy(
+    data: &[u8],
+    obj: &mut JsonObject,
+    warnings: &mut Vec<String>,
+) -> Result<(), ProgramError> {
+    use launch_buy_account_offsets::*;
+    check_length("launch_buy", data, LAUNCH_BUY_ACCOUNT_SIZE, warnings)?;
+    obj.str("type", "launch_buy");
+    obj.num("tag", read_u32(data, TAG));
+    obj.num("version", read_u32(data, VERSION));
+    obj.pubkey("wallet", &read_pubkey(data, WALLET));
+    obj.pubkey("mint", &read_pubkey(data, MINT));
+    obj.num("bought", read_u64(data, BOUGHT));
+    obj.num("slot", read_u64(data, SLOT));
+    obj.num("last_buy_time", read_u32(data, LAST_BUY_TIME));
+    obj.num("buys_count", read_u32(data, BUYS_COUNT));
+    Ok(())
+}
+
+fn inspect_pool(
+    data: &[u8],
+    obj: &mut JsonObject,
+    warnings: &mut Vec<String>,
+) -> Result<(), ProgramError> {
+    use pool_account_offsets::*;
+    check_length("pool", data, POOL_ACCOUNT_SIZE, warnings)?;
+    let base_crncy_reserve = read_u64(data, BASE_CRNCY_RESERVE);
+    let token_reserve = read_u64(data, TOKEN_RESERVE);
+    obj.str("type", "pool");
+    obj.num("tag", read_u32(data, TAG));
+    obj.num("version", read_u32(data, VERSION));
+    obj.pubkey("mint", &read_pubkey(data, MINT));
+    obj.pubkey("token", &read_pubkey(data, TOKEN));
+    obj.num("base_crncy_reserve", base_crncy_reserve);
+    obj.num("token_reserve", token_reserve);
+    obj.num(
+        "all_time_base_crncy_volume",
+        read_u64(data, ALL_TIME_BASE_CRNCY_VOLUME),
+    );
+    obj.num(
+        "all_time_tokens_volume",
+        read_u64(data, ALL_TIME_TOKENS_VOLUME),
+    );
+    obj.num("slot", read_u64(data, SLOT));
+    obj.num("time", read_u32(data, TIME));
+    obj.num("swaps_count", read_u32(data, SWAPS_COUNT));
+    obj.num("creation_time", read_u32(data, CREATION_TIME));
+    if base_crncy_reserve == 0 || token_reserve == 0 {
+        warnings.push("pool with an empty reserve".to_string());
+    }
+    Ok(())
+}
+
+fn inspect_price_history(
+    data: &[u8],
+    obj: &mut JsonObject,
+    warnings: &mut Vec<String>,
+) -> Result<(), ProgramError> {
+    use price_history_account_offsets::*;
+    check_length("price_history", data, PRICE_HISTORY_ACCOUNT_SIZE, warnings)?;
+    let interval = read_u32(data, INTERVAL);
+    let head = read_u32(data, HEAD) as usize;
+    let candles_count = if head < PRICE_HISTORY_CANDLES {
+        (read_u32(data, CANDLES_COUNT) as usize).min(PRICE_HISTORY_CANDLES)
+    } else {
+        0
+    };
+    obj.str("type", "price_history");
+    obj.num("tag", read_u32(data, TAG));
+    obj.num("version", read_u32(data, VERSION));
+    obj.pubkey("mint", &read_pubkey(data, MINT));
+    obj.num("interval", interval);
+    obj.num("head", head);
+    obj.num("candles_count", read_u32(data, CANDLES_COUNT));
+    obj.num("slot", read_u64(data, SLOT));
+    // Oldest candle first
+    let mut candles = Vec::new();
+    for i in 0..candles_count {
+        let index = (head + PRICE_HISTORY_CANDLES + 1 + i - candles_count) % PRICE_HISTORY_CANDLES;
+        let offset = CANDLES + index * CANDLE_SIZE;
+        let mut candle = JsonObject::new();
+        candle.num(
+            "start_time",
+            read_u32(data, offset + candle_offsets::START_TIME),
+        );
+        candle.num(
+            "trades_count",
+            read_u32(data, offset + candle_offsets::TRADES_COUNT),
+        );
+        candle.float("open", read_f64(data, offset + candle_offsets::OPEN));
+        candle.float("high", read_f64(data, offset + candle_offsets::HIGH));
+        candle.float("low", read_f64(data, offset + candle_offsets::LOW));
+        candle.float("close", read_f64(data, offset + candle_offsets::CLOSE));
+        candle.num(
+            "base_crncy_volume",
+            read_u64(data, offset + candle_offsets::BASE_CRNCY_VOLUME),
+        );
+        candle.num(
+            "tokens_volume",
+            read_u64(data, offset + candle_offsets::TOKENS_VOLUME),
+        );
+        candles.push(candle.to_json());
+    }
+    obj.raw("candles", json_array(&candles));
+    if interval == 0 {
+        warnings.push("interval is 0".to_string());
+    }
+    if head >= PRICE_HISTORY_CANDLES {
+        warnings.push(format!(
+            "head {} exceeds {}",
+            head,
+            PRICE_HISTORY_CANDLES - 1
+        ));
+    }
+    Ok(())
+}
+
+/// Detects a hype_watch account by its tag and renders every field as a JSON object.
+pub fn inspect_account(data: &[u8]) -> Result<String, ProgramError> {
+    if data.len() < 4 {
+        return Err(InvalidDataLength.into());
+    }
+    let mut obj = JsonObject::new();
+    let mut warnings = Vec::new();
+    match read_u32(data, 0) {
+        HOLDER_TAG => inspect_holder(data, &mut obj, &mut warnings)?,
+        ROOT_TAG => inspect_root(data, &mut obj, &mut warnings)?,
+        TOKEN_TAG => inspect_token(data, &mut obj, &mut warnings)?,
+        CLIENT_TAG => inspect_client(data, &mut obj, &mut warnings)?,
+        REFERRER_TAG => inspect_referrer(data, &mut obj, &mut warnings)?,
+        REF_CODE_TAG => inspect_ref_code(data, &mut obj, &mut warnings)?,
+        NICKNAME_TAG => inspect_nickname(data, &mut obj, &mut warnings)?,
+        POSITION_TAG => inspect_position(data, &mut obj, &mut warnings)?,
+        PRICE_HISTORY_TAG => inspect_price_history(data, &mut obj, &mut warnings)?,
+        DELEGATE_TAG => inspect_delegate(data, &mut obj, &mut warnings)?,
+        LAUNCH_ALLOWANCE_TAG => inspect_launch_allowance(data, &mut obj, &mut warnings)?,
+        LAUNCH_BUY_TAG => inspect_launch_buy(data, &mut obj, &mut warnings)?,
+        POOL_TAG => inspect_pool(data, &mut obj, &mut warnings)?,
+        _ => return Err(InvalidAccountTag.into()),
+    }
+    obj.num("data_length", data.len());
+    let warnings: Vec<String> = warnings.iter().map(|w| json_string(w)).collect();
+    obj.raw("warnings", json_array(&warnings));
+    Ok(obj.to_json())
+}
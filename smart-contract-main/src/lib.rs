@@ -1,18 +1,25 @@
-use solana_program::{
-    account_info::AccountInfo, entrypoint, entrypoint::ProgramResult, pubkey::Pubkey,
-};
+use solana_program::{account_info::AccountInfo, entrypoint::ProgramResult, pubkey::Pubkey};
 
+#[cfg(feature = "no-entrypoint")]
+pub mod cpi;
+#[cfg(not(target_os = "solana"))]
+pub mod inspector;
 pub mod program;
 pub mod state;
 use crate::program::processor::*;
+use crate::program::{InvalidDataLength, InvalidInstruction};
 
-entrypoint!(process_instruction);
+#[cfg(not(feature = "no-entrypoint"))]
+solana_program::entrypoint!(process_instruction);
 
 pub fn process_instruction(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
     _instruction_data: &[u8],
 ) -> ProgramResult {
+    if _instruction_data.is_empty() {
+        return Err(InvalidDataLength.into());
+    }
     match _instruction_data[0] {
         0 => unsafe {
             initialize_holder(program_id, accounts, _instruction_data)?;
@@ -44,7 +51,70 @@ pub fn process_instruction(
         9 => unsafe {
             withdraw_holder_funds(program_id, accounts)?;
         },
-        _ => {}
+        10 => unsafe {
+            check_invariants(program_id, accounts)?;
+        },
+        11 => unsafe {
+            claim_referral_fees(program_id, accounts)?;
+        },
+        12 => unsafe {
+            register_ref_code(program_id, accounts, _instruction_data)?;
+        },
+        13 => unsafe {
+            release_ref_code(program_id, accounts)?;
+        },
+        14 => unsafe {
+            set_referrer(program_id, accounts)?;
+        },
+        15 => unsafe {
+            set_fee_tiers(program_id, accounts, _instruction_data)?;
+        },
+        16 => unsafe {
+            claim_creator_fees(program_id, accounts)?;
+        },
+        17 => unsafe {
+            close_client(program_id, accounts)?;
+        },
+        18 => unsafe {
+            retire_token(program_id, accounts)?;
+        },
+        19 => unsafe {
+            init_price_history(program_id, accounts)?;
+        },
+        20 => unsafe {
+            get_twap(program_id, accounts, _instruction_data)?;
+        },
+        21 => unsafe {
+            batch_trade(program_id, accounts, _instruction_data)?;
+        },
+        22 => unsafe {
+            switch(program_id, accounts, _instruction_data)?;
+        },
+        23 => unsafe {
+            mint_for(program_id, accounts, _instruction_data)?;
+        },
+        24 => unsafe {
+            set_delegate(program_id, accounts, _instruction_data)?;
+        },
+        25 => unsafe {
+            revoke_delegate(program_id, accounts)?;
+        },
+        26 => unsafe {
+            cpi_trade(program_id, accounts, _instruction_data)?;
+        },
+        27 => unsafe {
+            set_launch_allowance(program_id, accounts, _instruction_data)?;
+        },
+        28 => unsafe {
+            graduate(program_id, accounts)?;
+        },
+        29 => unsafe {
+            swap(program_id, accounts, _instruction_data)?;
+        },
+        30 => unsafe {
+            migrate_account(program_id, accounts)?;
+        },
+        _ => return Err(InvalidInstruction.into()),
     }
     Ok(())
 }
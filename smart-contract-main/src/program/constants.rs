@@ -1,17 +1,63 @@
 pub const HYPE_SEED: &[u8] = b"hypewtch";
+pub const HOLDER_ADMIN: &str = "5V5zbRbs7wFAu5bE2JgYC3aLXKmfwA1rKfg1cXCsuk1p";
 pub const HOLDER_TAG: u32 = 1;
 pub const ROOT_TAG: u32 = 2;
 pub const TOKEN_TAG: u32 = 3;
 pub const CLIENT_TAG: u32 = 4;
+pub const REFERRER_TAG: u32 = 5;
+pub const REF_CODE_TAG: u32 = 6;
+pub const NICKNAME_TAG: u32 = 7;
+pub const POSITION_TAG: u32 = 8;
+pub const PRICE_HISTORY_TAG: u32 = 9;
+pub const DELEGATE_TAG: u32 = 10;
+pub const LAUNCH_ALLOWANCE_TAG: u32 = 11;
+pub const POOL_TAG: u32 = 12;
+pub const LAUNCH_BUY_TAG: u32 = 13;
 pub const NETWORK_STRING_LENGTH: usize = 32;
 pub const OPERATOR_NAME_STRING_LENGTH: usize = 32;
 pub const URL_PREFIX_STRING_LENGTH: usize = 32;
 pub const MASK_STRING_LENGTH: usize = 64;
 pub const NICKNAME_STRING_LENGTH: usize = 32;
 pub const ADDRESS_STRING_LENGTH: usize = 24;
+pub const MAX_REF_DEPTH: usize = 3;
+pub const MAX_FEE_TIERS: usize = 4;
+pub const PRICE_HISTORY_CANDLES: usize = 64;
+pub const DEFAULT_CANDLE_INTERVAL: u32 = 60;
+/// A client without a referrer can still set one for a day after its creation.
+pub const DEFAULT_REF_GRACE_PERIOD: u32 = 24 * 3600;
+pub const TWAP_OBSERVATIONS: usize = 8;
+pub const TWAP_OBSERVATION_PERIOD: u32 = 900;
+pub const MAX_BATCH_LEGS: usize = 5;
+pub const BATCH_SIDE_BUY: u32 = 0;
+pub const BATCH_SIDE_SELL: u32 = 1;
+// Extras flags of a trade, declaring the optional accounts that follow its accounts
+pub const TRADE_EXTRA_POSITION: u32 = 1;
+pub const TRADE_EXTRA_PRICE_HISTORY: u32 = 2;
+pub const TRADE_EXTRA_LAUNCH_ALLOWANCE: u32 = 4;
+pub const TRADE_EXTRA_LAUNCH_BUY: u32 = 8;
+pub const TRADE_EXTRAS_MASK: u32 = 15;
 pub const HOLDER_ACCOUNT_SIZE: usize = 56;
-pub const TOKEN_ACCOUNT_SIZE: usize = 216;
-pub const CLIENT_ACCOUNT_SIZE: usize = 176;
-pub const ROOT_ACCOUNT_SIZE: usize = 392;
-pub const NETWORK_RECORD_SIZE: usize = 136;
+pub const TOKEN_ACCOUNT_SIZE: usize = 392;
+pub const CLIENT_ACCOUNT_SIZE: usize = 264;
+pub const ROOT_ACCOUNT_SIZE: usize = 584;
+pub const REFERRER_ACCOUNT_SIZE: usize = 80;
+pub const REF_CODE_ACCOUNT_SIZE: usize = 88;
+pub const NICKNAME_ACCOUNT_SIZE: usize = 88;
+pub const POSITION_ACCOUNT_SIZE: usize = 128;
+pub const PRICE_HISTORY_ACCOUNT_SIZE: usize = 64 + CANDLE_SIZE * PRICE_HISTORY_CANDLES;
+pub const DELEGATE_ACCOUNT_SIZE: usize = 128;
+pub const LAUNCH_ALLOWANCE_ACCOUNT_SIZE: usize = 56;
+pub const POOL_ACCOUNT_SIZE: usize = 128;
+pub const LAUNCH_BUY_ACCOUNT_SIZE: usize = 96;
+pub const CANDLE_SIZE: usize = 56;
+pub const OBSERVATION_SIZE: usize = 16;
+pub const BATCH_LEG_SIZE: usize = 24;
+pub const TRADE_RESULT_SIZE: usize = 40;
+pub const NETWORK_RECORD_SIZE: usize = 144;
+pub const FEE_TIER_SIZE: usize = 16;
 pub const OPERATOR_RECORD_SIZE: usize = 72;
+// Sizes of the original layouts, which `migrate_account` grows to the current ones
+pub const LEGACY_TOKEN_ACCOUNT_SIZE: usize = 216;
+pub const LEGACY_CLIENT_ACCOUNT_SIZE: usize = 176;
+pub const LEGACY_ROOT_ACCOUNT_SIZE: usize = 392;
+pub const LEGACY_NETWORK_RECORD_SIZE: usize = 136;
@@ -0,0 +1,259 @@
+use crate::program::*;
+use crate::state::*;
+use solana_program::program_error::ProgramError;
+
+// Pure pricing and fee math shared by `mint` and `burn`.
+// Everything here works on plain values, so it can be exercised off-chain.
+
+#[derive(Clone, Copy, Debug)]
+pub struct CurveParams {
+    pub init_price: f64,
+    pub max_supply: u64,
+    pub fee_rate: f64,
+    pub min_fee: f64,
+    pub fee_ratio: f64,
+    pub base_crncy_decs_factor: u32,
+}
+
+impl CurveParams {
+    pub fn from_root(root: &RootAccount) -> Self {
+        CurveParams {
+            init_price: root.init_price,
+            max_supply: root.max_supply,
+            fee_rate: root.fee_rate,
+            min_fee: root.min_fee,
+            fee_ratio: root.fee_ratio,
+            base_crncy_decs_factor: root.base_crncy_decs_factor,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct TradeQuote {
+    pub base_crncy_amount: u64,
+    pub fees: u64,
+}
+
+impl TradeQuote {
+    /// Rejects a trade whose fee exceeds its size, as a dust trade paying the `min_fee` floor would.
+    pub fn check_fees(&self) -> Result<(), ProgramError> {
+        if self.fees > self.base_crncy_amount {
+            return Err(TooSmallQuantity.into());
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct FeeSplit {
+    pub holder_fees: u64,
+    pub operator_fees: u64,
+    pub ref_fees: u64,
+    pub upline_fees: [u64; MAX_REF_DEPTH - 1],
+    pub creator_fees: u64,
+}
+
+impl FeeSplit {
+    pub fn total(&self) -> u64 {
+        self.holder_fees
+            + self.operator_fees
+            + self.ref_fees
+            + self.upline_fees.iter().sum::<u64>()
+            + self.creator_fees
+    }
+
+    /// Takes `ratios[i]` of the referral part for upline level `i + 2` out of the operator fees.
+    pub fn with_upline_fees(mut self, ratios: &[f64]) -> FeeSplit {
+        let rest_of_fees = self.operator_fees + self.ref_fees;
+        for (upline_fees, ratio) in self.upline_fees.iter_mut().zip(ratios) {
+            *upline_fees = ((rest_of_fees as f64 * ratio) as u64).min(self.operator_fees);
+            self.operator_fees -= *upline_fees;
+        }
+        self
+    }
+
+    /// Takes `ratio` of the trading fees for the token creator out of the operator fees.
+    /// The creation fee is not part of `trade_fees`.
+    pub fn with_creator_fees(mut self, trade_fees: u64, ratio: f64) -> FeeSplit {
+        self.creator_fees = ((trade_fees as f64 * ratio) as u64).min(self.operator_fees);
+        self.operator_fees -= self.creator_fees;
+        self
+    }
+}
+
+/// Number of the highest tier `volume` reaches, counting from 1; 0 if it reaches none.
+/// `tiers` are ordered by `min_volume`.
+pub fn fee_tier(tiers: &[FeeTier], volume: u64) -> u32 {
+    tiers.iter().take_while(|t| volume >= t.min_volume).count() as u32
+}
+
+/// Curve reserve at `supply`, rejecting values a u64 can not hold.
+fn checked_reserve(params: &CurveParams, supply: u64) -> Result<f64, ProgramError> {
+    let reserve = get_reserve(params.init_price, params.max_supply, supply)?;
+    if !reserve.is_finite() || reserve < 0.0 || reserve >= u64::MAX as f64 {
+        return Err(TooBigQuantity.into());
+    }
+    Ok(reserve)
+}
+
+/// Curve reserve at `supply` in base currency units, rejecting values a u64 can not hold.
+pub fn reserve_units(params: &CurveParams, supply: u64) -> Result<u64, ProgramError> {
+    Ok(checked_reserve(params, supply)? as u64)
+}
+
+/// Marginal price of the next token at `supply`, the slope of the reserve curve,
+/// in base currency units per token unit.
+pub fn spot_price(params: &CurveParams, supply: u64) -> Result<f64, ProgramError> {
+    if params.max_supply <= supply {
+        return Err(InvalidSupply.into());
+    }
+    let ratio = params.max_supply as f64 / (params.max_supply - supply) as f64;
+    Ok(params.init_price * ratio * ratio)
+}
+
+/// Trading fee on a trade that moves the curve reserve by `cost`: `fee_rate` of it
+/// with a `min_fee` floor, scaled by `base_crncy_decs_factor`. A dust trade still pays
+/// `min_fee`, so the fee may exceed the trade; `TradeQuote::check_fees` rejects those.
+pub fn trade_fees(params: &CurveParams, cost: f64) -> u64 {
+    ((cost * params.fee_rate).max(params.min_fee) * params.base_crncy_decs_factor as f64) as u64
+}
+
+pub fn quote_mint(
+    params: &CurveParams,
+    supply: u64,
+    amount: u64,
+) -> Result<TradeQuote, ProgramError> {
+    if amount == 0 {
+        return Err(TooSmallQuantity.into());
+    }
+    let final_supply = supply.checked_add(amount).ok_or(TooBigQuantity)?;
+    let init_funds = checked_reserve(params, supply)?;
+    let final_funds = checked_reserve(params, final_supply)?;
+    Ok(TradeQuote {
+        base_crncy_amount: (final_funds as u64).saturating_sub(init_funds as u64),
+        fees: trade_fees(params, final_funds - init_funds),
+    })
+}
+
+pub fn quote_burn(
+    params: &CurveParams,
+    supply: u64,
+    amount: u64,
+) -> Result<TradeQuote, ProgramError> {
+    if amount == 0 {
+        return Err(TooSmallQuantity.into());
+    }
+    if amount > supply {
+        return Err(TooBigQuantity.into());
+    }
+    let init_funds = checked_reserve(params, supply)?;
+    let final_funds = checked_reserve(params, supply - amount)?;
+    Ok(TradeQuote {
+        base_crncy_amount: (init_funds as u64).saturating_sub(final_funds as u64),
+        fees: trade_fees(params, init_funds - final_funds),
+    })
+}
+
+/// Most tokens that `funds` buy at `supply`, fees aside, from the inverse of the
+/// reserve curve `s = r * max_supply / (max_supply * init_price + r)`.
+pub fn mint_amount_for_funds(
+    params: &CurveParams,
+    supply: u64,
+    funds: u64,
+) -> Result<u64, ProgramError> {
+    let init_funds = reserve_units(params, supply)?;
+    let final_funds = init_funds.checked_add(funds).ok_or(TooBigQuantity)? as f64;
+    let max_supply = params.max_supply as f64;
+    let final_supply = final_funds * max_supply / (max_supply * params.init_price + final_funds);
+    let mut amount = (final_supply as u64)
+        .saturating_sub(supply)
+        .min(params.max_supply - supply - 1);
+    // The float inverse may overshoot by a few ulps of the supply
+    let step = ((supply + amount) >> 50).max(1);
+    while amount > 0 && reserve_units(params, supply + amount)?.saturating_sub(init_funds) > funds {
+        amount = amount.saturating_sub(step);
+    }
+    Ok(amount)
+}
+
+/// Most tokens a buy at `supply` takes before the curve reserve passes
+/// `graduation_reserve`: all that keep the reserve within it and the one that reaches
+/// it, none once the reserve is there.
+pub fn graduation_cap(
+    params: &CurveParams,
+    supply: u64,
+    graduation_reserve: u64,
+) -> Result<u64, ProgramError> {
+    let reserve = reserve_units(params, supply)?;
+    if reserve >= graduation_reserve {
+        return Ok(0);
+    }
+    let amount = mint_amount_for_funds(params, supply, graduation_reserve - reserve)?;
+    if reserve_units(params, supply + amount)? >= graduation_reserve {
+        return Ok(amount);
+    }
+    Ok((amount + 1).min(params.max_supply - supply - 1))
+}
+
+/// Base currency and tokens a token at `supply` moves into its pool on graduation:
+/// its curve reserve and as many tokens as that buys at the spot price, so the pool
+/// opens at the price the curve left off.
+pub fn graduation_amounts(params: &CurveParams, supply: u64) -> Result<(u64, u64), ProgramError> {
+    let reserve = reserve_units(params, supply)?;
+    let tokens = reserve as f64 / spot_price(params, supply)?;
+    if tokens >= u64::MAX as f64 {
+        return Err(TooBigQuantity.into());
+    }
+    if tokens.is_nan() || tokens < 1.0 {
+        return Err(TooSmallQuantity.into());
+    }
+    Ok((reserve, tokens as u64))
+}
+
+/// Output of a constant-product swap of `amount_in` into a pool holding `reserve_in`
+/// and `reserve_out`, rounded down so that the product of the reserves never shrinks.
+pub fn swap_amount_out(
+    reserve_in: u64,
+    reserve_out: u64,
+    amount_in: u64,
+) -> Result<u64, ProgramError> {
+    if amount_in == 0 {
+        return Err(TooSmallQuantity.into());
+    }
+    let amount_out =
+        reserve_out as u128 * amount_in as u128 / (reserve_in as u128 + amount_in as u128);
+    Ok(amount_out as u64)
+}
+
+/// Pool fee on a swap moving `base_crncy_amount`, never more than the swap itself.
+pub fn pool_fees(fee_rate: f64, base_crncy_amount: u64) -> u64 {
+    ((base_crncy_amount as f64 * fee_rate) as u64).min(base_crncy_amount)
+}
+
+/// Splits `total_fees` between the holder, the operator and an active referrer
+/// (`ref_discount`, `ref_ratio` of the client). The discount is what the client saves.
+pub fn split_fees(total_fees: u64, fee_ratio: f64, referral: Option<(f64, f64)>) -> FeeSplit {
+    let holder_fees = ((total_fees as f64 * (1.0 - fee_ratio)) as u64).min(total_fees);
+    match referral {
+        Some((ref_discount, ref_ratio)) => {
+            let discounted_fees =
+                ((total_fees as f64 * (1.0 - ref_discount)) as u64).min(total_fees);
+            let rest_of_fees = discounted_fees.saturating_sub(holder_fees);
+            let ref_fees = ((rest_of_fees as f64 * ref_ratio) as u64).min(rest_of_fees);
+            FeeSplit {
+                holder_fees: holder_fees,
+                operator_fees: rest_of_fees - ref_fees,
+                ref_fees: ref_fees,
+                upline_fees: [0; MAX_REF_DEPTH - 1],
+                creator_fees: 0,
+            }
+        }
+        None => FeeSplit {
+            holder_fees: holder_fees,
+            operator_fees: total_fees - holder_fees,
+            ref_fees: 0,
+            upline_fees: [0; MAX_REF_DEPTH - 1],
+            creator_fees: 0,
+        },
+    }
+}
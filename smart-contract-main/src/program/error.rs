@@ -93,6 +93,104 @@ pub enum HypeError {
     InvalidFeeWallet = 6043,
     #[error("Invalid Supply")]
     InvalidSupply = 6044,
+    #[error("Vault Balance Mismatch")]
+    VaultBalanceMismatch = 6045,
+    #[error("Mint Supply Mismatch")]
+    MintSupplyMismatch = 6046,
+    #[error("Total Supply Mismatch")]
+    TotalSupplyMismatch = 6047,
+    #[error("Reserve Mismatch")]
+    ReserveMismatch = 6048,
+    #[error("Invalid Account Size")]
+    InvalidAccountSize = 6049,
+    #[error("Invalid Referrer Account")]
+    InvalidReferrerAccount = 6050,
+    #[error("Invalid Ref Depth")]
+    InvalidRefDepth = 6051,
+    #[error("Invalid Ref Code")]
+    InvalidRefCode = 6052,
+    #[error("Ref Code Already Registered")]
+    RefCodeAlreadyRegistered = 6053,
+    #[error("Referral Already Active")]
+    ReferralAlreadyActive = 6054,
+    #[error("Ref Grace Period Expired")]
+    RefGracePeriodExpired = 6055,
+    #[error("Self Referral")]
+    SelfReferral = 6056,
+    #[error("Referral Cycle")]
+    ReferralCycle = 6057,
+    #[error("Invalid Fee Tiers")]
+    InvalidFeeTiers = 6058,
+    #[error("Invalid Creator Fee Ratio")]
+    InvalidCreatorFeeRatio = 6059,
+    #[error("Invalid Token Creator")]
+    InvalidTokenCreator = 6060,
+    #[error("Creator Fees Mismatch")]
+    CreatorFeesMismatch = 6061,
+    #[error("Invalid Nickname")]
+    InvalidNickname = 6062,
+    #[error("Nickname Already Taken")]
+    NicknameAlreadyTaken = 6063,
+    #[error("Unclaimed Fees")]
+    UnclaimedFees = 6064,
+    #[error("Token Not Idle")]
+    TokenNotIdle = 6065,
+    #[error("Invalid Position Account")]
+    InvalidPositionAccount = 6066,
+    #[error("Invalid Price History Account")]
+    InvalidPriceHistoryAccount = 6067,
+    #[error("Invalid Candle Interval")]
+    InvalidCandleInterval = 6068,
+    #[error("Twap Unavailable")]
+    TwapUnavailable = 6069,
+    #[error("Trade Expired")]
+    TradeExpired = 6070,
+    #[error("Invalid Batch")]
+    InvalidBatch = 6071,
+    #[error("Invalid Switch Fee Rate")]
+    InvalidSwitchFeeRate = 6072,
+    #[error("Invalid Delegate Account")]
+    InvalidDelegateAccount = 6073,
+    #[error("Delegate Expired")]
+    DelegateExpired = 6074,
+    #[error("Delegate Spend Limit Exceeded")]
+    DelegateSpendLimitExceeded = 6075,
+    #[error("Network Not Allowed For Delegate")]
+    DelegateNetworkNotAllowed = 6076,
+    #[error("Launch Window Restricted")]
+    LaunchRestricted = 6077,
+    #[error("Launch Max Buy Exceeded")]
+    LaunchMaxBuyExceeded = 6078,
+    #[error("Buy Cooldown Active")]
+    BuyCooldownActive = 6079,
+    #[error("Invalid Launch Allowance Account")]
+    InvalidLaunchAllowance = 6080,
+    #[error("Token Graduated")]
+    TokenGraduated = 6081,
+    #[error("Graduation Reserve Not Reached")]
+    GraduationReserveNotReached = 6082,
+    #[error("Invalid Pool Account")]
+    InvalidPoolAccount = 6083,
+    #[error("Invalid Pool Fee Rate")]
+    InvalidPoolFeeRate = 6084,
+    #[error("Account Already Migrated")]
+    AccountAlreadyMigrated = 6085,
+    #[error("Invalid Trade Extras")]
+    InvalidTradeExtras = 6086,
+    #[error("Price History Required")]
+    PriceHistoryRequired = 6087,
+    #[error("Delegate Not Approved")]
+    DelegateNotApproved = 6088,
+    #[error("Launch Buy Account Required")]
+    LaunchBuyAccountRequired = 6089,
+    #[error("Invalid Launch Buy Account")]
+    InvalidLaunchBuyAccount = 6090,
+    #[error("Position Account Required")]
+    PositionRequired = 6091,
+    #[error("Graduation Reserve Exceeded")]
+    GraduationReserveExceeded = 6092,
+    #[error("Account Not Migrated")]
+    AccountNotMigrated = 6093,
 }
 
 impl From<HypeError> for ProgramError {
@@ -1,6 +1,7 @@
 use crate::program::*;
 use solana_program::{
     account_info::AccountInfo,
+    entrypoint::ProgramResult,
     //msg,
     program_error::ProgramError,
     pubkey::Pubkey,
@@ -34,6 +35,41 @@ pub unsafe fn check_account(
     Ok(bump_seed)
 }
 
+pub fn check_account_size(account: &AccountInfo, size: usize) -> ProgramResult {
+    if account.data_len() < size {
+        return Err(InvalidAccountSize.into());
+    }
+    Ok(())
+}
+
+/// Lowercases a nul-padded name, which has to be 1 to 32 of `a-z`, `0-9`, `_` and `-`.
+fn fold_name(name: &[u8]) -> Option<[u8; NICKNAME_STRING_LENGTH]> {
+    if name.len() != NICKNAME_STRING_LENGTH {
+        return None;
+    }
+    let end = name.iter().position(|c| *c == 0).unwrap_or(name.len());
+    if end == 0 || name[end..].iter().any(|c| *c != 0) {
+        return None;
+    }
+    let mut res = [0; NICKNAME_STRING_LENGTH];
+    for (dst, c) in res.iter_mut().zip(&name[..end]) {
+        if !c.is_ascii_alphanumeric() && *c != b'_' && *c != b'-' {
+            return None;
+        }
+        *dst = c.to_ascii_lowercase();
+    }
+    Some(res)
+}
+
+pub fn normalize_ref_code(code: &[u8]) -> Result<[u8; NICKNAME_STRING_LENGTH], ProgramError> {
+    fold_name(code).ok_or_else(|| InvalidRefCode.into())
+}
+
+/// Registry key of a client nickname; the client account keeps the nickname as typed.
+pub fn normalize_nickname(nickname: &[u8]) -> Result<[u8; NICKNAME_STRING_LENGTH], ProgramError> {
+    fold_name(nickname).ok_or_else(|| InvalidNickname.into())
+}
+
 pub unsafe fn get_seed_by_tag(version: u32, tag: u32) -> [u8; 8] {
     let mut res = [0; 8];
     res[0..4].copy_from_slice(&version.to_le_bytes());
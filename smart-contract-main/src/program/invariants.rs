@@ -0,0 +1,155 @@
+use crate::program::*;
+use crate::state::*;
+use solana_program::{entrypoint::ProgramResult, msg, program_error::ProgramError, pubkey::Pubkey};
+
+pub const TOKEN_ACCOUNT_AMOUNT_OFFSET: usize = 64;
+pub const TOKEN_ACCOUNT_DELEGATE_OFFSET: usize = 72;
+pub const MINT_SUPPLY_OFFSET: usize = 36;
+
+pub fn read_token_amount(data: &[u8]) -> Result<u64, ProgramError> {
+    if data.len() < TOKEN_ACCOUNT_AMOUNT_OFFSET + 8 {
+        return Err(InvalidDataLength.into());
+    }
+    let mut amount = [0; 8];
+    amount.copy_from_slice(&data[TOKEN_ACCOUNT_AMOUNT_OFFSET..TOKEN_ACCOUNT_AMOUNT_OFFSET + 8]);
+    Ok(u64::from_le_bytes(amount))
+}
+
+/// SPL delegate of a token account, `None` if it has none.
+pub fn read_token_delegate(data: &[u8]) -> Result<Option<Pubkey>, ProgramError> {
+    if data.len() < TOKEN_ACCOUNT_DELEGATE_OFFSET + 36 {
+        return Err(InvalidDataLength.into());
+    }
+    let tag = &data[TOKEN_ACCOUNT_DELEGATE_OFFSET..TOKEN_ACCOUNT_DELEGATE_OFFSET + 4];
+    if tag != [1, 0, 0, 0] {
+        return Ok(None);
+    }
+    let mut key = [0; 32];
+    key.copy_from_slice(
+        &data[TOKEN_ACCOUNT_DELEGATE_OFFSET + 4..TOKEN_ACCOUNT_DELEGATE_OFFSET + 36],
+    );
+    Ok(Some(Pubkey::new_from_array(key)))
+}
+
+pub fn read_mint_supply(data: &[u8]) -> Result<u64, ProgramError> {
+    if data.len() < MINT_SUPPLY_OFFSET + 8 {
+        return Err(InvalidDataLength.into());
+    }
+    let mut supply = [0; 8];
+    supply.copy_from_slice(&data[MINT_SUPPLY_OFFSET..MINT_SUPPLY_OFFSET + 8]);
+    Ok(u64::from_le_bytes(supply))
+}
+
+/// Recomputes the protocol accounting relations of one root.
+/// Tokens have to be added in increasing id order; the totals are only
+/// compared exactly once every token of the root has been added.
+pub struct InvariantChecker<'a> {
+    pub root: &'a RootAccount,
+    pub tokens_checked: u64,
+    pub supply: u64,
+    pub reserve: u64,
+    pub creator_fees: u64,
+    pub last_id: Option<u64>,
+}
+
+impl<'a> InvariantChecker<'a> {
+    pub fn new(root: &'a RootAccount) -> Self {
+        InvariantChecker {
+            root: root,
+            tokens_checked: 0,
+            supply: 0,
+            reserve: 0,
+            creator_fees: 0,
+            last_id: None,
+        }
+    }
+
+    /// The vault at `base_crncy_program_address` backs the TVL, the pools and all unpaid
+    /// fees, including referral and creator fees that have not been claimed yet.
+    pub fn check_vault(&self, vault_amount: u64) -> ProgramResult {
+        let expected = self
+            .root
+            .tvl
+            .checked_add(self.root.fees)
+            .and_then(|v| v.checked_add(self.root.holder_fees))
+            .and_then(|v| v.checked_add(self.root.ref_fees))
+            .and_then(|v| v.checked_add(self.root.creator_fees))
+            .and_then(|v| v.checked_add(self.root.pool_reserves))
+            .ok_or(ArithmeticOverflow)?;
+        if vault_amount != expected {
+            msg!("Vault holds {}, expected {}", vault_amount, expected);
+            return Err(VaultBalanceMismatch.into());
+        }
+        Ok(())
+    }
+
+    pub fn add_token(&mut self, token: &TokenAccount, mint_supply: u64) -> ProgramResult {
+        if token.tag != TOKEN_TAG || token.version != self.root.version {
+            return Err(InvalidTokenAccount.into());
+        }
+        if token.id >= self.root.tokens_count || self.last_id.is_some_and(|id| token.id <= id) {
+            return Err(InvalidTokenAccount.into());
+        }
+        if mint_supply != token.supply {
+            msg!(
+                "Token {} supply {}, mint supply {}",
+                token.id,
+                token.supply,
+                mint_supply
+            );
+            return Err(MintSupplyMismatch.into());
+        }
+        // The reserve of a graduated token moved into its pool
+        let reserve = if token.graduated != 0 {
+            0
+        } else {
+            reserve_units(&CurveParams::from_root(self.root), token.supply)?
+        };
+        self.supply = self
+            .supply
+            .checked_add(token.supply)
+            .ok_or(ArithmeticOverflow)?;
+        self.reserve = self
+            .reserve
+            .checked_add(reserve)
+            .ok_or(ArithmeticOverflow)?;
+        self.creator_fees = self
+            .creator_fees
+            .checked_add(token.creator_fees)
+            .ok_or(ArithmeticOverflow)?;
+        self.tokens_checked += 1;
+        self.last_id = Some(token.id);
+        Ok(())
+    }
+
+    /// Compares the summed token supplies, curve reserves and unclaimed creator fees
+    /// with the root counters. Retired tokens count as checked, they hold nothing.
+    /// With a subset of the tokens the sums can only be checked as lower bounds.
+    pub fn finish(&self) -> ProgramResult {
+        let complete =
+            self.tokens_checked + self.root.retired_tokens_count == self.root.tokens_count;
+        if (complete && self.supply != self.root.supply) || self.supply > self.root.supply {
+            msg!(
+                "Tokens supply {}, root supply {}",
+                self.supply,
+                self.root.supply
+            );
+            return Err(TotalSupplyMismatch.into());
+        }
+        if (complete && self.reserve != self.root.tvl) || self.reserve > self.root.tvl {
+            msg!("Curve reserve {}, root tvl {}", self.reserve, self.root.tvl);
+            return Err(ReserveMismatch.into());
+        }
+        if (complete && self.creator_fees != self.root.creator_fees)
+            || self.creator_fees > self.root.creator_fees
+        {
+            msg!(
+                "Tokens creator fees {}, root creator fees {}",
+                self.creator_fees,
+                self.root.creator_fees
+            );
+            return Err(CreatorFeesMismatch.into());
+        }
+        Ok(())
+    }
+}
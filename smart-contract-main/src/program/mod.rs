@@ -1,7 +1,11 @@
 pub mod constants;
+pub mod curve;
 pub mod error;
 pub mod helper;
+pub mod invariants;
 pub(crate) mod processor;
 pub use constants::*;
-pub use helper::*;
+pub use curve::*;
 pub use error::HypeError::*;
+pub use helper::*;
+pub use invariants::*;
@@ -5,18 +5,36 @@ use solana_program::{
     clock::Clock,
     entrypoint::ProgramResult,
     program::invoke,
-    program_error::ProgramError,
     pubkey::Pubkey,
     rent::Rent,
     system_instruction,
     sysvar::Sysvar,
 };
+use std::convert::TryInto;
 
 pub unsafe fn add_network(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
     instruction_data: &[u8],
 ) -> ProgramResult {
+    // [0..8] - 3
+    // [8..40] - Descriptor
+    // [40..104] - Mask
+    // [104..112] - Max length
+    // [112..120] - Creator fee cap (Optional, no cap if omitted)
+    if instruction_data.len() < 8 + NETWORK_STRING_LENGTH + MASK_STRING_LENGTH + 8 {
+        return Err(InvalidDataLength.into());
+    }
+    let mut creator_fee_cap: f64 = 1.0;
+    if instruction_data.len() > 112 {
+        if instruction_data.len() < 120 {
+            return Err(InvalidDataLength.into());
+        }
+        creator_fee_cap = f64::from_le_bytes(instruction_data[112..120].try_into().unwrap());
+    }
+    if !(0.0..=1.0).contains(&creator_fee_cap) {
+        return Err(InvalidCreatorFeeRatio.into());
+    }
     let account_info_iter = &mut accounts.iter();
     let admin = next_account_info(account_info_iter)?;
     let root_acc = next_account_info(account_info_iter)?;
@@ -25,13 +43,7 @@ pub unsafe fn add_network(
     if !admin.is_signer {
         return Err(AdminSignatureRequired.into());
     }
-    if root_acc.owner != program_id {
-        return Err(ProgramError::IncorrectProgramId);
-    }
-    let root = root_acc.data.borrow().as_ptr() as *mut RootAccount;
-    if (*root).tag != ROOT_TAG {
-        return Err(InvalidAccountTag.into());
-    }
+    let root = load_root_account(program_id, root_acc)?;
     if (*root).admin != *admin.key {
         return Err(InvalidAdmin.into());
     }
@@ -73,6 +85,7 @@ pub unsafe fn add_network(
         max_length: *(instruction_data[8 + NETWORK_STRING_LENGTH + MASK_STRING_LENGTH..].as_ptr()
             as *const usize),
         validator: *validator_acc.key,
+        creator_fee_cap: creator_fee_cap,
     };
     (*root).slot = clock.slot;
     (*root).time = clock.unix_timestamp as u32;
@@ -12,6 +12,7 @@ use solana_program::{
 
 use crate::program::*;
 use crate::state::*;
+use std::convert::TryInto;
 
 pub unsafe fn add_operator(
     program_id: &Pubkey,
@@ -41,7 +42,7 @@ pub unsafe fn add_operator(
     let system_program = next_account_info(accounts_iter)?;
     check_holder_admin(admin)?;
     check_holder_account(holder_acc, program_id, true)?;
-    let version = *((_instruction_data[1..]).as_ptr() as *const u32);
+    let version = u32::from_le_bytes(_instruction_data[1..5].try_into().unwrap());
     let holder = holder_acc.data.borrow()[..].as_ptr() as *mut HolderAccount;
     let new_size =
         HOLDER_ACCOUNT_SIZE + (((*holder).operators_count + 1) as usize) * OPERATOR_RECORD_SIZE;
@@ -56,12 +57,10 @@ pub unsafe fn add_operator(
         )?;
         holder_acc.realloc(new_size, true)?;
     }
-    let operators: Vec<OperatorRecord>;
     let begin = HOLDER_ACCOUNT_SIZE + ((*holder).operators_count as usize) * OPERATOR_RECORD_SIZE;
     let operators_ptr = (&holder_acc.data.borrow()[HOLDER_ACCOUNT_SIZE..begin]).as_ptr();
-    operators = Vec::from_raw_parts(
-        operators_ptr as *mut OperatorRecord,
-        (*holder).operators_count as usize,
+    let operators = std::slice::from_raw_parts(
+        operators_ptr as *const OperatorRecord,
         (*holder).operators_count as usize,
     );
     for p in operators {
@@ -71,7 +70,7 @@ pub unsafe fn add_operator(
     }
     *((&holder_acc.data.borrow()[begin..]).as_ptr() as *mut OperatorRecord) = OperatorRecord {
         version: version,
-        max_networks_count: *((_instruction_data[5..]).as_ptr() as *const u32),
+        max_networks_count: u32::from_le_bytes(_instruction_data[5..9].try_into().unwrap()),
         operator_address: *operator.key,
         operator_name: *((_instruction_data[9..9 + OPERATOR_NAME_STRING_LENGTH]).as_ptr()
             as *const [u8; OPERATOR_NAME_STRING_LENGTH]),
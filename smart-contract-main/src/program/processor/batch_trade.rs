@@ -0,0 +1,89 @@
+use crate::program::processor::{burn_tokens, mint_tokens};
+use crate::program::*;
+use crate::state::*;
+use solana_program::{account_info::AccountInfo, entrypoint::ProgramResult, pubkey::Pubkey};
+use std::convert::TryInto;
+
+pub unsafe fn batch_trade(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    // Batch Trade Instruction
+    // #1..#17 - Trade accounts of `mint`, holding the token, hype mint, hype program account
+    //           and client associated hype account of the first leg
+    // Delegate and referral accounts as in `mint`, then for each further leg:
+    // #1 - Token Account (Writable)
+    // #2 - Hype Mint (Writable)
+    // #3 - Hype Program Account (Writable)
+    // #4 - Client Associated Hype Account (Writable)
+    // Each leg's accounts are followed by the accounts declared in its extras flags.
+    // [0..8] - 21
+    // [8..12] - Legs count (1 to 5)
+    // [12..16] - Reserved
+    // [16..] - Legs: side (u32, 0 buy, 1 sell), extras flags (u32, see `next_trade_extras`), amount (u64) and
+    //          max cost for a buy or min cash out for a sell (u64, 0 for no limit)
+    // [..+8] - Valid until slot (Optional, 0 for no limit)
+    // [..+12] - Valid until unix time (Optional, 0 for no limit)
+    // Every leg trades an existing token and the whole batch fails with any of them.
+    if instruction_data.len() < 16 {
+        return Err(InvalidDataLength.into());
+    }
+    let count = u32::from_le_bytes(instruction_data[8..12].try_into().unwrap()) as usize;
+    if count == 0 || count > MAX_BATCH_LEGS {
+        return Err(InvalidBatch.into());
+    }
+    let legs_end = 16 + count * BATCH_LEG_SIZE;
+    if instruction_data.len() < legs_end {
+        return Err(InvalidDataLength.into());
+    }
+    let accounts_iter = &mut accounts.iter();
+    // Clients created by a batch start without a nickname
+    let ctx = Context::new(program_id, accounts_iter, instruction_data, legs_end)?;
+    ctx.check_valid_until(instruction_data, legs_end)?;
+    let mut base_ata_checked = false;
+    for i in 0..count {
+        let offset = 16 + i * BATCH_LEG_SIZE;
+        let side = u32::from_le_bytes(instruction_data[offset..offset + 4].try_into().unwrap());
+        let flags = read_trade_extras(instruction_data, offset + 4)?;
+        let amount = u64::from_le_bytes(
+            instruction_data[offset + 8..offset + 16]
+                .try_into()
+                .unwrap(),
+        );
+        let limit = u64::from_le_bytes(
+            instruction_data[offset + 16..offset + 24]
+                .try_into()
+                .unwrap(),
+        );
+        let leg = if i == 0 {
+            ctx
+        } else {
+            ctx.next_token(accounts_iter)?
+        };
+        let extras = next_trade_extras(accounts_iter, flags)?;
+        let token_account = leg.load_token_account(program_id)?;
+        let records = leg.load_trade_records(program_id, &*token_account, &extras)?;
+        match side {
+            BATCH_SIDE_BUY => {
+                let leg = leg.with_launch_extras(program_id, &extras)?;
+                leg.create_client_hype_account()?;
+                let fill = mint_tokens(&leg, token_account, amount, limit, 0)?;
+                records.record_buy(&fill, leg.slot, leg.time)?;
+            }
+            BATCH_SIDE_SELL => {
+                if extras.launch_allowance.is_some() || extras.launch_buy.is_some() {
+                    return Err(InvalidTradeExtras.into());
+                }
+                if !base_ata_checked {
+                    leg.create_client_token_account()?;
+                }
+                base_ata_checked = true;
+                let fill = burn_tokens(&leg, token_account, amount, limit)?;
+                records.record_sell(&fill, leg.slot, leg.time)?;
+            }
+            _ => return Err(InvalidBatch.into()),
+        }
+    }
+    Ok(())
+}
@@ -4,12 +4,10 @@ use solana_program::{
     account_info::AccountInfo,
     entrypoint::ProgramResult,
     //msg,
-    program::invoke,
     program::invoke_signed,
+    program_error::ProgramError,
     pubkey::Pubkey,
-    system_program,
 };
-use spl_associated_token_account::{instruction::create_associated_token_account, *};
 pub unsafe fn burn(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
@@ -18,207 +16,75 @@ pub unsafe fn burn(
     // [0..8] - 3
     // [8..16] - Amount
     // [16..24] - Min cash out
-    let ctx = Context::new(program_id, &mut accounts.iter(), instruction_data, 24)?;
-    let token_account = ctx.token_acc.data.borrow().as_ptr() as *mut TokenAccount;
-    if (*token_account).mint != *ctx.hype_mint_acc.key {
-        return Err(InvalidTokenAccount.into());
+    // [24..56] - Nickname
+    // [56..64] - Valid until slot (Optional, 0 for no limit)
+    // [64..68] - Valid until unix time (Optional, 0 for no limit)
+    // [68..72] - Extras flags (Optional, see `next_trade_extras`)
+    if instruction_data.len() < 24 {
+        return Err(InvalidDataLength.into());
     }
+    let accounts_iter = &mut accounts.iter();
+    let ctx = Context::new(program_id, accounts_iter, instruction_data, 24)?;
+    ctx.check_valid_until(instruction_data, 56)?;
     if ctx.token_acc.owner != program_id {
         return Err(InvalidTokenAccount.into());
     }
-    if *ctx.client_associated_token_acc.owner == system_program::ID {
-        invoke(
-            &create_associated_token_account(
-                ctx.signer.key,
-                ctx.signer.key,
-                ctx.base_crncy_mint_acc.key,
-                ctx.token_program_id.key,
-            ),
-            &[
-                ctx.signer.clone(),
-                ctx.client_associated_token_acc.clone(),
-                ctx.base_crncy_mint_acc.clone(),
-                ctx.token_program_id.clone(),
-            ],
-        )?;
-    }
-    let init_supply = *(ctx.hype_mint_acc.data.borrow()[36..].as_ptr() as *const u64);
-    if init_supply != (*token_account).supply {
-        return Err(InvalidTokenSupply.into());
+    check_account_size(ctx.token_acc, TOKEN_ACCOUNT_SIZE)?;
+    let token_account = ctx.token_acc.data.borrow().as_ptr() as *mut TokenAccount;
+    if (*token_account).mint != *ctx.hype_mint_acc.key {
+        return Err(InvalidTokenAccount.into());
     }
-    let init_funds = get_reserve((*ctx.root).init_price, (*ctx.root).max_supply, init_supply)?;
+    ctx.create_client_token_account()?;
     let amount = *((instruction_data[8..]).as_ptr() as *const u64);
-    if amount == 0 {
-        return Err(TooSmallQuantity.into());
-    }
-    if amount > init_supply {
-        return Err(TooBigQuantity.into());
-    }
     let min_cashout = *((instruction_data[16..]).as_ptr() as *const u64);
-    let final_funds = get_reserve(
-        (*ctx.root).init_price,
-        (*ctx.root).max_supply,
-        init_supply - amount,
-    )?;
-    let cashout = init_funds - final_funds;
-    let base_crncy_cashout = init_funds as u64 - final_funds as u64;
-    if min_cashout > 0 && base_crncy_cashout < min_cashout {
-        return Err(MaxTradeCostExceeded.into());
+    let extras = next_trade_extras(accounts_iter, read_trade_extras(instruction_data, 68)?)?;
+    // Launch allowances only lift limits on buys
+    if extras.launch_allowance.is_some() || extras.launch_buy.is_some() {
+        return Err(InvalidTradeExtras.into());
     }
-    let fees = ((cashout * (*ctx.root).fee_rate).max((*ctx.root).min_fee)
-        * (*ctx.root).base_crncy_decs_factor as f64) as u64;
+    let records = ctx.load_trade_records(program_id, &*token_account, &extras)?;
+    let fill = burn_tokens(&ctx, token_account, amount, min_cashout)?;
+    records.record_sell(&fill, ctx.slot, ctx.time)?;
+    Ok(())
+}
 
-    let holder_fees = (fees as f64 * (1.0 - (*ctx.root).fee_ratio)) as u64;
-    let ref_fees: u64;
-    let operator_fees: u64;
-    if (*ctx.client).ref_stop > ctx.time {
-        if (*ctx.client).ref_address != *ctx.ref_acc.key {
-            return Err(InvalidRefAddress.into());
-        }
-        let discounted_fees = (fees as f64 * (1.0 - (*ctx.client).ref_discount)) as u64;
-        let rest_of_fees: u64;
-        if discounted_fees > holder_fees {
-            rest_of_fees = discounted_fees - holder_fees;
-        } else {
-            rest_of_fees = 0;
-        }
-        if rest_of_fees > 0 {
-            ref_fees = (rest_of_fees as f64 * (*ctx.client).ref_ratio) as u64;
-            operator_fees = rest_of_fees - ref_fees;
-        } else {
-            ref_fees = 0;
-            operator_fees = 0;
-        }
-    } else {
-        ref_fees = 0;
-        operator_fees = fees - holder_fees;
+/// Sells `amount` of the token of `ctx` from the client's associated account back to
+/// the curve and pays the cash out less fees. The client's base currency account has to exist.
+pub unsafe fn burn_tokens(
+    ctx: &Context,
+    token_account: *mut TokenAccount,
+    amount: u64,
+    min_cashout: u64,
+) -> Result<Fill, ProgramError> {
+    let init_supply = read_mint_supply(&ctx.hype_mint_acc.data.borrow())?;
+    if init_supply != (*token_account).supply {
+        return Err(InvalidTokenSupply.into());
     }
-    if ref_fees > 0 {
-        (*ctx.client).ref_paid += ref_fees;
-        if *ctx.ref_associated_token_acc.owner == system_program::ID {
-            invoke(
-                &create_associated_token_account(
-                    ctx.signer.key,
-                    ctx.ref_acc.key,
-                    ctx.base_crncy_mint_acc.key,
-                    ctx.token_program_id.key,
-                ),
-                &[
-                    ctx.signer.clone(),
-                    ctx.ref_associated_token_acc.clone(),
-                    ctx.ref_acc.clone(),
-                    ctx.base_crncy_mint_acc.clone(),
-                    ctx.token_program_id.clone(),
-                ],
-            )?;
-        }
+    ctx.check_curve_open(&*token_account)?;
+    let fee_tier = ctx.fee_tier();
+    let curve_params = ctx.curve_params(fee_tier);
+    let quote = quote_burn(&curve_params, init_supply, amount)?;
+    quote.check_fees()?;
+    let base_crncy_cashout = quote.base_crncy_amount;
+    if min_cashout > 0 && base_crncy_cashout < min_cashout {
+        return Err(MaxTradeCostExceeded.into());
     }
-    let final_payment = base_crncy_cashout - fees;
-    if *ctx.base_crncy_program_acc.owner == spl_token_2022::id() {
-        let transfer_to_taker_ix = spl_token_2022::instruction::transfer_checked(
-            &spl_token_2022::id(),
-            ctx.base_crncy_program_acc.key,
-            ctx.base_crncy_mint_acc.key,
-            ctx.client_associated_token_acc.key,
-            &ctx.hype_auth,
-            &[&ctx.hype_auth],
-            final_payment,
-            (*ctx.root).decimals as u8,
-        )?;
-        invoke_signed(
-            &transfer_to_taker_ix,
-            &[
-                ctx.token_2022_program_id.clone(),
-                ctx.base_crncy_program_acc.clone(),
-                ctx.base_crncy_mint_acc.clone(),
-                ctx.client_associated_token_acc.clone(),
-                ctx.hype_auth_acc.clone(),
-            ],
-            &[&[&HYPE_SEED[..], &[ctx.hype_bump_seed]]],
-        )?;
-        if ref_fees > 0 {
-            let transfer_to_taker_ix = spl_token_2022::instruction::transfer_checked(
-                &spl_token_2022::id(),
-                ctx.base_crncy_program_acc.key,
-                ctx.base_crncy_mint_acc.key,
-                ctx.ref_associated_token_acc.key,
-                &ctx.hype_auth,
-                &[&ctx.hype_auth],
-                ref_fees,
-                (*ctx.root).decimals as u8,
-            )?;
-            invoke_signed(
-                &transfer_to_taker_ix,
-                &[
-                    ctx.token_2022_program_id.clone(),
-                    ctx.base_crncy_program_acc.clone(),
-                    ctx.base_crncy_mint_acc.clone(),
-                    ctx.ref_associated_token_acc.clone(),
-                    ctx.hype_auth_acc.clone(),
-                ],
-                &[&[&HYPE_SEED[..], &[ctx.hype_bump_seed]]],
-            )?;
-        }
-    } else {
-        let transfer_to_taker_ix = spl_token::instruction::transfer(
-            &spl_token::id(),
-            ctx.base_crncy_program_acc.key,
-            ctx.client_associated_token_acc.key,
-            &ctx.hype_auth,
-            &[&ctx.hype_auth],
-            final_payment,
-        )?;
-        invoke_signed(
-            &transfer_to_taker_ix,
-            &[
-                ctx.base_crncy_program_acc.clone(),
-                ctx.client_associated_token_acc.clone(),
-                ctx.hype_auth_acc.clone(),
-                ctx.token_program_id.clone(),
-            ],
-            &[&[&HYPE_SEED[..], &[ctx.hype_bump_seed]]],
-        )?;
-        if ref_fees > 0 {
-            let transfer_to_taker_ix = spl_token::instruction::transfer(
-                &spl_token::id(),
-                ctx.base_crncy_program_acc.key,
-                ctx.ref_associated_token_acc.key,
-                &ctx.hype_auth,
-                &[&ctx.hype_auth],
-                ref_fees,
-            )?;
-            invoke_signed(
-                &transfer_to_taker_ix,
-                &[
-                    ctx.base_crncy_program_acc.clone(),
-                    ctx.ref_associated_token_acc.clone(),
-                    ctx.hype_auth_acc.clone(),
-                    ctx.token_program_id.clone(),
-                ],
-                &[&[&HYPE_SEED[..], &[ctx.hype_bump_seed]]],
-            )?;
-        }
+    let fee_split = ctx
+        .fee_split(quote.fees)
+        .with_creator_fees(quote.fees, ctx.creator_fee_ratio(&*token_account)?);
+    let holder_fees = fee_split.holder_fees;
+    let operator_fees = fee_split.operator_fees;
+    // A referred seller pays only the discounted fees, like a referred buyer
+    let final_payment = base_crncy_cashout
+        .checked_sub(fee_split.total())
+        .ok_or(TooSmallQuantity)?;
+    ctx.accrue_ref_fees(&fee_split)?;
+    ctx.accrue_creator_fees(&mut *token_account, &fee_split)?;
+    if !ctx.delegate.is_null() {
+        (*ctx.delegate).add_trade((*token_account).network, 0, ctx.slot, ctx.time)?;
     }
-    let transfer_to_taker_ix = spl_token_2022::instruction::transfer_checked(
-        &spl_token_2022::id(),
-        ctx.client_associated_hype_acc.key,
-        ctx.hype_mint_acc.key,
-        ctx.hype_program_acc.key,
-        &ctx.signer.key,
-        &[&ctx.signer.key],
-        amount,
-        (*ctx.root).decimals as u8,
-    )?;
-    invoke(
-        &transfer_to_taker_ix,
-        &[
-            ctx.token_2022_program_id.clone(),
-            ctx.client_associated_hype_acc.clone(),
-            ctx.hype_mint_acc.clone(),
-            ctx.hype_program_acc.clone(),
-            ctx.signer.clone(),
-        ],
-    )?;
+    ctx.withdraw_base_crncy(final_payment)?;
+    ctx.deposit_hype(amount)?;
     invoke_signed(
         &spl_token_2022::instruction::burn(
             &spl_token_2022::ID,
@@ -261,6 +127,8 @@ pub unsafe fn burn(
     (*token_account).supply -= amount;
     (*token_account).slot = ctx.slot;
     (*token_account).time = ctx.time;
+    let price_after = spot_price(&curve_params, init_supply - amount)?;
+    (*token_account).update_twap(price_after, ctx.time);
     log_burn(
         (*ctx.client).id,
         (*ctx.root).counter,
@@ -279,6 +147,13 @@ pub unsafe fn burn(
         base_crncy_cashout,
         ctx.time,
         ctx.slot,
+        fee_tier,
     );
-    Ok(())
+    Ok(Fill {
+        amount: amount,
+        base_crncy_amount: base_crncy_cashout,
+        payment: final_payment,
+        price_before: spot_price(&curve_params, init_supply)?,
+        price_after: price_after,
+    })
 }
@@ -4,7 +4,9 @@ use solana_program::{
     account_info::{next_account_info, AccountInfo},
     clock::Clock,
     entrypoint::ProgramResult,
+    program_error::ProgramError,
     pubkey::Pubkey,
+    system_program,
     sysvar::Sysvar,
 };
 
@@ -13,18 +15,73 @@ pub unsafe fn change_client_data(
     accounts: &[AccountInfo],
     instruction_data: &[u8],
 ) -> ProgramResult {
+    // Change Client Data Instruction
+    // #1 - Wallet (Signer, Writable)
+    // #2 - Client Account (Writable)
+    // #3 - Old Nickname Account (Writable, ignored without a current nickname)
+    // #4 - New Nickname Account (Writable, ignored for an empty nickname)
+    // #5 - System Program
+    // #6 - Ref Code Account of the new nickname (ignored for an empty nickname)
+    // [0..8] - 6
+    // [8..40] - Nickname (all zeros to drop the nickname)
+    if instruction_data.len() < 8 + NICKNAME_STRING_LENGTH {
+        return Err(InvalidDataLength.into());
+    }
     let account_info_iter = &mut accounts.iter();
     let signer = next_account_info(account_info_iter)?;
     let client_acc = next_account_info(account_info_iter)?;
+    if !signer.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
     if client_acc.owner != program_id {
         return Err(InvalidClientAccount.into());
     }
+    check_account_size(client_acc, CLIENT_ACCOUNT_SIZE)?;
     let client = client_acc.data.borrow().as_ptr() as *mut ClientAccount;
     if (*client).tag != CLIENT_TAG || (*client).wallet != *signer.key {
         return Err(InvalidClientAccount.into());
     }
-    (*client).nickname = *((instruction_data[8..]).as_ptr() as *const [u8; NICKNAME_STRING_LENGTH]);
+    let old_nickname_acc = next_account_info(account_info_iter)?;
+    let new_nickname_acc = next_account_info(account_info_iter)?;
+    let system_program_acc = next_account_info(account_info_iter)?;
+    let code_acc = next_account_info(account_info_iter)?;
+    if !system_program::check_id(system_program_acc.key) {
+        return Err(InvalidSystemProgramId.into());
+    }
+    let nickname = *((instruction_data[8..]).as_ptr() as *const [u8; NICKNAME_STRING_LENGTH]);
+    let has_nickname = nickname.iter().any(|c| *c != 0);
+    let key = if has_nickname {
+        Some(normalize_nickname(&nickname)?)
+    } else {
+        None
+    };
+    let old_key = normalize_nickname(&(*client).nickname).ok();
     let clock = Clock::get()?;
+    // A new spelling of the same nickname keeps its registry account
+    if key != old_key {
+        if let Some(old_key) = old_key {
+            release_nickname(
+                program_id,
+                (*client).version,
+                signer,
+                old_nickname_acc,
+                &old_key,
+            )?;
+        }
+        if has_nickname {
+            claim_nickname(
+                program_id,
+                (*client).version,
+                signer,
+                new_nickname_acc,
+                code_acc,
+                &nickname,
+                clock.slot,
+                clock.unix_timestamp as u32,
+            )?;
+        }
+    }
+    (*client).nickname = nickname;
     (*client).slot = clock.slot;
     (*client).time = clock.unix_timestamp as u32;
     Ok(())
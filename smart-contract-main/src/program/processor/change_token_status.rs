@@ -4,7 +4,6 @@ use solana_program::{
     account_info::{next_account_info, AccountInfo},
     clock::Clock,
     entrypoint::ProgramResult,
-    program_error::ProgramError,
     pubkey::Pubkey,
     sysvar::Sysvar,
 };
@@ -14,25 +13,26 @@ pub unsafe fn change_token_status(
     accounts: &[AccountInfo],
     instruction_data: &[u8],
 ) -> ProgramResult {
+    // [0] - 7
+    // [1] - 1 to verify, anything else to reject
+    if instruction_data.len() < 2 {
+        return Err(InvalidDataLength.into());
+    }
     let account_info_iter = &mut accounts.iter();
     let signer = next_account_info(account_info_iter)?;
     let root_acc = next_account_info(account_info_iter)?;
     let token_acc = next_account_info(account_info_iter)?;
-    if root_acc.owner != program_id {
-        return Err(ProgramError::IncorrectProgramId);
-    }
-    let root = root_acc.data.borrow().as_ptr() as *mut RootAccount;
-    if (*root).tag != ROOT_TAG {
-        return Err(InvalidAccountTag.into());
-    }
+    let root = load_root_account(program_id, root_acc)?;
     let (hype_auth, _) = Pubkey::find_program_address(&[HYPE_SEED], program_id);
+    if token_acc.owner != program_id {
+        return Err(InvalidTokenAccount.into());
+    }
+    check_account_size(token_acc, TOKEN_ACCOUNT_SIZE)?;
     let token = token_acc.data.borrow()[..].as_ptr() as *mut TokenAccount;
     let token_account_seed =
         get_token_seed_bytes((*root).version, (*token).network, &(*token).address);
     check_account(token_acc, &hype_auth, program_id, &token_account_seed)?;
-    let network_record = *(root_acc.data.borrow()
-        [ROOT_ACCOUNT_SIZE + (*token).network as usize * NETWORK_RECORD_SIZE..]
-        .as_ptr() as *const NetworkRecord);
+    let network_record = read_network_record(root_acc, (*token).network)?;
     if network_record.validator != *signer.key {
         return Err(InvalidValidator.into());
     }
@@ -0,0 +1,40 @@
+use crate::program::*;
+use crate::state::*;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    pubkey::Pubkey,
+};
+
+pub unsafe fn check_invariants(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    // Check Invariants Instruction (read-only, meant to be simulated)
+    // #1 - Root Account
+    // #2 - Base Currency Program Account
+    // #3.. - (Token Account, Hype Mint) pairs in increasing token id order
+    // [0] - 10
+    if accounts.len() < 2 || !accounts.len().is_multiple_of(2) {
+        return Err(InvalidAccountsNumber.into());
+    }
+    let accounts_iter = &mut accounts.iter();
+    let root_acc = next_account_info(accounts_iter)?;
+    let base_crncy_program_acc = next_account_info(accounts_iter)?;
+    let root = load_root_account(program_id, root_acc)?;
+    if (*root).base_crncy_program_address != *base_crncy_program_acc.key {
+        return Err(InvalidBaseCrncyProgramAddress.into());
+    }
+    let mut checker = InvariantChecker::new(&*root);
+    checker.check_vault(read_token_amount(&base_crncy_program_acc.data.borrow())?)?;
+    while let Ok(token_acc) = next_account_info(accounts_iter) {
+        let hype_mint_acc = next_account_info(accounts_iter)?;
+        if token_acc.owner != program_id {
+            return Err(InvalidTokenAccount.into());
+        }
+        check_account_size(token_acc, TOKEN_ACCOUNT_SIZE)?;
+        let token = token_acc.data.borrow().as_ptr() as *const TokenAccount;
+        if (*token).mint != *hype_mint_acc.key || *hype_mint_acc.owner != spl_token_2022::id() {
+            return Err(InvalidTokenMint.into());
+        }
+        checker.add_token(&*token, read_mint_supply(&hype_mint_acc.data.borrow())?)?;
+    }
+    checker.finish()
+}
@@ -0,0 +1,97 @@
+use crate::program::*;
+use crate::state::*;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_program,
+    sysvar::Sysvar,
+};
+
+pub unsafe fn claim_creator_fees(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    // Claim Creator Fees Instruction
+    // #1 - Creator Wallet (Signer, Writable)
+    // #2 - Token Account (Writable)
+    // #3 - Root Account (Writable)
+    // #4 - Base Currency Mint
+    // #5 - Base Currency Program Account (Writable)
+    // #6 - Creator Base Currency Associated Token Account (Writable)
+    // #7 - Base Currency Token Program
+    // #8 - Hype Authority
+    // #9 - System Program
+    // #10 - Associated Token Program
+    // [0] - 16
+    let accounts_iter = &mut accounts.iter();
+    let wallet = next_account_info(accounts_iter)?;
+    let token_acc = next_account_info(accounts_iter)?;
+    let root_acc = next_account_info(accounts_iter)?;
+    let base_crncy_mint = next_account_info(accounts_iter)?;
+    let base_crncy_program_acc = next_account_info(accounts_iter)?;
+    let associated_token_acc = next_account_info(accounts_iter)?;
+    let token_program_id = next_account_info(accounts_iter)?;
+    let hype_auth_acc = next_account_info(accounts_iter)?;
+    let system_program_acc = next_account_info(accounts_iter)?;
+    let associated_token_id = next_account_info(accounts_iter)?;
+    if !wallet.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if !system_program::check_id(system_program_acc.key) {
+        return Err(InvalidSystemProgramId.into());
+    }
+    if *associated_token_id.key != spl_associated_token_account::id() {
+        return Err(InvalidAssociatedTokenId.into());
+    }
+    let root = load_root_account(program_id, root_acc)?;
+    if (*root).base_crncy_mint != *base_crncy_mint.key {
+        return Err(InvalidBaseCrncyMint.into());
+    }
+    if (*root).base_crncy_program_address != *base_crncy_program_acc.key {
+        return Err(InvalidBaseCrncyProgramAddress.into());
+    }
+    if *base_crncy_program_acc.owner != *token_program_id.key {
+        return Err(InvalidTokenProgramId.into());
+    }
+    if token_acc.owner != program_id {
+        return Err(InvalidTokenAccount.into());
+    }
+    check_account_size(token_acc, TOKEN_ACCOUNT_SIZE)?;
+    let token = token_acc.data.borrow().as_ptr() as *mut TokenAccount;
+    if (*token).tag != TOKEN_TAG || (*token).version != (*root).version {
+        return Err(InvalidTokenAccount.into());
+    }
+    if (*token).creator != *wallet.key {
+        return Err(InvalidTokenCreator.into());
+    }
+    let amount = (*token).creator_fees;
+    if amount == 0 {
+        return Ok(());
+    }
+    let root_creator_fees = (*root)
+        .creator_fees
+        .checked_sub(amount)
+        .ok_or(ArithmeticOverflow)?;
+    VaultPayout {
+        payer: wallet,
+        owner: wallet,
+        base_crncy_mint_acc: base_crncy_mint,
+        base_crncy_program_acc: base_crncy_program_acc,
+        associated_token_acc: associated_token_acc,
+        token_program_id: token_program_id,
+        hype_auth_acc: hype_auth_acc,
+    }
+    .pay(program_id, &*root, amount)?;
+    let clock = Clock::get()?;
+    (*root).creator_fees = root_creator_fees;
+    (*token).creator_fees = 0;
+    log_claim_creator_fees(
+        (*token).id,
+        wallet.key,
+        amount,
+        (*token).all_time_creator_fees,
+        clock.unix_timestamp as u32,
+        clock.slot,
+    );
+    Ok(())
+}
@@ -0,0 +1,112 @@
+use crate::program::*;
+use crate::state::*;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_program,
+    sysvar::Sysvar,
+};
+
+pub unsafe fn claim_referral_fees(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    // Claim Referral Fees Instruction
+    // #1 - Referrer Wallet (Signer, Writable)
+    // #2 - Referrer Account (Writable)
+    // #3 - Root Account (Writable)
+    // #4 - Base Currency Mint
+    // #5 - Base Currency Program Account (Writable)
+    // #6 - Referrer Base Currency Associated Token Account (Writable)
+    // #7 - Base Currency Token Program
+    // #8 - Hype Authority
+    // #9 - System Program
+    // #10 - Associated Token Program
+    // [0] - 11
+    // Opens the referrer account on the wallet's rent if it does not exist yet, after
+    // which the referrer earns fees on the trades of the clients it referred.
+    let accounts_iter = &mut accounts.iter();
+    let wallet = next_account_info(accounts_iter)?;
+    let referrer_acc = next_account_info(accounts_iter)?;
+    let root_acc = next_account_info(accounts_iter)?;
+    let base_crncy_mint = next_account_info(accounts_iter)?;
+    let base_crncy_program_acc = next_account_info(accounts_iter)?;
+    let associated_token_acc = next_account_info(accounts_iter)?;
+    let token_program_id = next_account_info(accounts_iter)?;
+    let hype_auth_acc = next_account_info(accounts_iter)?;
+    let system_program_acc = next_account_info(accounts_iter)?;
+    let associated_token_id = next_account_info(accounts_iter)?;
+    if !wallet.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if !system_program::check_id(system_program_acc.key) {
+        return Err(InvalidSystemProgramId.into());
+    }
+    if *associated_token_id.key != spl_associated_token_account::id() {
+        return Err(InvalidAssociatedTokenId.into());
+    }
+    let root = load_root_account(program_id, root_acc)?;
+    if (*root).base_crncy_mint != *base_crncy_mint.key {
+        return Err(InvalidBaseCrncyMint.into());
+    }
+    if (*root).base_crncy_program_address != *base_crncy_program_acc.key {
+        return Err(InvalidBaseCrncyProgramAddress.into());
+    }
+    if *base_crncy_program_acc.owner != *token_program_id.key {
+        return Err(InvalidTokenProgramId.into());
+    }
+    if referrer_acc.owner == &system_program::ID {
+        let clock = Clock::get()?;
+        return create_referrer_account(
+            program_id,
+            (*root).version,
+            wallet,
+            referrer_acc,
+            wallet.key,
+            clock.slot,
+            clock.unix_timestamp as u32,
+        );
+    }
+    let referrer_seed = get_seed_by_tag((*root).version, REFERRER_TAG);
+    check_account(referrer_acc, wallet.key, program_id, &referrer_seed)?;
+    if referrer_acc.owner != program_id {
+        return Err(InvalidReferrerAccount.into());
+    }
+    check_account_size(referrer_acc, REFERRER_ACCOUNT_SIZE)?;
+    let referrer = referrer_acc.data.borrow().as_ptr() as *mut ReferrerAccount;
+    if (*referrer).tag != REFERRER_TAG || (*referrer).wallet != *wallet.key {
+        return Err(InvalidReferrerAccount.into());
+    }
+    let amount = (*referrer).fees;
+    if amount == 0 {
+        return Ok(());
+    }
+    let root_ref_fees = (*root)
+        .ref_fees
+        .checked_sub(amount)
+        .ok_or(ArithmeticOverflow)?;
+    VaultPayout {
+        payer: wallet,
+        owner: wallet,
+        base_crncy_mint_acc: base_crncy_mint,
+        base_crncy_program_acc: base_crncy_program_acc,
+        associated_token_acc: associated_token_acc,
+        token_program_id: token_program_id,
+        hype_auth_acc: hype_auth_acc,
+    }
+    .pay(program_id, &*root, amount)?;
+    let clock = Clock::get()?;
+    (*root).ref_fees = root_ref_fees;
+    (*referrer).fees = 0;
+    (*referrer).all_time_claimed += amount;
+    (*referrer).slot = clock.slot;
+    (*referrer).time = clock.unix_timestamp as u32;
+    log_claim_ref_fees(
+        wallet.key,
+        amount,
+        (*referrer).all_time_claimed,
+        clock.unix_timestamp as u32,
+        clock.slot,
+    );
+    Ok(())
+}
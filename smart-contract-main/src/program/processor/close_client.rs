@@ -0,0 +1,65 @@
+use crate::program::*;
+use crate::state::*;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+};
+
+pub unsafe fn close_client(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    // Close Client Instruction
+    // #1 - Wallet (Signer, Writable)
+    // #2 - Client Account (Writable)
+    // #3 - Referrer Account of the wallet
+    // #4 - Nickname Account (Writable, ignored without a nickname)
+    // [0] - 17
+    // Creator fees stay on the token accounts and remain claimable without a client.
+    let accounts_iter = &mut accounts.iter();
+    let signer = next_account_info(accounts_iter)?;
+    let client_acc = next_account_info(accounts_iter)?;
+    let referrer_acc = next_account_info(accounts_iter)?;
+    let nickname_acc = next_account_info(accounts_iter)?;
+    if !signer.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if client_acc.owner != program_id {
+        return Err(InvalidClientAccount.into());
+    }
+    check_account_size(client_acc, CLIENT_ACCOUNT_SIZE)?;
+    let client = client_acc.data.borrow().as_ptr() as *const ClientAccount;
+    if (*client).tag != CLIENT_TAG || (*client).wallet != *signer.key {
+        return Err(InvalidClientAccount.into());
+    }
+    let version = (*client).version;
+    let referrer_seed = get_seed_by_tag(version, REFERRER_TAG);
+    check_account(referrer_acc, signer.key, program_id, &referrer_seed)?;
+    if referrer_acc.owner == program_id {
+        check_account_size(referrer_acc, REFERRER_ACCOUNT_SIZE)?;
+        let referrer = referrer_acc.data.borrow().as_ptr() as *const ReferrerAccount;
+        if (*referrer).fees > 0 {
+            return Err(UnclaimedFees.into());
+        }
+    }
+    if let Ok(key) = normalize_nickname(&(*client).nickname) {
+        release_nickname(program_id, version, signer, nickname_acc, &key)?;
+    }
+    let client_id = (*client).id;
+    let lamports = client_acc.lamports();
+    **signer.lamports.borrow_mut() = signer
+        .lamports()
+        .checked_add(lamports)
+        .ok_or(ArithmeticOverflow)?;
+    **client_acc.lamports.borrow_mut() = 0;
+    client_acc.data.borrow_mut().fill(0);
+    let clock = Clock::get()?;
+    log_close_client(
+        client_id,
+        signer.key,
+        clock.unix_timestamp as u32,
+        clock.slot,
+    );
+    Ok(())
+}
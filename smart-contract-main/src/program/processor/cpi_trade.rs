@@ -0,0 +1,87 @@
+use crate::program::processor::{burn_tokens, mint_tokens};
+use crate::program::*;
+use crate::state::*;
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, program::set_return_data, pubkey::Pubkey,
+};
+use std::convert::TryInto;
+
+pub unsafe fn cpi_trade(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    // Cpi Trade Instruction
+    // #1 - Wallet (Signer, Writable)
+    // #2 - Client Account (Writable)
+    // #3 - Client Associated Token Account (Writable)
+    // #4 - Client Associated Hype Account (Writable)
+    // #5 - Root Account (Writable)
+    // #6 - Token Account (Writable)
+    // #7 - Base Crncy Mint
+    // #8 - Base Crncy Program Account (Writable)
+    // #9 - Hype Mint (Writable)
+    // #10 - Hype Program Account (Writable)
+    // #11 - Hype Authority
+    // #12 - Base Crncy Token Program
+    // #13 - Token 2022 Program
+    // #14 - System Program
+    // Delegate accounts as in `mint`, then the referral accounts of `mint` only while
+    // the client's referral is active, then the accounts declared in the extras flags
+    // [0..8] - 26
+    // [8..12] - Side (0 - buy, 1 - sell)
+    // [12..16] - Extras flags (see `next_trade_extras`)
+    // [16..24] - Amount
+    // [24..32] - Max cost of a buy or min cash out of a sell (0 for no limit)
+    // [32..40] - Valid until slot (Optional, 0 for no limit)
+    // [40..44] - Valid until unix time (Optional, 0 for no limit)
+    // Return data (see `TradeResult`):
+    // [0..8] - Amount
+    // [8..16] - Base currency amount at the curve price
+    // [16..24] - Fees
+    // [24..32] - Paid with fees or received after fees
+    // [32..40] - Token supply after the trade
+    // Trades existing tokens; the client's associated token accounts have to exist.
+    if instruction_data.len() < 32 {
+        return Err(InvalidDataLength.into());
+    }
+    let accounts_iter = &mut accounts.iter();
+    let ctx = Context::new_cpi(program_id, accounts_iter, instruction_data)?;
+    ctx.check_valid_until(instruction_data, 32)?;
+    let side = u32::from_le_bytes(instruction_data[8..12].try_into().unwrap());
+    let amount = u64::from_le_bytes(instruction_data[16..24].try_into().unwrap());
+    let limit = u64::from_le_bytes(instruction_data[24..32].try_into().unwrap());
+    let extras = next_trade_extras(accounts_iter, read_trade_extras(instruction_data, 12)?)?;
+    let token_account = ctx.load_token_account(program_id)?;
+    let records = ctx.load_trade_records(program_id, &*token_account, &extras)?;
+    let (fill, fees) = match side {
+        BATCH_SIDE_BUY => {
+            let ctx = ctx.with_launch_extras(program_id, &extras)?;
+            let fill = mint_tokens(&ctx, token_account, amount, limit, 0)?;
+            records.record_buy(&fill, ctx.slot, ctx.time)?;
+            let fees = fill.payment - fill.base_crncy_amount;
+            (fill, fees)
+        }
+        BATCH_SIDE_SELL => {
+            if extras.launch_allowance.is_some() || extras.launch_buy.is_some() {
+                return Err(InvalidTradeExtras.into());
+            }
+            let fill = burn_tokens(&ctx, token_account, amount, limit)?;
+            records.record_sell(&fill, ctx.slot, ctx.time)?;
+            let fees = fill.base_crncy_amount - fill.payment;
+            (fill, fees)
+        }
+        _ => return Err(InvalidInstruction.into()),
+    };
+    set_return_data(
+        &TradeResult {
+            amount: fill.amount,
+            base_crncy_amount: fill.base_crncy_amount,
+            fees: fees,
+            payment: fill.payment,
+            supply: (*token_account).supply,
+        }
+        .to_bytes(),
+    );
+    Ok(())
+}
@@ -0,0 +1,47 @@
+use crate::program::*;
+use crate::state::*;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    program::set_return_data,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+};
+use std::convert::TryInto;
+
+pub unsafe fn get_twap(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    // Get Twap Instruction
+    // #1 - Token Account
+    // [0..8] - 20
+    // [8..12] - Window in seconds
+    // Return data:
+    // [0..8] - Time-weighted average spot price (f64)
+    // [8..12] - Seconds the average covers, at least the window
+    // [12..20] - Current spot price (f64)
+    if instruction_data.len() < 12 {
+        return Err(InvalidDataLength.into());
+    }
+    let window = u32::from_le_bytes(instruction_data[8..12].try_into().unwrap());
+    let accounts_iter = &mut accounts.iter();
+    let token_acc = next_account_info(accounts_iter)?;
+    if token_acc.owner != program_id {
+        return Err(InvalidTokenAccount.into());
+    }
+    check_account_size(token_acc, TOKEN_ACCOUNT_SIZE)?;
+    let token = token_acc.data.borrow().as_ptr() as *const TokenAccount;
+    if (*token).tag != TOKEN_TAG {
+        return Err(InvalidTokenAccount.into());
+    }
+    let (price, elapsed) = (*token).twap(window, Clock::get()?.unix_timestamp as u32)?;
+    let mut data = [0u8; 20];
+    data[0..8].copy_from_slice(&price.to_le_bytes());
+    data[8..12].copy_from_slice(&elapsed.to_le_bytes());
+    data[12..20].copy_from_slice(&(*token).twap_price.to_le_bytes());
+    set_return_data(&data);
+    Ok(())
+}
@@ -0,0 +1,156 @@
+use crate::program::*;
+use crate::state::*;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_program,
+    sysvar::Sysvar,
+};
+
+pub unsafe fn graduate(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    // Graduate Instruction
+    // #1 - Payer (Signer, Writable)
+    // #2 - Root Account (Writable)
+    // #3 - Token Account (Writable)
+    // #4 - Hype Mint (Writable)
+    // #5 - Hype Program Account (Writable)
+    // #6 - Hype Authority
+    // #7 - Pool Account (Writable)
+    // #8 - Token 2022 Program
+    // #9 - System Program
+    // [0] - 28
+    // Anyone may graduate a token whose curve reserve reached `graduation_reserve`.
+    // The reserve and the tokens it buys at the spot price open the token's pool,
+    // which `swap` trades from then on.
+    let accounts_iter = &mut accounts.iter();
+    let signer = next_account_info(accounts_iter)?;
+    let root_acc = next_account_info(accounts_iter)?;
+    let token_acc = next_account_info(accounts_iter)?;
+    let hype_mint_acc = next_account_info(accounts_iter)?;
+    let hype_program_acc = next_account_info(accounts_iter)?;
+    let hype_auth_acc = next_account_info(accounts_iter)?;
+    let pool_acc = next_account_info(accounts_iter)?;
+    let token_2022_program_id = next_account_info(accounts_iter)?;
+    let system_program_acc = next_account_info(accounts_iter)?;
+    if !signer.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if *token_2022_program_id.key != spl_token_2022::id() {
+        return Err(InvalidToken2022ProgramId.into());
+    }
+    if !system_program::check_id(system_program_acc.key) {
+        return Err(InvalidSystemProgramId.into());
+    }
+    let root = load_root_account(program_id, root_acc)?;
+    if token_acc.owner != program_id {
+        return Err(InvalidTokenAccount.into());
+    }
+    check_account_size(token_acc, TOKEN_ACCOUNT_SIZE)?;
+    let token = token_acc.data.borrow().as_ptr() as *mut TokenAccount;
+    if (*token).tag != TOKEN_TAG || (*token).version != (*root).version {
+        return Err(InvalidTokenAccount.into());
+    }
+    if (*token).mint != *hype_mint_acc.key {
+        return Err(InvalidTokenMint.into());
+    }
+    if (*token).program_address != *hype_program_acc.key {
+        return Err(InvalidTokenAccount.into());
+    }
+    let (hype_auth, hype_bump_seed) = Pubkey::find_program_address(&[HYPE_SEED], program_id);
+    if hype_auth != *hype_auth_acc.key {
+        return Err(InvalidHypeAuthority.into());
+    }
+    if read_mint_supply(&hype_mint_acc.data.borrow())? != (*token).supply {
+        return Err(InvalidTokenSupply.into());
+    }
+    if (*token).graduated != 0 {
+        return Err(TokenGraduated.into());
+    }
+    let curve_params = CurveParams::from_root(&*root);
+    let (base_crncy_reserve, token_reserve) = graduation_amounts(&curve_params, (*token).supply)?;
+    if (*root).graduation_reserve == 0 || base_crncy_reserve < (*root).graduation_reserve {
+        return Err(GraduationReserveNotReached.into());
+    }
+    let pool_seed = get_seed_by_tag((*root).version, POOL_TAG);
+    let (pool_address, pool_bump_seed) =
+        Pubkey::find_program_address(&[&pool_seed, hype_mint_acc.key.as_ref()], program_id);
+    if pool_address != *pool_acc.key {
+        return Err(InvalidAccountKey.into());
+    }
+    create_pda_account(
+        program_id,
+        signer,
+        pool_acc,
+        POOL_ACCOUNT_SIZE,
+        &[&pool_seed, hype_mint_acc.key.as_ref(), &[pool_bump_seed]],
+    )?;
+    invoke_signed(
+        &spl_token_2022::instruction::mint_to(
+            &spl_token_2022::ID,
+            hype_mint_acc.key,
+            hype_program_acc.key,
+            hype_auth_acc.key,
+            &[hype_auth_acc.key],
+            token_reserve,
+        )?,
+        &[
+            hype_mint_acc.clone(),
+            hype_program_acc.clone(),
+            hype_auth_acc.clone(),
+        ],
+        &[&[&HYPE_SEED[..], &[hype_bump_seed]]],
+    )?;
+    let clock = Clock::get()?;
+    let time = clock.unix_timestamp as u32;
+    *(pool_acc.data.borrow().as_ptr() as *mut PoolAccount) = PoolAccount {
+        tag: POOL_TAG,
+        version: (*root).version,
+        mint: *hype_mint_acc.key,
+        token: *token_acc.key,
+        base_crncy_reserve: base_crncy_reserve,
+        token_reserve: token_reserve,
+        all_time_base_crncy_volume: 0,
+        all_time_tokens_volume: 0,
+        slot: clock.slot,
+        time: time,
+        swaps_count: 0,
+        creation_time: time,
+        reserved: 0,
+    };
+    (*token).graduated = 1;
+    (*token).supply = (*token)
+        .supply
+        .checked_add(token_reserve)
+        .ok_or(ArithmeticOverflow)?;
+    (*token).slot = clock.slot;
+    (*token).time = time;
+    (*root).supply = (*root)
+        .supply
+        .checked_add(token_reserve)
+        .ok_or(ArithmeticOverflow)?;
+    if (*root).tvl < base_crncy_reserve {
+        return Err(InvalidTVL.into());
+    }
+    (*root).tvl -= base_crncy_reserve;
+    (*root).pool_reserves = (*root)
+        .pool_reserves
+        .checked_add(base_crncy_reserve)
+        .ok_or(ArithmeticOverflow)?;
+    (*root).counter += 1;
+    (*root).slot = clock.slot;
+    (*root).time = time;
+    log_graduate(
+        (*root).counter,
+        (*token).id,
+        hype_mint_acc.key,
+        base_crncy_reserve,
+        token_reserve,
+        time,
+        clock.slot,
+    );
+    Ok(())
+}
@@ -0,0 +1,86 @@
+use crate::program::*;
+use crate::state::*;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction, system_program,
+    sysvar::Sysvar,
+};
+
+pub unsafe fn init_price_history(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    // Init Price History Instruction
+    // #1 - Payer (Signer, Writable)
+    // #2 - Root Account
+    // #3 - Token Account (Writable)
+    // #4 - Price History Account (Writable)
+    // #5 - System Program
+    // [0] - 19
+    // Anyone may pay for the history of a token; from then on every trade of the token
+    // has to pass it, and `mint` and `burn` keep it up to date.
+    let accounts_iter = &mut accounts.iter();
+    let signer = next_account_info(accounts_iter)?;
+    let root_acc = next_account_info(accounts_iter)?;
+    let token_acc = next_account_info(accounts_iter)?;
+    let price_history_acc = next_account_info(accounts_iter)?;
+    let system_program_acc = next_account_info(accounts_iter)?;
+    if !signer.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if !system_program::check_id(system_program_acc.key) {
+        return Err(InvalidSystemProgramId.into());
+    }
+    let root = load_root_account(program_id, root_acc)?;
+    if token_acc.owner != program_id {
+        return Err(InvalidTokenAccount.into());
+    }
+    check_account_size(token_acc, TOKEN_ACCOUNT_SIZE)?;
+    let token = token_acc.data.borrow().as_ptr() as *mut TokenAccount;
+    if (*token).tag != TOKEN_TAG || (*token).version != (*root).version {
+        return Err(InvalidTokenAccount.into());
+    }
+    let price_history_seed = get_seed_by_tag((*root).version, PRICE_HISTORY_TAG);
+    let (price_history_address, price_history_bump_seed) =
+        Pubkey::find_program_address(&[&price_history_seed, (*token).mint.as_ref()], program_id);
+    if price_history_address != *price_history_acc.key {
+        return Err(InvalidAccountKey.into());
+    }
+    if price_history_acc.owner != &system_program::ID {
+        return Err(InvalidPriceHistoryAccount.into());
+    }
+    let rent = &Rent::default();
+    invoke_signed(
+        &system_instruction::create_account(
+            signer.key,
+            price_history_acc.key,
+            rent.minimum_balance(PRICE_HISTORY_ACCOUNT_SIZE),
+            PRICE_HISTORY_ACCOUNT_SIZE as u64,
+            program_id,
+        ),
+        &[signer.clone(), price_history_acc.clone()],
+        &[&[
+            &price_history_seed,
+            (*token).mint.as_ref(),
+            &[price_history_bump_seed],
+        ]],
+    )?;
+    // The candles start zeroed, the header is written field by field
+    // to keep the whole account off the stack.
+    let price_history = price_history_acc.data.borrow().as_ptr() as *mut PriceHistoryAccount;
+    (*price_history).tag = PRICE_HISTORY_TAG;
+    (*price_history).version = (*root).version;
+    (*price_history).mint = (*token).mint;
+    // Roots initialized before candle intervals existed hold 0 there
+    (*price_history).interval = if (*root).candle_interval == 0 {
+        DEFAULT_CANDLE_INTERVAL
+    } else {
+        (*root).candle_interval
+    };
+    (*price_history).slot = Clock::get()?.slot;
+    (*token).price_history = 1;
+    Ok(())
+}
@@ -57,12 +57,8 @@ pub unsafe fn initialize_holder(
             break;
         }
     }
-    let seed = Vec::from_raw_parts(
-        (_instruction_data[1..1 + seed_length]).as_ptr() as *mut u8,
-        seed_length,
-        seed_length,
-    );
-    let seeds = &[&seed, admin.key.as_ref(), &[bump_seed]];
+    let seed = &_instruction_data[1..1 + seed_length];
+    let seeds = &[seed, admin.key.as_ref(), &[bump_seed]];
     let expected_pda = Pubkey::create_program_address(seeds, program_id)?;
     if holder_acc.key != &expected_pda {
         return Err(InvalidNewAccountPDA.into());
@@ -76,9 +72,9 @@ pub unsafe fn initialize_holder(
             program_id,
         ),
         &[admin.clone(), holder_acc.clone()],
-        &[&[&seed, admin.key.as_ref(), &[bump_seed]]],
+        &[&[seed, admin.key.as_ref(), &[bump_seed]]],
     )?;
-    let clock = Clock::get().unwrap();
+    let clock = Clock::get()?;
     let time = clock.unix_timestamp as u32;
     *(holder_acc.data.borrow().as_ptr() as *mut HolderAccount) = HolderAccount {
         tag: HOLDER_TAG as u32,
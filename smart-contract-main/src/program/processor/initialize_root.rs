@@ -7,12 +7,14 @@ use solana_program::{
     //msg,
     program::invoke,
     program::invoke_signed,
+    program_pack::Pack,
     pubkey::Pubkey,
     system_instruction,
     system_program,
     sysvar::rent::Rent,
     sysvar::Sysvar,
 };
+use std::convert::TryInto;
 
 pub unsafe fn initialize_root(
     program_id: &Pubkey,
@@ -32,8 +34,108 @@ pub unsafe fn initialize_root(
     [92..96] - Ref duration
     [96..104] - Ref discount
     [104..112] - Ref ratio
+    [112..116] - Ref depth (Optional, 1 if omitted)
+    [116..132] - Ref ratios of levels 2 and 3 (Optional)
+    [132..136] - Ref grace period (Optional, DEFAULT_REF_GRACE_PERIOD if omitted, 0 disables SetReferrer)
+    [136..144] - Creator fee ratio (Optional, 0 if omitted)
+    [144..148] - Token idle period (Optional, 0 if omitted)
+    [148..152] - Candle interval of price histories (Optional, 60 if omitted)
+    [152..160] - Switch fee rate (Optional, the fee rate if omitted)
+    [160..164] - Launch window in seconds (Optional, 0 if omitted)
+    [164..168] - Restricted window in seconds (Optional, 0 if omitted)
+    [168..176] - Launch max buy (Optional, 0 for no limit if omitted)
+    [176..180] - Buy cooldown in seconds (Optional, 0 if omitted)
+    [180..188] - Graduation reserve (Optional, 0 for no graduation if omitted)
+    [188..196] - Pool fee rate (Optional, the fee rate if omitted)
      */
 ) -> ProgramResult {
+    if instruction_data.len() < 112 {
+        return Err(InvalidDataLength.into());
+    }
+    let mut ref_depth: u32 = 1;
+    let mut ref_upline_ratios = [0.0; MAX_REF_DEPTH - 1];
+    if instruction_data.len() > 112 {
+        if instruction_data.len() < 132 {
+            return Err(InvalidDataLength.into());
+        }
+        ref_depth = u32::from_le_bytes(instruction_data[112..116].try_into().unwrap());
+        for (i, ratio) in ref_upline_ratios.iter_mut().enumerate() {
+            let offset = 116 + i * 8;
+            *ratio = f64::from_le_bytes(instruction_data[offset..offset + 8].try_into().unwrap());
+        }
+    }
+    let mut ref_grace_period: u32 = DEFAULT_REF_GRACE_PERIOD;
+    if instruction_data.len() > 132 {
+        if instruction_data.len() < 136 {
+            return Err(InvalidDataLength.into());
+        }
+        ref_grace_period = u32::from_le_bytes(instruction_data[132..136].try_into().unwrap());
+    }
+    let mut creator_fee_ratio: f64 = 0.0;
+    if instruction_data.len() > 136 {
+        if instruction_data.len() < 144 {
+            return Err(InvalidDataLength.into());
+        }
+        creator_fee_ratio = f64::from_le_bytes(instruction_data[136..144].try_into().unwrap());
+    }
+    let mut token_idle_period: u32 = 0;
+    if instruction_data.len() > 144 {
+        if instruction_data.len() < 148 {
+            return Err(InvalidDataLength.into());
+        }
+        token_idle_period = u32::from_le_bytes(instruction_data[144..148].try_into().unwrap());
+    }
+    let mut candle_interval: u32 = DEFAULT_CANDLE_INTERVAL;
+    if instruction_data.len() > 148 {
+        if instruction_data.len() < 152 {
+            return Err(InvalidDataLength.into());
+        }
+        candle_interval = u32::from_le_bytes(instruction_data[148..152].try_into().unwrap());
+    }
+    let mut switch_fee_rate = *((instruction_data[32..]).as_ptr() as *const f64);
+    if instruction_data.len() > 152 {
+        if instruction_data.len() < 160 {
+            return Err(InvalidDataLength.into());
+        }
+        switch_fee_rate = f64::from_le_bytes(instruction_data[152..160].try_into().unwrap());
+    }
+    let mut launch_window: u32 = 0;
+    let mut restricted_window: u32 = 0;
+    let mut launch_max_buy: u64 = 0;
+    let mut buy_cooldown: u32 = 0;
+    if instruction_data.len() > 160 {
+        if instruction_data.len() < 180 {
+            return Err(InvalidDataLength.into());
+        }
+        launch_window = u32::from_le_bytes(instruction_data[160..164].try_into().unwrap());
+        restricted_window = u32::from_le_bytes(instruction_data[164..168].try_into().unwrap());
+        launch_max_buy = u64::from_le_bytes(instruction_data[168..176].try_into().unwrap());
+        buy_cooldown = u32::from_le_bytes(instruction_data[176..180].try_into().unwrap());
+    }
+    let mut graduation_reserve: u64 = 0;
+    let mut pool_fee_rate = *((instruction_data[32..]).as_ptr() as *const f64);
+    if instruction_data.len() > 180 {
+        if instruction_data.len() < 196 {
+            return Err(InvalidDataLength.into());
+        }
+        graduation_reserve = u64::from_le_bytes(instruction_data[180..188].try_into().unwrap());
+        pool_fee_rate = f64::from_le_bytes(instruction_data[188..196].try_into().unwrap());
+    }
+    if !(0.0..1.0).contains(&pool_fee_rate) {
+        return Err(InvalidPoolFeeRate.into());
+    }
+    if !(0.0..=1.0).contains(&switch_fee_rate) {
+        return Err(InvalidSwitchFeeRate.into());
+    }
+    if candle_interval == 0 {
+        return Err(InvalidCandleInterval.into());
+    }
+    if ref_depth == 0 || ref_depth as usize > MAX_REF_DEPTH {
+        return Err(InvalidRefDepth.into());
+    }
+    if !(0.0..=1.0).contains(&creator_fee_ratio) {
+        return Err(InvalidCreatorFeeRatio.into());
+    }
     let accounts_iter = &mut accounts.iter();
     let signer = next_account_info(accounts_iter)?;
     let holder_acc = next_account_info(accounts_iter)?;
@@ -56,15 +158,18 @@ pub unsafe fn initialize_root(
     if !system_program::check_id(system_program.key) {
         return Err(InvalidSystemProgramId.into());
     }
+    if *base_crncy_mint.owner != *token_program_id.key {
+        return Err(InvalidBaseCrncyMint.into());
+    }
+    check_account_size(base_crncy_mint, spl_token::state::Mint::LEN)?;
     check_holder_account(holder_acc, program_id, false)?;
     let operators_count = *(holder_acc.data.borrow()[holder_account_offsets::OPERATORS_COUNT..]
         .as_ptr() as *const u32);
-    let operators: Vec<OperatorRecord>;
     let begin = HOLDER_ACCOUNT_SIZE + (operators_count as usize) * OPERATOR_RECORD_SIZE;
+    check_account_size(holder_acc, begin)?;
     let operators_ptr = (&holder_acc.data.borrow()[HOLDER_ACCOUNT_SIZE..begin]).as_ptr();
-    operators = Vec::from_raw_parts(
-        operators_ptr as *mut OperatorRecord,
-        operators_count as usize,
+    let operators = std::slice::from_raw_parts(
+        operators_ptr as *const OperatorRecord,
         operators_count as usize,
     );
     let mut version: u32 = 0xFFFFFFFF;
@@ -182,6 +287,27 @@ pub unsafe fn initialize_root(
         ref_duration: *((instruction_data[mask_offset + 4..]).as_ptr() as *const u32),
         ref_discount: *((instruction_data[mask_offset + 8..]).as_ptr() as *const f64),
         ref_ratio: *((instruction_data[mask_offset + 16..]).as_ptr() as *const f64),
+        ref_fees: 0,
+        ref_depth: ref_depth,
+        ref_grace_period: ref_grace_period,
+        fee_tiers_count: 0,
+        reserved: 0,
+        fee_tiers: [FeeTier::default(); MAX_FEE_TIERS],
+        ref_upline_ratios: ref_upline_ratios,
+        creator_fee_ratio: creator_fee_ratio,
+        creator_fees: 0,
+        retired_tokens_count: 0,
+        token_idle_period: token_idle_period,
+        candle_interval: candle_interval,
+        switch_fee_rate: switch_fee_rate,
+        launch_window: launch_window,
+        restricted_window: restricted_window,
+        launch_max_buy: launch_max_buy,
+        buy_cooldown: buy_cooldown,
+        reserved2: 0,
+        graduation_reserve: graduation_reserve,
+        pool_fee_rate: pool_fee_rate,
+        pool_reserves: 0,
     };
     //return Err(solana_program::program_error::ProgramError::Custom(2000));
     Ok(())
@@ -0,0 +1,129 @@
+use crate::program::*;
+use crate::state::*;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    program::invoke,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction, system_program,
+    sysvar::Sysvar,
+};
+use std::convert::TryInto;
+
+pub unsafe fn migrate_account(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    // MigrateAccount Instruction
+    // #1 - Payer (Signer, Writable)
+    // #2 - Root, Client or Token Account (Writable)
+    // #3 - System Program
+    // [0] - 30
+    // Anyone may grow a root, client or token account of the original layout to the
+    // current one, paying its extra rent. Fields the original layout did not have start
+    // from the values `initialize_root` and `add_network` use when they are omitted.
+    // An account is of the original layout if it is shorter than the current one; a root
+    // is compared with the current size for its networks count, and every other
+    // instruction rejects it with AccountNotMigrated until it has been migrated.
+    let accounts_iter = &mut accounts.iter();
+    let signer = next_account_info(accounts_iter)?;
+    let account = next_account_info(accounts_iter)?;
+    let system_program_acc = next_account_info(accounts_iter)?;
+    if !signer.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if !system_program::check_id(system_program_acc.key) {
+        return Err(InvalidSystemProgramId.into());
+    }
+    if account.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    check_account_size(account, 8)?;
+    let tag = u32::from_le_bytes(account.data.borrow()[0..4].try_into().unwrap());
+    match tag {
+        ROOT_TAG => migrate_root(signer, account, system_program_acc),
+        CLIENT_TAG => grow(
+            signer,
+            account,
+            system_program_acc,
+            LEGACY_CLIENT_ACCOUNT_SIZE,
+            CLIENT_ACCOUNT_SIZE,
+        ),
+        TOKEN_TAG => grow(
+            signer,
+            account,
+            system_program_acc,
+            LEGACY_TOKEN_ACCOUNT_SIZE,
+            TOKEN_ACCOUNT_SIZE,
+        ),
+        _ => Err(InvalidAccountTag.into()),
+    }
+}
+
+/// Moves the network records behind the fields appended to the root, then fills those
+/// fields and each record's `creator_fee_cap` with their defaults.
+unsafe fn migrate_root<'a>(
+    signer: &AccountInfo<'a>,
+    root_acc: &AccountInfo<'a>,
+    system_program_acc: &AccountInfo<'a>,
+) -> ProgramResult {
+    check_account_size(root_acc, LEGACY_ROOT_ACCOUNT_SIZE)?;
+    let networks_count = u32::from_le_bytes(
+        root_acc.data.borrow()
+            [root_account_offsets::NETWORKS_COUNT..root_account_offsets::NETWORKS_COUNT + 4]
+            .try_into()
+            .unwrap(),
+    ) as usize;
+    grow(
+        signer,
+        root_acc,
+        system_program_acc,
+        LEGACY_ROOT_ACCOUNT_SIZE + networks_count * LEGACY_NETWORK_RECORD_SIZE,
+        ROOT_ACCOUNT_SIZE + networks_count * NETWORK_RECORD_SIZE,
+    )?;
+    {
+        let mut data = root_acc.data.borrow_mut();
+        // Records only move forward, so the last one moves first
+        for i in (0..networks_count).rev() {
+            let from = LEGACY_ROOT_ACCOUNT_SIZE + i * LEGACY_NETWORK_RECORD_SIZE;
+            let to = root_account_offsets::NETWORK_RECORDS + i * NETWORK_RECORD_SIZE;
+            data.copy_within(from..from + LEGACY_NETWORK_RECORD_SIZE, to);
+            let cap = to + network_record_offsets::CREATOR_FEE_CAP;
+            data[cap..cap + 8].copy_from_slice(&1.0f64.to_le_bytes());
+        }
+        data[LEGACY_ROOT_ACCOUNT_SIZE..root_account_offsets::NETWORK_RECORDS].fill(0);
+    }
+    let root = root_acc.data.borrow().as_ptr() as *mut RootAccount;
+    (*root).ref_depth = 1;
+    (*root).ref_grace_period = DEFAULT_REF_GRACE_PERIOD;
+    (*root).candle_interval = DEFAULT_CANDLE_INTERVAL;
+    (*root).switch_fee_rate = (*root).fee_rate;
+    (*root).pool_fee_rate = (*root).fee_rate;
+    Ok(())
+}
+
+/// Reallocates an account of `legacy_size` to `size`, zero filling the new bytes.
+unsafe fn grow<'a>(
+    signer: &AccountInfo<'a>,
+    account: &AccountInfo<'a>,
+    system_program_acc: &AccountInfo<'a>,
+    legacy_size: usize,
+    size: usize,
+) -> ProgramResult {
+    if account.data_len() >= size {
+        return Err(AccountAlreadyMigrated.into());
+    }
+    if account.data_len() != legacy_size {
+        return Err(InvalidAccountSize.into());
+    }
+    let rent = Rent::get()?;
+    let lamports_diff = rent
+        .minimum_balance(size)
+        .saturating_sub(account.lamports());
+    if lamports_diff > 0 {
+        invoke(
+            &system_instruction::transfer(signer.key, account.key, lamports_diff),
+            &[signer.clone(), account.clone(), system_program_acc.clone()],
+        )?;
+    }
+    account.realloc(size, true)
+}
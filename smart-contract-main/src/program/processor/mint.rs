@@ -7,12 +7,11 @@ use solana_program::{
     //msg,
     program::invoke,
     program::invoke_signed,
+    program_error::ProgramError,
     pubkey::Pubkey,
     rent::Rent,
     system_instruction,
-    system_program,
 };
-use spl_associated_token_account::{instruction::create_associated_token_account, *};
 use spl_pod::optional_keys::OptionalNonZeroPubkey;
 use spl_token_2022::extension::ExtensionType;
 use spl_token_metadata_interface::state::TokenMetadata;
@@ -29,7 +28,15 @@ pub unsafe fn mint(
     // [32..40] - Amount
     // [40..48] - Max Price
     // [48..80] - Nickname
-    let ctx = Context::new(program_id, &mut accounts.iter(), instruction_data, 48)?;
+    // [80..88] - Valid until slot (Optional, 0 for no limit)
+    // [88..92] - Valid until unix time (Optional, 0 for no limit)
+    // [92..96] - Extras flags (Optional, see `next_trade_extras`)
+    if instruction_data.len() < 48 {
+        return Err(InvalidDataLength.into());
+    }
+    let accounts_iter = &mut accounts.iter();
+    let ctx = Context::new(program_id, accounts_iter, instruction_data, 48)?;
+    ctx.check_valid_until(instruction_data, 80)?;
     let network = *((instruction_data[4..]).as_ptr() as *const u32);
     if network >= (*ctx.root).networks_count {
         return Err(InvalidNetworkId.into());
@@ -48,6 +55,7 @@ pub unsafe fn mint(
         if ctx.token_acc.owner != program_id {
             return Err(InvalidTokenAccount.into());
         }
+        check_account_size(ctx.token_acc, TOKEN_ACCOUNT_SIZE)?;
         token_account = ctx.token_acc.data.borrow().as_ptr() as *mut TokenAccount;
         if (*token_account).network != network {
             return Err(InvalidNetworkId.into());
@@ -57,12 +65,12 @@ pub unsafe fn mint(
         }
         creation_fee = 0;
     } else {
+        // Delegates trade existing tokens only
+        ctx.check_wallet_signed()?;
         if ctx.hype_mint_acc.owner != &solana_program::system_program::id() {
             return Err(InvalidTokenMint.into());
         }
-        let network_record = *((*ctx.root_acc).data.borrow()
-            [ROOT_ACCOUNT_SIZE + network as usize * NETWORK_RECORD_SIZE..]
-            .as_ptr() as *const NetworkRecord);
+        let network_record = read_network_record(ctx.root_acc, network)?;
         check_name(
             &(instruction_data[8..32]),
             &network_record.mask,
@@ -101,11 +109,20 @@ pub unsafe fn mint(
             creation_time: ctx.time,
             supply: 0,
             network: network,
-            reserved: 0,
+            graduated: 0,
             all_time_trades_count: 0,
             all_time_base_crncy_volume: 0,
             all_time_tokens_volume: 0,
             status: token_status::NOT_CHECKED as u64,
+            creator_fees: 0,
+            all_time_creator_fees: 0,
+            twap_price: (*ctx.root).init_price,
+            price_cumulative: 0.0,
+            twap_time: ctx.time,
+            observation_head: 0,
+            observations_count: 0,
+            price_history: 0,
+            observations: [Observation::default(); TWAP_OBSERVATIONS],
         };
         (*ctx.root).tokens_count += 1;
 
@@ -147,6 +164,7 @@ pub unsafe fn mint(
         let instance_size = get_instance_packed_len(&token_metadata)?;
         let space = ExtensionType::try_calculate_account_len::<spl_token_2022::state::Mint>(&[
             ExtensionType::MetadataPointer,
+            ExtensionType::MintCloseAuthority,
         ])?;
         let hype_mint_account_size = space + instance_size + 4 + 2;
         let hype_lamports = rent.minimum_balance(hype_mint_account_size);
@@ -171,6 +189,14 @@ pub unsafe fn mint(
             &pointer_instruction,
             &[ctx.hype_mint_acc.clone(), ctx.hype_auth_acc.clone()],
         )?;
+        invoke(
+            &spl_token_2022::instruction::initialize_mint_close_authority(
+                &spl_token_2022::ID,
+                ctx.hype_mint_acc.key,
+                Some(&ctx.hype_auth),
+            )?,
+            std::slice::from_ref(ctx.hype_mint_acc),
+        )?;
         let initialize_mint_instruction = spl_token_2022::instruction::initialize_mint2(
             &spl_token_2022::ID,
             ctx.hype_mint_acc.key,
@@ -246,173 +272,68 @@ pub unsafe fn mint(
             ((*ctx.root).creation_fee * (*ctx.root).base_crncy_decs_factor as f64) as u64;
         (*ctx.client).tokens_created += 1;
     }
-    if *ctx.client_associated_hype_acc.owner == system_program::ID {
-        invoke(
-            &create_associated_token_account(
-                ctx.signer.key,
-                ctx.signer.key,
-                ctx.hype_mint_acc.key,
-                ctx.token_2022_program_id.key,
-            ),
-            &[
-                ctx.signer.clone(),
-                ctx.client_associated_hype_acc.clone(),
-                ctx.hype_mint_acc.clone(),
-                ctx.token_2022_program_id.clone(),
-            ],
-        )?;
-    }
-    let init_supply = *(ctx.hype_mint_acc.data.borrow()[36..].as_ptr() as *const u64);
+    ctx.create_client_hype_account()?;
+    let amount = *((instruction_data[32..]).as_ptr() as *const u64);
+    let max_cost = *((instruction_data[40..]).as_ptr() as *const u64);
+    let extras = next_trade_extras(accounts_iter, read_trade_extras(instruction_data, 92)?)?;
+    let records = ctx.load_trade_records(program_id, &*token_account, &extras)?;
+    let ctx = ctx.with_launch_extras(program_id, &extras)?;
+    let fill = mint_tokens(&ctx, token_account, amount, max_cost, creation_fee)?;
+    records.record_buy(&fill, ctx.slot, ctx.time)?;
+    //return Err(solana_program::program_error::ProgramError::Custom(2000));
+    Ok(())
+}
+
+/// Buys `amount` of the token of `ctx` for the client at the curve price plus fees,
+/// `creation_fee` included, and mints it to the client's associated account. A buy
+/// carrying the curve past the graduation reserve fails (see `check_graduation_reserve`).
+/// The client's associated hype account has to exist.
+pub unsafe fn mint_tokens(
+    ctx: &Context,
+    token_account: *mut TokenAccount,
+    amount: u64,
+    max_cost: u64,
+    creation_fee: u64,
+) -> Result<Fill, ProgramError> {
+    let init_supply = read_mint_supply(&ctx.hype_mint_acc.data.borrow())?;
     if init_supply != (*token_account).supply {
         return Err(InvalidTokenSupply.into());
     }
-    let init_funds = get_reserve((*ctx.root).init_price, (*ctx.root).max_supply, init_supply)?;
-    let amount = *((instruction_data[32..]).as_ptr() as *const u64);
-    if amount == 0 {
-        return Err(TooSmallQuantity.into());
-    }
-    let max_cost = *((instruction_data[40..]).as_ptr() as *const u64);
-    let final_funds = get_reserve(
-        (*ctx.root).init_price,
-        (*ctx.root).max_supply,
-        init_supply + amount,
-    )?;
-    let cost = final_funds - init_funds;
-    let base_crncy_cost = final_funds as u64 - init_funds as u64;
+    ctx.check_curve_open(&*token_account)?;
+    ctx.check_graduation_reserve(&*token_account, amount)?;
+    ctx.check_delegate_approved()?;
+    ctx.check_launch(&*token_account, amount)?;
+    let fee_tier = ctx.fee_tier();
+    let curve_params = ctx.curve_params(fee_tier);
+    let quote = quote_mint(&curve_params, init_supply, amount)?;
+    quote.check_fees()?;
+    let base_crncy_cost = quote.base_crncy_amount;
     if max_cost > 0 && base_crncy_cost > max_cost {
         return Err(MaxTradeCostExceeded.into());
     }
-    let fees = ((cost * (*ctx.root).fee_rate).max((*ctx.root).min_fee)
-        * (*ctx.root).base_crncy_decs_factor as f64) as u64;
-    let total_fees = fees + creation_fee;
-    let holder_fees = (total_fees as f64 * (1.0 - (*ctx.root).fee_ratio)) as u64;
-
-    let ref_fees: u64;
-    let operator_fees: u64;
-    if (*ctx.client).ref_stop > ctx.time {
-        if (*ctx.client).ref_address != *ctx.ref_acc.key {
-            return Err(InvalidRefAddress.into());
-        }
-        let discounted_fees = (total_fees as f64 * (1.0 - (*ctx.client).ref_discount)) as u64;
-        let rest_of_fees: u64;
-        if discounted_fees > holder_fees {
-            rest_of_fees = discounted_fees - holder_fees;
-        } else {
-            rest_of_fees = 0;
-        }
-        if rest_of_fees > 0 {
-            ref_fees = (rest_of_fees as f64 * (*ctx.client).ref_ratio) as u64;
-            operator_fees = rest_of_fees - ref_fees;
-        } else {
-            ref_fees = 0;
-            operator_fees = 0;
-        }
-    } else {
-        ref_fees = 0;
-        operator_fees = total_fees - holder_fees;
-    }
-    if ref_fees > 0 {
-        (*ctx.client).ref_paid += ref_fees;
-        if *ctx.ref_associated_token_acc.owner == system_program::ID {
-            invoke(
-                &create_associated_token_account(
-                    ctx.signer.key,
-                    ctx.ref_acc.key,
-                    ctx.base_crncy_mint_acc.key,
-                    ctx.token_program_id.key,
-                ),
-                &[
-                    ctx.signer.clone(),
-                    ctx.ref_associated_token_acc.clone(),
-                    ctx.ref_acc.clone(),
-                    ctx.base_crncy_mint_acc.clone(),
-                    ctx.token_program_id.clone(),
-                ],
-            )?;
-        }
-    }
-    let program_payment = base_crncy_cost + operator_fees + holder_fees;
-    if *ctx.base_crncy_program_acc.owner == spl_token_2022::id() {
-        let transfer_to_taker_ix = spl_token_2022::instruction::transfer_checked(
-            &spl_token_2022::id(),
-            ctx.client_associated_token_acc.key,
-            ctx.base_crncy_mint_acc.key,
-            ctx.base_crncy_program_acc.key,
-            &ctx.signer.key,
-            &[&ctx.signer.key],
-            program_payment,
-            (*ctx.root).decimals as u8,
-        )?;
-        invoke(
-            &transfer_to_taker_ix,
-            &[
-                ctx.token_2022_program_id.clone(),
-                ctx.client_associated_token_acc.clone(),
-                ctx.base_crncy_mint_acc.clone(),
-                ctx.base_crncy_program_acc.clone(),
-                ctx.signer.clone(),
-            ],
-        )?;
-        if ref_fees > 0 {
-            let transfer_to_taker_ix = spl_token_2022::instruction::transfer_checked(
-                &spl_token_2022::id(),
-                ctx.client_associated_token_acc.key,
-                ctx.base_crncy_mint_acc.key,
-                ctx.ref_associated_token_acc.key,
-                &ctx.signer.key,
-                &[&ctx.signer.key],
-                ref_fees,
-                (*ctx.root).decimals as u8,
-            )?;
-            invoke(
-                &transfer_to_taker_ix,
-                &[
-                    ctx.token_2022_program_id.clone(),
-                    ctx.client_associated_token_acc.clone(),
-                    ctx.base_crncy_mint_acc.clone(),
-                    ctx.ref_associated_token_acc.clone(),
-                    ctx.signer.clone(),
-                ],
-            )?;
-        }
-    } else {
-        let transfer_to_taker_ix = spl_token::instruction::transfer(
-            &spl_token::id(),
-            ctx.client_associated_token_acc.key,
-            ctx.base_crncy_program_acc.key,
-            &ctx.signer.key,
-            &[&ctx.signer.key],
+    let total_fees = quote
+        .fees
+        .checked_add(creation_fee)
+        .ok_or(ArithmeticOverflow)?;
+    let fee_split = ctx
+        .fee_split(total_fees)
+        .with_creator_fees(quote.fees, ctx.creator_fee_ratio(&*token_account)?);
+    let holder_fees = fee_split.holder_fees;
+    let operator_fees = fee_split.operator_fees;
+    ctx.accrue_ref_fees(&fee_split)?;
+    ctx.accrue_creator_fees(&mut *token_account, &fee_split)?;
+    let program_payment = base_crncy_cost
+        .checked_add(fee_split.total())
+        .ok_or(ArithmeticOverflow)?;
+    if !ctx.delegate.is_null() {
+        (*ctx.delegate).add_trade(
+            (*token_account).network,
             program_payment,
+            ctx.slot,
+            ctx.time,
         )?;
-        invoke(
-            &transfer_to_taker_ix,
-            &[
-                ctx.client_associated_token_acc.clone(),
-                ctx.base_crncy_program_acc.clone(),
-                ctx.signer.clone(),
-                ctx.token_program_id.clone(),
-            ],
-        )?;
-        if ref_fees > 0 {
-            let transfer_to_taker_ix = spl_token::instruction::transfer(
-                &spl_token::id(),
-                ctx.client_associated_token_acc.key,
-                ctx.ref_associated_token_acc.key,
-                &ctx.signer.key,
-                &[&ctx.signer.key],
-                ref_fees,
-            )?;
-            invoke(
-                &transfer_to_taker_ix,
-                &[
-                    ctx.client_associated_token_acc.clone(),
-                    ctx.ref_associated_token_acc.clone(),
-                    ctx.signer.clone(),
-                    ctx.token_program_id.clone(),
-                ],
-            )?;
-        }
     }
+    ctx.deposit_base_crncy(program_payment)?;
     invoke_signed(
         &spl_token_2022::instruction::mint_to(
             &spl_token_2022::ID,
@@ -436,8 +357,14 @@ pub unsafe fn mint(
     (*ctx.root).holder_fees += holder_fees;
     (*ctx.root).slot = ctx.slot;
     (*ctx.root).time = ctx.time;
-    (*ctx.root).supply += amount;
-    (*ctx.root).tvl += base_crncy_cost;
+    (*ctx.root).supply = (*ctx.root)
+        .supply
+        .checked_add(amount)
+        .ok_or(ArithmeticOverflow)?;
+    (*ctx.root).tvl = (*ctx.root)
+        .tvl
+        .checked_add(base_crncy_cost)
+        .ok_or(ArithmeticOverflow)?;
     (*ctx.client).all_time_trades_count += 1;
     (*ctx.client).all_time_base_crncy_volume += base_crncy_cost;
     (*ctx.client).all_time_tokens_volume += amount;
@@ -449,6 +376,8 @@ pub unsafe fn mint(
     (*token_account).supply += amount;
     (*token_account).slot = ctx.slot;
     (*token_account).time = ctx.time;
+    let price_after = spot_price(&curve_params, init_supply + amount)?;
+    (*token_account).update_twap(price_after, ctx.time);
     log_mint(
         (*ctx.client).id,
         (*ctx.root).counter,
@@ -467,7 +396,13 @@ pub unsafe fn mint(
         base_crncy_cost,
         ctx.time,
         ctx.slot,
+        fee_tier,
     );
-    //return Err(solana_program::program_error::ProgramError::Custom(2000));
-    Ok(())
+    Ok(Fill {
+        amount: amount,
+        base_crncy_amount: base_crncy_cost,
+        payment: program_payment,
+        price_before: spot_price(&curve_params, init_supply)?,
+        price_after: price_after,
+    })
 }
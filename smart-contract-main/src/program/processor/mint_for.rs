@@ -0,0 +1,96 @@
+use crate::program::processor::mint_tokens;
+use crate::program::*;
+use crate::state::*;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    program::invoke,
+    pubkey::Pubkey,
+    system_program,
+};
+use spl_associated_token_account::instruction::create_associated_token_account;
+
+pub unsafe fn mint_for(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    // MintFor Instruction
+    // #1..#17 - Trade accounts of `mint` for the payer
+    // Referral and nickname accounts as in `mint`, then:
+    // #1 - Recipient Wallet
+    // #2 - Recipient Associated Hype Account (Writable)
+    // #3 - Price History Account (Optional, Writable)
+    // #4 - Launch Allowance Account of the payer (Optional)
+    // #5 - Launch Buy Account of the payer (Optional, Writable)
+    // [0..8] - 23
+    // [8..16] - Amount
+    // [16..24] - Max Price
+    // [24..56] - Nickname
+    // [56..64] - Valid until slot (Optional, 0 for no limit)
+    // [64..68] - Valid until unix time (Optional, 0 for no limit)
+    // [68..72] - Extras flags (Optional, see `next_trade_extras`)
+    // The payer pays and is credited with the volume, the recipient receives the tokens.
+    // Gifts are kept out of positions, the payer's as well as the recipient's.
+    if instruction_data.len() < 24 {
+        return Err(InvalidDataLength.into());
+    }
+    let accounts_iter = &mut accounts.iter();
+    let ctx = Context::new(program_id, accounts_iter, instruction_data, 24)?;
+    ctx.check_valid_until(instruction_data, 56)?;
+    // A delegate could otherwise give the wallet's funds away
+    ctx.check_wallet_signed()?;
+    let recipient = next_account_info(accounts_iter)?;
+    let recipient_associated_hype_acc = next_account_info(accounts_iter)?;
+    let ctx = ctx.with_recipient(recipient, recipient_associated_hype_acc)?;
+    let token_account = ctx.load_token_account(program_id)?;
+    if *recipient_associated_hype_acc.owner == system_program::ID {
+        invoke(
+            &create_associated_token_account(
+                ctx.signer.key,
+                recipient.key,
+                ctx.hype_mint_acc.key,
+                ctx.token_2022_program_id.key,
+            ),
+            &[
+                ctx.signer.clone(),
+                recipient_associated_hype_acc.clone(),
+                recipient.clone(),
+                ctx.hype_mint_acc.clone(),
+                ctx.token_2022_program_id.clone(),
+            ],
+        )?;
+    }
+    let amount = *((instruction_data[8..]).as_ptr() as *const u64);
+    let max_cost = *((instruction_data[16..]).as_ptr() as *const u64);
+    let extras = next_trade_extras(accounts_iter, read_trade_extras(instruction_data, 68)?)?;
+    // The payer does not hold the tokens it gives away
+    if extras.position.is_some() {
+        return Err(InvalidPositionAccount.into());
+    }
+    let ctx = ctx.with_launch_extras(program_id, &extras)?;
+    let price_history = load_price_history(program_id, &*token_account, extras.price_history)?;
+    let fill = mint_tokens(&ctx, token_account, amount, max_cost, 0)?;
+    if !price_history.is_null() {
+        (*price_history).record(
+            fill.price_before,
+            fill.price_after,
+            fill.base_crncy_amount,
+            fill.amount,
+            ctx.slot,
+            ctx.time,
+        );
+    }
+    log_gift(
+        (*ctx.client).id,
+        (*ctx.root).counter,
+        (*token_account).id,
+        ctx.signer.key,
+        recipient.key,
+        fill.amount,
+        fill.base_crncy_amount,
+        ctx.time,
+        ctx.slot,
+    );
+    Ok(())
+}
@@ -1,21 +1,63 @@
 pub mod add_network;
 pub mod add_operator;
+pub mod batch_trade;
 pub mod burn;
 pub mod change_client_data;
 pub mod change_token_status;
+pub mod check_invariants;
+pub mod claim_creator_fees;
+pub mod claim_referral_fees;
+pub mod close_client;
+pub mod cpi_trade;
+pub mod get_twap;
+pub mod graduate;
+pub mod init_price_history;
 pub mod initialize_holder;
 pub mod initialize_root;
+pub mod migrate_account;
 pub mod mint;
+pub mod mint_for;
+pub mod register_ref_code;
+pub mod release_ref_code;
+pub mod retire_token;
+pub mod revoke_delegate;
+pub mod set_delegate;
+pub mod set_fee_tiers;
+pub mod set_launch_allowance;
+pub mod set_referrer;
+pub mod swap;
+pub mod switch;
 pub mod withdraw_holder_funds;
 pub mod withdraw_operator_funds;
 
 pub use add_network::*;
 pub use add_operator::*;
+pub use batch_trade::*;
 pub use burn::*;
 pub use change_client_data::*;
 pub use change_token_status::*;
+pub use check_invariants::*;
+pub use claim_creator_fees::*;
+pub use claim_referral_fees::*;
+pub use close_client::*;
+pub use cpi_trade::*;
+pub use get_twap::*;
+pub use graduate::*;
+pub use init_price_history::*;
 pub use initialize_holder::*;
 pub use initialize_root::*;
+pub use migrate_account::*;
 pub use mint::*;
+pub use mint_for::*;
+pub use register_ref_code::*;
+pub use release_ref_code::*;
+pub use retire_token::*;
+pub use revoke_delegate::*;
+pub use set_delegate::*;
+pub use set_fee_tiers::*;
+pub use set_launch_allowance::*;
+pub use set_referrer::*;
+pub use swap::*;
+pub use switch::*;
 pub use withdraw_holder_funds::*;
 pub use withdraw_operator_funds::*;
@@ -0,0 +1,113 @@
+use crate::program::*;
+use crate::state::*;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction, system_program,
+    sysvar::Sysvar,
+};
+
+pub unsafe fn register_ref_code(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    // Register Ref Code Instruction
+    // #1 - Wallet (Signer, Writable)
+    // #2 - Client Account
+    // #3 - Ref Code Account (Writable)
+    // #4 - System Program
+    // #5 - Referrer Account (Writable)
+    // #6 - Nickname Account of the code
+    // [0..8] - 12
+    // [8..40] - Code (all zeros to register the client nickname)
+    // Also opens the wallet's referrer account if it does not exist yet. A code that is
+    // another wallet's nickname is taken, as nicknames and codes share one namespace.
+    if instruction_data.len() < 8 + NICKNAME_STRING_LENGTH {
+        return Err(InvalidDataLength.into());
+    }
+    let accounts_iter = &mut accounts.iter();
+    let signer = next_account_info(accounts_iter)?;
+    let client_acc = next_account_info(accounts_iter)?;
+    let code_acc = next_account_info(accounts_iter)?;
+    let system_program_acc = next_account_info(accounts_iter)?;
+    let referrer_acc = next_account_info(accounts_iter)?;
+    let nickname_acc = next_account_info(accounts_iter)?;
+    if !signer.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if !system_program::check_id(system_program_acc.key) {
+        return Err(InvalidSystemProgramId.into());
+    }
+    if client_acc.owner != program_id {
+        return Err(InvalidClientAccount.into());
+    }
+    check_account_size(client_acc, CLIENT_ACCOUNT_SIZE)?;
+    let client = client_acc.data.borrow().as_ptr() as *const ClientAccount;
+    if (*client).tag != CLIENT_TAG || (*client).wallet != *signer.key {
+        return Err(InvalidClientAccount.into());
+    }
+    let input = &instruction_data[8..8 + NICKNAME_STRING_LENGTH];
+    let code = if input.iter().all(|c| *c == 0) {
+        normalize_ref_code(&(*client).nickname)?
+    } else {
+        normalize_ref_code(input)?
+    };
+    let code_seed = get_seed_by_tag((*client).version, REF_CODE_TAG);
+    let (code_address, code_bump_seed) =
+        Pubkey::find_program_address(&[&code_seed, &code], program_id);
+    if code_address != *code_acc.key {
+        return Err(InvalidAccountKey.into());
+    }
+    if code_acc.owner != &system_program::ID {
+        return Err(RefCodeAlreadyRegistered.into());
+    }
+    check_name_free(
+        program_id,
+        (*client).version,
+        NICKNAME_TAG,
+        nickname_acc,
+        &code,
+        signer.key,
+        RefCodeAlreadyRegistered,
+    )?;
+    let rent = &Rent::default();
+    let code_lamports = rent.minimum_balance(REF_CODE_ACCOUNT_SIZE);
+    invoke_signed(
+        &system_instruction::create_account(
+            signer.key,
+            code_acc.key,
+            code_lamports,
+            REF_CODE_ACCOUNT_SIZE as u64,
+            program_id,
+        ),
+        &[signer.clone(), code_acc.clone()],
+        &[&[&code_seed, &code, &[code_bump_seed]]],
+    )?;
+    let clock = Clock::get()?;
+    *(code_acc.data.borrow().as_ptr() as *mut RefCodeAccount) = RefCodeAccount {
+        tag: REF_CODE_TAG,
+        version: (*client).version,
+        wallet: *signer.key,
+        code: code,
+        slot: clock.slot,
+        time: clock.unix_timestamp as u32,
+        reserved: 0,
+    };
+    create_referrer_account(
+        program_id,
+        (*client).version,
+        signer,
+        referrer_acc,
+        signer.key,
+        clock.slot,
+        clock.unix_timestamp as u32,
+    )?;
+    log_register_ref_code(signer.key, &code, clock.unix_timestamp as u32, clock.slot);
+    Ok(())
+}
@@ -0,0 +1,42 @@
+use crate::program::*;
+use crate::state::*;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+};
+
+pub unsafe fn release_ref_code(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    // Release Ref Code Instruction
+    // #1 - Wallet (Signer, Writable)
+    // #2 - Ref Code Account (Writable)
+    // [0] - 13
+    let accounts_iter = &mut accounts.iter();
+    let signer = next_account_info(accounts_iter)?;
+    let code_acc = next_account_info(accounts_iter)?;
+    if !signer.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if code_acc.owner != program_id {
+        return Err(InvalidRefCode.into());
+    }
+    check_account_size(code_acc, REF_CODE_ACCOUNT_SIZE)?;
+    let code_account = code_acc.data.borrow().as_ptr() as *const RefCodeAccount;
+    if (*code_account).tag != REF_CODE_TAG || (*code_account).wallet != *signer.key {
+        return Err(InvalidRefCode.into());
+    }
+    let code = (*code_account).code;
+    let lamports = code_acc.lamports();
+    **signer.lamports.borrow_mut() = signer
+        .lamports()
+        .checked_add(lamports)
+        .ok_or(ArithmeticOverflow)?;
+    **code_acc.lamports.borrow_mut() = 0;
+    code_acc.data.borrow_mut().fill(0);
+    let clock = Clock::get()?;
+    log_release_ref_code(signer.key, &code, clock.unix_timestamp as u32, clock.slot);
+    Ok(())
+}
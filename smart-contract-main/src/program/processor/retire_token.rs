@@ -0,0 +1,117 @@
+use crate::program::*;
+use crate::state::*;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    program::invoke_signed,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+};
+
+pub unsafe fn retire_token(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    // Retire Token Instruction
+    // #1 - Root Account (Writable)
+    // #2 - Token Account (Writable)
+    // #3 - Hype Mint (Writable)
+    // #4 - Hype Program Account (Writable)
+    // #5 - Creator Wallet (Writable)
+    // #6 - Hype Authority
+    // #7 - Token 2022 Program
+    // [0] - 18
+    // Anyone may retire a token idle at zero supply; the rent goes back to its creator.
+    let accounts_iter = &mut accounts.iter();
+    let root_acc = next_account_info(accounts_iter)?;
+    let token_acc = next_account_info(accounts_iter)?;
+    let hype_mint_acc = next_account_info(accounts_iter)?;
+    let hype_program_acc = next_account_info(accounts_iter)?;
+    let creator_acc = next_account_info(accounts_iter)?;
+    let hype_auth_acc = next_account_info(accounts_iter)?;
+    let token_2022_program_id = next_account_info(accounts_iter)?;
+    if *token_2022_program_id.key != spl_token_2022::id() {
+        return Err(InvalidToken2022ProgramId.into());
+    }
+    let root = load_root_account(program_id, root_acc)?;
+    if token_acc.owner != program_id {
+        return Err(InvalidTokenAccount.into());
+    }
+    check_account_size(token_acc, TOKEN_ACCOUNT_SIZE)?;
+    let token = token_acc.data.borrow().as_ptr() as *const TokenAccount;
+    if (*token).tag != TOKEN_TAG || (*token).version != (*root).version {
+        return Err(InvalidTokenAccount.into());
+    }
+    if (*token).mint != *hype_mint_acc.key {
+        return Err(InvalidTokenMint.into());
+    }
+    if (*token).program_address != *hype_program_acc.key {
+        return Err(InvalidTokenAccount.into());
+    }
+    if (*token).creator != *creator_acc.key {
+        return Err(InvalidTokenCreator.into());
+    }
+    let (hype_auth, hype_bump_seed) = Pubkey::find_program_address(&[HYPE_SEED], program_id);
+    if hype_auth != *hype_auth_acc.key {
+        return Err(InvalidHypeAuthority.into());
+    }
+    if (*token).supply != 0 || read_mint_supply(&hype_mint_acc.data.borrow())? != 0 {
+        return Err(InvalidTokenSupply.into());
+    }
+    if (*token).creator_fees != 0 {
+        return Err(UnclaimedFees.into());
+    }
+    let clock = Clock::get()?;
+    let time = clock.unix_timestamp as u32;
+    if time < (*token).time.saturating_add((*root).token_idle_period) {
+        return Err(TokenNotIdle.into());
+    }
+    invoke_signed(
+        &spl_token_2022::instruction::close_account(
+            &spl_token_2022::ID,
+            hype_program_acc.key,
+            creator_acc.key,
+            hype_auth_acc.key,
+            &[hype_auth_acc.key],
+        )?,
+        &[
+            hype_program_acc.clone(),
+            creator_acc.clone(),
+            hype_auth_acc.clone(),
+        ],
+        &[&[&HYPE_SEED[..], &[hype_bump_seed]]],
+    )?;
+    invoke_signed(
+        &spl_token_2022::instruction::close_account(
+            &spl_token_2022::ID,
+            hype_mint_acc.key,
+            creator_acc.key,
+            hype_auth_acc.key,
+            &[hype_auth_acc.key],
+        )?,
+        &[
+            hype_mint_acc.clone(),
+            creator_acc.clone(),
+            hype_auth_acc.clone(),
+        ],
+        &[&[&HYPE_SEED[..], &[hype_bump_seed]]],
+    )?;
+    log_retire_token(
+        (*token).id,
+        (*token).network,
+        &(*token).mint,
+        &(*token).creator,
+        &(*token).address,
+        time,
+        clock.slot,
+    );
+    let lamports = token_acc.lamports();
+    **creator_acc.lamports.borrow_mut() = creator_acc
+        .lamports()
+        .checked_add(lamports)
+        .ok_or(ArithmeticOverflow)?;
+    **token_acc.lamports.borrow_mut() = 0;
+    token_acc.data.borrow_mut().fill(0);
+    (*root).retired_tokens_count += 1;
+    (*root).slot = clock.slot;
+    (*root).time = time;
+    Ok(())
+}
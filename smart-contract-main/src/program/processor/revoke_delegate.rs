@@ -0,0 +1,79 @@
+use crate::program::*;
+use crate::state::*;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    program::invoke,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+};
+
+pub unsafe fn revoke_delegate(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    // Revoke Delegate Instruction
+    // #1 - Wallet (Signer, Writable)
+    // #2 - Delegate Account (Writable)
+    // #3 - Client Associated Token Account (Writable)
+    // #4 - Base Crncy Token Program
+    // #5 - Token 2022 Program
+    // #6.. - Client Associated Hype Accounts (Optional, Writable)
+    // [0] - 25
+    // Closes the delegate account, which alone stops the delegate from trading,
+    // and clears the SPL delegation of the given token accounts.
+    let accounts_iter = &mut accounts.iter();
+    let signer = next_account_info(accounts_iter)?;
+    let delegate_acc = next_account_info(accounts_iter)?;
+    let client_associated_token_acc = next_account_info(accounts_iter)?;
+    let base_crncy_token_program_id = next_account_info(accounts_iter)?;
+    let token_2022_program_id = next_account_info(accounts_iter)?;
+    if !signer.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if *base_crncy_token_program_id.key != spl_token::id()
+        && *base_crncy_token_program_id.key != spl_token_2022::id()
+    {
+        return Err(InvalidTokenProgramId.into());
+    }
+    if *token_2022_program_id.key != spl_token_2022::id() {
+        return Err(InvalidToken2022ProgramId.into());
+    }
+    if delegate_acc.owner != program_id {
+        return Err(InvalidDelegateAccount.into());
+    }
+    check_account_size(delegate_acc, DELEGATE_ACCOUNT_SIZE)?;
+    let delegate = delegate_acc.data.borrow().as_ptr() as *const DelegateAccount;
+    if (*delegate).tag != DELEGATE_TAG || (*delegate).wallet != *signer.key {
+        return Err(InvalidDelegateAccount.into());
+    }
+    let delegate_key = (*delegate).delegate;
+    for (token_acc, token_program_id) in
+        std::iter::once((client_associated_token_acc, base_crncy_token_program_id))
+            .chain(accounts_iter.map(|acc| (acc, token_2022_program_id)))
+    {
+        invoke(
+            &spl_token_2022::instruction::revoke(
+                token_program_id.key,
+                token_acc.key,
+                signer.key,
+                &[signer.key],
+            )?,
+            &[token_acc.clone(), signer.clone(), token_program_id.clone()],
+        )?;
+    }
+    let lamports = delegate_acc.lamports();
+    **signer.lamports.borrow_mut() = signer
+        .lamports()
+        .checked_add(lamports)
+        .ok_or(ArithmeticOverflow)?;
+    **delegate_acc.lamports.borrow_mut() = 0;
+    delegate_acc.data.borrow_mut().fill(0);
+    let clock = Clock::get()?;
+    log_revoke_delegate(
+        signer.key,
+        &delegate_key,
+        clock.unix_timestamp as u32,
+        clock.slot,
+    );
+    Ok(())
+}
@@ -0,0 +1,227 @@
+use crate::program::*;
+use crate::state::*;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction, system_program,
+    sysvar::Sysvar,
+};
+use spl_associated_token_account::{
+    get_associated_token_address_with_program_id, instruction::create_associated_token_account,
+};
+use std::convert::TryInto;
+
+pub unsafe fn set_delegate(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    // Set Delegate Instruction
+    // #1 - Wallet (Signer, Writable)
+    // #2 - Client Account
+    // #3 - Delegate Key
+    // #4 - Delegate Account (Writable)
+    // #5 - Client Associated Token Account (Writable)
+    // #6 - Base Crncy Mint
+    // #7 - Base Crncy Token Program
+    // #8 - Token 2022 Program
+    // #9 - System Program
+    // #10 - Associated Token Program
+    // #11.. - Pairs of a Hype Mint and the Client Associated Hype Account (Writable) the
+    //         delegate may trade (Optional)
+    // [0..8] - 24
+    // [8..16] - Spend limit per period (0 for no limit)
+    // [16..24] - Allowed networks bitmask (u64::MAX for every network)
+    // [24..28] - Period in seconds (0 for the whole lifetime)
+    // [28..32] - Expiry unix time (0 for no expiry)
+    // Creates or replaces the limits of the delegate and makes its delegate account
+    // the SPL delegate of the given token accounts, creating missing hype accounts.
+    // Delegated buys only go into hype accounts approved here.
+    if instruction_data.len() < 32 {
+        return Err(InvalidDataLength.into());
+    }
+    let accounts_iter = &mut accounts.iter();
+    let signer = next_account_info(accounts_iter)?;
+    let client_acc = next_account_info(accounts_iter)?;
+    let delegate_key_acc = next_account_info(accounts_iter)?;
+    let delegate_acc = next_account_info(accounts_iter)?;
+    let client_associated_token_acc = next_account_info(accounts_iter)?;
+    let base_crncy_mint_acc = next_account_info(accounts_iter)?;
+    let base_crncy_token_program_id = next_account_info(accounts_iter)?;
+    let token_2022_program_id = next_account_info(accounts_iter)?;
+    let system_program_acc = next_account_info(accounts_iter)?;
+    let associated_token_id = next_account_info(accounts_iter)?;
+    if !signer.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if *base_crncy_token_program_id.key != spl_token::id()
+        && *base_crncy_token_program_id.key != spl_token_2022::id()
+    {
+        return Err(InvalidTokenProgramId.into());
+    }
+    if *token_2022_program_id.key != spl_token_2022::id() {
+        return Err(InvalidToken2022ProgramId.into());
+    }
+    if !system_program::check_id(system_program_acc.key) {
+        return Err(InvalidSystemProgramId.into());
+    }
+    if *associated_token_id.key != spl_associated_token_account::id() {
+        return Err(InvalidAssociatedTokenId.into());
+    }
+    if client_acc.owner != program_id {
+        return Err(InvalidClientAccount.into());
+    }
+    check_account_size(client_acc, CLIENT_ACCOUNT_SIZE)?;
+    let client = client_acc.data.borrow().as_ptr() as *const ClientAccount;
+    if (*client).tag != CLIENT_TAG || (*client).wallet != *signer.key {
+        return Err(InvalidClientAccount.into());
+    }
+    if delegate_key_acc.key == signer.key {
+        return Err(InvalidDelegateAccount.into());
+    }
+    let expected_address = get_associated_token_address_with_program_id(
+        signer.key,
+        base_crncy_mint_acc.key,
+        base_crncy_token_program_id.key,
+    );
+    if expected_address != *client_associated_token_acc.key {
+        return Err(InvalidAssociatedTokenAddress.into());
+    }
+    let spend_limit = u64::from_le_bytes(instruction_data[8..16].try_into().unwrap());
+    let networks = u64::from_le_bytes(instruction_data[16..24].try_into().unwrap());
+    let period = u32::from_le_bytes(instruction_data[24..28].try_into().unwrap());
+    let expiry = u32::from_le_bytes(instruction_data[28..32].try_into().unwrap());
+    let clock = Clock::get()?;
+    let time = clock.unix_timestamp as u32;
+    if expiry != 0 && expiry <= time {
+        return Err(DelegateExpired.into());
+    }
+    let delegate_seed = get_seed_by_tag((*client).version, DELEGATE_TAG);
+    let (delegate_address, delegate_bump_seed) = Pubkey::find_program_address(
+        &[
+            &delegate_seed,
+            signer.key.as_ref(),
+            delegate_key_acc.key.as_ref(),
+        ],
+        program_id,
+    );
+    if delegate_address != *delegate_acc.key {
+        return Err(InvalidAccountKey.into());
+    }
+    if delegate_acc.owner == &system_program::ID {
+        let rent = &Rent::default();
+        let delegate_lamports = rent.minimum_balance(DELEGATE_ACCOUNT_SIZE);
+        invoke_signed(
+            &system_instruction::create_account(
+                signer.key,
+                delegate_acc.key,
+                delegate_lamports,
+                DELEGATE_ACCOUNT_SIZE as u64,
+                program_id,
+            ),
+            &[signer.clone(), delegate_acc.clone()],
+            &[&[
+                &delegate_seed,
+                signer.key.as_ref(),
+                delegate_key_acc.key.as_ref(),
+                &[delegate_bump_seed],
+            ]],
+        )?;
+    } else {
+        load_delegate_account(program_id, delegate_acc, signer.key, delegate_key_acc.key)?;
+    }
+    let delegate = delegate_acc.data.borrow().as_ptr() as *mut DelegateAccount;
+    *delegate = DelegateAccount {
+        tag: DELEGATE_TAG,
+        version: (*client).version,
+        wallet: *signer.key,
+        delegate: *delegate_key_acc.key,
+        spend_limit: spend_limit,
+        spent: 0,
+        networks: networks,
+        period: period,
+        period_start: time,
+        expiry: expiry,
+        bump_seed: delegate_bump_seed as u32,
+        slot: clock.slot,
+        time: time,
+        trades_count: 0,
+    };
+    invoke(
+        &spl_token_2022::instruction::approve(
+            base_crncy_token_program_id.key,
+            client_associated_token_acc.key,
+            delegate_acc.key,
+            signer.key,
+            &[signer.key],
+            u64::MAX,
+        )?,
+        &[
+            client_associated_token_acc.clone(),
+            delegate_acc.clone(),
+            signer.clone(),
+            base_crncy_token_program_id.clone(),
+        ],
+    )?;
+    while let Some(hype_mint_acc) = accounts_iter.next() {
+        let client_associated_hype_acc = next_account_info(accounts_iter)?;
+        let expected_hype_address = get_associated_token_address_with_program_id(
+            signer.key,
+            hype_mint_acc.key,
+            token_2022_program_id.key,
+        );
+        if expected_hype_address != *client_associated_hype_acc.key {
+            return Err(InvalidAssociatedTokenAddress.into());
+        }
+        if *client_associated_hype_acc.owner == system_program::ID {
+            invoke(
+                &create_associated_token_account(
+                    signer.key,
+                    signer.key,
+                    hype_mint_acc.key,
+                    token_2022_program_id.key,
+                ),
+                &[
+                    signer.clone(),
+                    client_associated_hype_acc.clone(),
+                    signer.clone(),
+                    hype_mint_acc.clone(),
+                    token_2022_program_id.clone(),
+                ],
+            )?;
+        }
+        invoke(
+            &spl_token_2022::instruction::approve(
+                token_2022_program_id.key,
+                client_associated_hype_acc.key,
+                delegate_acc.key,
+                signer.key,
+                &[signer.key],
+                u64::MAX,
+            )?,
+            &[
+                client_associated_hype_acc.clone(),
+                delegate_acc.clone(),
+                signer.clone(),
+                token_2022_program_id.clone(),
+            ],
+        )?;
+    }
+    log_set_delegate(
+        (*client).id,
+        signer.key,
+        delegate_key_acc.key,
+        spend_limit,
+        networks,
+        period,
+        expiry,
+        time,
+        clock.slot,
+    );
+    Ok(())
+}
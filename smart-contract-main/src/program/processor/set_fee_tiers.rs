@@ -0,0 +1,74 @@
+use crate::program::*;
+use crate::state::*;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+};
+use std::convert::TryInto;
+
+pub unsafe fn set_fee_tiers(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    // Set Fee Tiers Instruction
+    // #1 - Admin (Signer)
+    // #2 - Root Account (Writable)
+    // [0..8] - 15
+    // [8..12] - Tiers count
+    // [12..16] - Reserved
+    // [16..] - Tiers: min volume (u64) and fee rate (f64), ordered by min volume
+    if instruction_data.len() < 16 {
+        return Err(InvalidDataLength.into());
+    }
+    let count = u32::from_le_bytes(instruction_data[8..12].try_into().unwrap()) as usize;
+    if count > MAX_FEE_TIERS {
+        return Err(InvalidFeeTiers.into());
+    }
+    if instruction_data.len() < 16 + count * FEE_TIER_SIZE {
+        return Err(InvalidDataLength.into());
+    }
+    let account_info_iter = &mut accounts.iter();
+    let admin = next_account_info(account_info_iter)?;
+    let root_acc = next_account_info(account_info_iter)?;
+    if !admin.is_signer {
+        return Err(AdminSignatureRequired.into());
+    }
+    let root = load_root_account(program_id, root_acc)?;
+    if (*root).admin != *admin.key {
+        return Err(InvalidAdmin.into());
+    }
+    let mut fee_tiers = [FeeTier::default(); MAX_FEE_TIERS];
+    for (i, tier) in fee_tiers[..count].iter_mut().enumerate() {
+        let offset = 16 + i * FEE_TIER_SIZE;
+        tier.min_volume =
+            u64::from_le_bytes(instruction_data[offset..offset + 8].try_into().unwrap());
+        tier.fee_rate = f64::from_le_bytes(
+            instruction_data[offset + 8..offset + 16]
+                .try_into()
+                .unwrap(),
+        );
+    }
+    // Each tier needs more volume than the one below and may only lower the fee rate
+    let mut prev = FeeTier {
+        min_volume: 0,
+        fee_rate: (*root).fee_rate,
+    };
+    for (i, tier) in fee_tiers[..count].iter().enumerate() {
+        if (i > 0 && tier.min_volume <= prev.min_volume)
+            || !(0.0..=prev.fee_rate).contains(&tier.fee_rate)
+        {
+            return Err(InvalidFeeTiers.into());
+        }
+        prev = *tier;
+    }
+    (*root).fee_tiers_count = count as u32;
+    (*root).fee_tiers = fee_tiers;
+    let clock = Clock::get()?;
+    (*root).slot = clock.slot;
+    (*root).time = clock.unix_timestamp as u32;
+    Ok(())
+}
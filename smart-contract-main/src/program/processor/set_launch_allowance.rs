@@ -0,0 +1,93 @@
+use crate::program::*;
+use crate::state::*;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    program::invoke_signed,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction, system_program,
+    sysvar::Sysvar,
+};
+use std::convert::TryInto;
+
+pub unsafe fn set_launch_allowance(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    // Set Launch Allowance Instruction
+    // #1 - Admin (Signer, Writable)
+    // #2 - Root Account
+    // #3 - Wallet
+    // #4 - Launch Allowance Account (Writable)
+    // #5 - System Program
+    // [0..8] - 27
+    // [8..12] - 1 to let the wallet buy in restricted launch windows, 0 to stop it
+    if instruction_data.len() < 12 {
+        return Err(InvalidDataLength.into());
+    }
+    let accounts_iter = &mut accounts.iter();
+    let admin = next_account_info(accounts_iter)?;
+    let root_acc = next_account_info(accounts_iter)?;
+    let wallet = next_account_info(accounts_iter)?;
+    let launch_allowance_acc = next_account_info(accounts_iter)?;
+    let system_program_acc = next_account_info(accounts_iter)?;
+    if !admin.is_signer {
+        return Err(AdminSignatureRequired.into());
+    }
+    if !system_program::check_id(system_program_acc.key) {
+        return Err(InvalidSystemProgramId.into());
+    }
+    let root = load_root_account(program_id, root_acc)?;
+    if (*root).admin != *admin.key {
+        return Err(InvalidAdmin.into());
+    }
+    let launch_allowance_seed = get_seed_by_tag((*root).version, LAUNCH_ALLOWANCE_TAG);
+    let (launch_allowance_address, launch_allowance_bump_seed) =
+        Pubkey::find_program_address(&[&launch_allowance_seed, wallet.key.as_ref()], program_id);
+    if launch_allowance_address != *launch_allowance_acc.key {
+        return Err(InvalidAccountKey.into());
+    }
+    let allowed = u32::from_le_bytes(instruction_data[8..12].try_into().unwrap()) != 0;
+    let exists = launch_allowance_acc.owner == program_id;
+    if allowed && !exists {
+        let rent = &Rent::default();
+        let launch_allowance_lamports = rent.minimum_balance(LAUNCH_ALLOWANCE_ACCOUNT_SIZE);
+        invoke_signed(
+            &system_instruction::create_account(
+                admin.key,
+                launch_allowance_acc.key,
+                launch_allowance_lamports,
+                LAUNCH_ALLOWANCE_ACCOUNT_SIZE as u64,
+                program_id,
+            ),
+            &[admin.clone(), launch_allowance_acc.clone()],
+            &[&[
+                &launch_allowance_seed,
+                wallet.key.as_ref(),
+                &[launch_allowance_bump_seed],
+            ]],
+        )?;
+        let clock = Clock::get()?;
+        *(launch_allowance_acc.data.borrow().as_ptr() as *mut LaunchAllowanceAccount) =
+            LaunchAllowanceAccount {
+                tag: LAUNCH_ALLOWANCE_TAG,
+                version: (*root).version,
+                wallet: *wallet.key,
+                slot: clock.slot,
+                time: clock.unix_timestamp as u32,
+                reserved: 0,
+            };
+    } else if !allowed && exists {
+        let lamports = launch_allowance_acc.lamports();
+        **admin.lamports.borrow_mut() = admin
+            .lamports()
+            .checked_add(lamports)
+            .ok_or(ArithmeticOverflow)?;
+        **launch_allowance_acc.lamports.borrow_mut() = 0;
+        launch_allowance_acc.data.borrow_mut().fill(0);
+    }
+    Ok(())
+}
@@ -0,0 +1,96 @@
+use crate::program::*;
+use crate::state::*;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_program,
+    sysvar::Sysvar,
+};
+
+pub unsafe fn set_referrer(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    // Set Referrer Instruction
+    // #1 - Wallet (Signer)
+    // #2 - Client Account (Writable)
+    // #3 - Root Account
+    // #4 - Referrer Wallet or Ref Code Account
+    // #5 - Referrer Client Account
+    // [0] - 14
+    // Only within the root's ref grace period after the client creation; a period of 0
+    // disables the instruction.
+    let accounts_iter = &mut accounts.iter();
+    let signer = next_account_info(accounts_iter)?;
+    let client_acc = next_account_info(accounts_iter)?;
+    let root_acc = next_account_info(accounts_iter)?;
+    let ref_acc = next_account_info(accounts_iter)?;
+    let ref_client_acc = next_account_info(accounts_iter)?;
+    if !signer.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    let root = load_root_account(program_id, root_acc)?;
+    if client_acc.owner != program_id {
+        return Err(InvalidClientAccount.into());
+    }
+    check_account_size(client_acc, CLIENT_ACCOUNT_SIZE)?;
+    let client = client_acc.data.borrow().as_ptr() as *mut ClientAccount;
+    if (*client).tag != CLIENT_TAG
+        || (*client).wallet != *signer.key
+        || (*client).version != (*root).version
+    {
+        return Err(InvalidClientAccount.into());
+    }
+    let clock = Clock::get()?;
+    let time = clock.unix_timestamp as u32;
+    // A client that referred itself never had a real referral
+    if (*client).ref_stop > time && (*client).ref_address != *signer.key {
+        return Err(ReferralAlreadyActive.into());
+    }
+    if time
+        > (*client)
+            .creation_time
+            .saturating_add((*root).ref_grace_period)
+    {
+        return Err(RefGracePeriodExpired.into());
+    }
+    let ref_wallet = resolve_ref_wallet(program_id, &*root, ref_acc)?;
+    if ref_wallet == *signer.key {
+        return Err(SelfReferral.into());
+    }
+    if ref_wallet == system_program::ID || ref_acc.lamports() == 0 {
+        return Err(InvalidRefAddress.into());
+    }
+    let client_seed = get_seed_by_tag((*root).version, CLIENT_TAG);
+    check_account(ref_client_acc, &ref_wallet, program_id, &client_seed)?;
+    if ref_client_acc.owner == program_id {
+        check_account_size(ref_client_acc, CLIENT_ACCOUNT_SIZE)?;
+        let ref_client = ref_client_acc.data.borrow().as_ptr() as *const ClientAccount;
+        if (*ref_client).tag != CLIENT_TAG {
+            return Err(InvalidClientAccount.into());
+        }
+        if (*ref_client).ref_address == *signer.key
+            || (*ref_client).ref_uplines.contains(signer.key)
+        {
+            return Err(ReferralCycle.into());
+        }
+    }
+    (*client).ref_uplines =
+        resolve_ref_uplines(program_id, &*root, ref_client_acc, &ref_wallet, signer.key)?;
+    (*client).ref_address = ref_wallet;
+    (*client).ref_stop = time + (*root).ref_duration;
+    (*client).ref_discount = (*root).ref_discount;
+    (*client).ref_ratio = (*root).ref_ratio;
+    (*client).ref_upline_ratios = (*root).ref_upline_ratios;
+    (*client).slot = clock.slot;
+    (*client).time = time;
+    log_set_referrer(
+        (*client).id,
+        signer.key,
+        &ref_wallet,
+        (*client).ref_stop,
+        time,
+        clock.slot,
+    );
+    Ok(())
+}
@@ -0,0 +1,171 @@
+use crate::program::*;
+use crate::state::*;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    pubkey::Pubkey,
+};
+use std::convert::TryInto;
+
+pub unsafe fn swap(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    // Swap Instruction
+    // #1 - Pool Account (Writable)
+    // #2..#15 - Accounts of `cpi_trade`
+    // Delegate, referral and extras accounts as in `cpi_trade`, without the launch ones
+    // [0..8] - 29
+    // [8..12] - Side (0 - buy, 1 - sell)
+    // [12..16] - Extras flags (see `next_trade_extras`)
+    // [16..24] - Amount paid in: base currency with fees for a buy, tokens for a sell
+    // [24..32] - Min amount received (0 for no limit)
+    // [32..40] - Valid until slot (Optional, 0 for no limit)
+    // [40..44] - Valid until unix time (Optional, 0 for no limit)
+    // Trades a graduated token against its constant-product pool. The pool fee is
+    // taken in base currency and split like the curve's fees. The client's associated
+    // token accounts have to exist.
+    if instruction_data.len() < 32 {
+        return Err(InvalidDataLength.into());
+    }
+    let accounts_iter = &mut accounts.iter();
+    let pool_acc = next_account_info(accounts_iter)?;
+    let ctx = Context::new_cpi(program_id, accounts_iter, instruction_data)?;
+    ctx.check_valid_until(instruction_data, 32)?;
+    let side = u32::from_le_bytes(instruction_data[8..12].try_into().unwrap());
+    let amount = u64::from_le_bytes(instruction_data[16..24].try_into().unwrap());
+    let min_amount_out = u64::from_le_bytes(instruction_data[24..32].try_into().unwrap());
+    let extras = next_trade_extras(accounts_iter, read_trade_extras(instruction_data, 12)?)?;
+    if extras.launch_allowance.is_some() || extras.launch_buy.is_some() {
+        return Err(InvalidTradeExtras.into());
+    }
+    let token_account = ctx.load_token_account(program_id)?;
+    let records = ctx.load_trade_records(program_id, &*token_account, &extras)?;
+    let pool = load_pool_account(program_id, pool_acc, ctx.hype_mint_acc.key)?;
+    if (*pool).token != *ctx.token_acc.key {
+        return Err(InvalidPoolAccount.into());
+    }
+    let price_before = (*pool).price();
+    let fee_rate = (*ctx.root).pool_fee_rate;
+    let creator_fee_ratio = ctx.creator_fee_ratio(&*token_account)?;
+    let (base_crncy_amount, tokens_amount, amount_out, fee_split) = match side {
+        BATCH_SIDE_BUY => {
+            let fees = pool_fees(fee_rate, amount);
+            let fee_split = ctx
+                .fee_split(fees)
+                .with_creator_fees(fees, creator_fee_ratio);
+            let base_crncy_in = amount - fee_split.total();
+            let tokens_out = swap_amount_out(
+                (*pool).base_crncy_reserve,
+                (*pool).token_reserve,
+                base_crncy_in,
+            )?;
+            (base_crncy_in, tokens_out, tokens_out, fee_split)
+        }
+        BATCH_SIDE_SELL => {
+            let base_crncy_out =
+                swap_amount_out((*pool).token_reserve, (*pool).base_crncy_reserve, amount)?;
+            let fees = pool_fees(fee_rate, base_crncy_out);
+            let fee_split = ctx
+                .fee_split(fees)
+                .with_creator_fees(fees, creator_fee_ratio);
+            (
+                base_crncy_out,
+                amount,
+                base_crncy_out - fee_split.total(),
+                fee_split,
+            )
+        }
+        _ => return Err(InvalidInstruction.into()),
+    };
+    if amount_out == 0 {
+        return Err(TooSmallQuantity.into());
+    }
+    if amount_out < min_amount_out {
+        return Err(MaxTradeCostExceeded.into());
+    }
+    ctx.accrue_ref_fees(&fee_split)?;
+    ctx.accrue_creator_fees(&mut *token_account, &fee_split)?;
+    if !ctx.delegate.is_null() {
+        let spend = if side == BATCH_SIDE_BUY { amount } else { 0 };
+        (*ctx.delegate).add_trade((*token_account).network, spend, ctx.slot, ctx.time)?;
+    }
+    if side == BATCH_SIDE_BUY {
+        ctx.check_delegate_approved()?;
+        ctx.deposit_base_crncy(amount)?;
+        ctx.withdraw_hype(tokens_amount)?;
+        (*pool).base_crncy_reserve += base_crncy_amount;
+        (*pool).token_reserve -= tokens_amount;
+        (*ctx.root).pool_reserves = (*ctx.root)
+            .pool_reserves
+            .checked_add(base_crncy_amount)
+            .ok_or(ArithmeticOverflow)?;
+    } else {
+        ctx.deposit_hype(tokens_amount)?;
+        ctx.withdraw_base_crncy(amount_out)?;
+        (*pool).base_crncy_reserve -= base_crncy_amount;
+        (*pool).token_reserve = (*pool)
+            .token_reserve
+            .checked_add(tokens_amount)
+            .ok_or(ArithmeticOverflow)?;
+        if (*ctx.root).pool_reserves < base_crncy_amount {
+            return Err(InvalidTVL.into());
+        }
+        (*ctx.root).pool_reserves -= base_crncy_amount;
+    }
+    (*pool).all_time_base_crncy_volume += base_crncy_amount;
+    (*pool).all_time_tokens_volume += tokens_amount;
+    (*pool).swaps_count += 1;
+    (*pool).slot = ctx.slot;
+    (*pool).time = ctx.time;
+    (*ctx.root).all_time_base_crncy_volume += base_crncy_amount as u128;
+    (*ctx.root).all_time_tokens_volume += tokens_amount as u128;
+    (*ctx.root).counter += 1;
+    (*ctx.root).fees += fee_split.operator_fees;
+    (*ctx.root).holder_fees += fee_split.holder_fees;
+    (*ctx.root).slot = ctx.slot;
+    (*ctx.root).time = ctx.time;
+    (*ctx.client).all_time_trades_count += 1;
+    (*ctx.client).all_time_base_crncy_volume += base_crncy_amount;
+    (*ctx.client).all_time_tokens_volume += tokens_amount;
+    (*ctx.client).slot = ctx.slot;
+    (*ctx.client).time = ctx.time;
+    (*token_account).all_time_trades_count += 1;
+    (*token_account).all_time_base_crncy_volume += base_crncy_amount as u128;
+    (*token_account).all_time_tokens_volume += tokens_amount as u128;
+    (*token_account).slot = ctx.slot;
+    (*token_account).time = ctx.time;
+    (*token_account).update_twap((*pool).price(), ctx.time);
+    let fill = Fill {
+        amount: tokens_amount,
+        base_crncy_amount: base_crncy_amount,
+        payment: if side == BATCH_SIDE_BUY {
+            amount
+        } else {
+            amount_out
+        },
+        price_before: price_before,
+        price_after: (*pool).price(),
+    };
+    if side == BATCH_SIDE_BUY {
+        records.record_buy(&fill, ctx.slot, ctx.time)?;
+    } else {
+        records.record_sell(&fill, ctx.slot, ctx.time)?;
+    }
+    log_swap(
+        (*ctx.client).id,
+        (*ctx.root).counter,
+        (*token_account).id,
+        ctx.signer.key,
+        side,
+        tokens_amount,
+        base_crncy_amount,
+        fee_split.total(),
+        (*pool).base_crncy_reserve,
+        (*pool).token_reserve,
+        ctx.time,
+        ctx.slot,
+    );
+    Ok(())
+}
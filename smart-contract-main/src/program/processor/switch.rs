@@ -0,0 +1,260 @@
+use crate::program::*;
+use crate::state::*;
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, program::invoke, program::invoke_signed,
+    pubkey::Pubkey,
+};
+use std::convert::TryInto;
+
+pub unsafe fn switch(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    // Switch Instruction
+    // #1..#17 - Trade accounts of `burn` for the token given up
+    // Referral accounts as in `burn`, then for the token received:
+    // #1 - Token Account (Writable)
+    // #2 - Hype Mint (Writable)
+    // #3 - Hype Program Account (Writable)
+    // #4 - Client Associated Hype Account (Writable)
+    // Then the accounts declared in the extras flags of the token received, followed by
+    // those of the token given up
+    // [0..8] - 22
+    // [8..16] - Amount given up
+    // [16..24] - Min amount received (0 for no limit)
+    // [24..32] - Valid until slot (Optional, 0 for no limit)
+    // [32..36] - Valid until unix time (Optional, 0 for no limit)
+    // [36..40] - Extras flags of the token received (Optional, see `next_trade_extras`)
+    // [40..44] - Extras flags of the token given up (Optional, see `next_trade_extras`)
+    // The cash out of the first token stays in the vault and buys the second one,
+    // less a single `switch_fee_rate` fee, discounted for referred clients.
+    if instruction_data.len() < 24 {
+        return Err(InvalidDataLength.into());
+    }
+    let accounts_iter = &mut accounts.iter();
+    // Clients created by a switch start without a nickname
+    let ctx = Context::new(
+        program_id,
+        accounts_iter,
+        instruction_data,
+        instruction_data.len(),
+    )?;
+    ctx.check_valid_until(instruction_data, 24)?;
+    ctx.check_wallet_signed()?;
+    let from = ctx;
+    let to = ctx.next_token(accounts_iter)?;
+    let to_extras = next_trade_extras(accounts_iter, read_trade_extras(instruction_data, 36)?)?;
+    let from_extras = next_trade_extras(accounts_iter, read_trade_extras(instruction_data, 40)?)?;
+    // Launch allowances only lift limits on buys
+    if from_extras.launch_allowance.is_some() || from_extras.launch_buy.is_some() {
+        return Err(InvalidTradeExtras.into());
+    }
+    let to = to.with_launch_extras(program_id, &to_extras)?;
+    if from.token_acc.key == to.token_acc.key {
+        return Err(InvalidTokenAccount.into());
+    }
+    let from_token = from.load_token_account(program_id)?;
+    let to_token = to.load_token_account(program_id)?;
+    let from_records = from.load_trade_records(program_id, &*from_token, &from_extras)?;
+    let to_records = to.load_trade_records(program_id, &*to_token, &to_extras)?;
+    let from_supply = read_mint_supply(&from.hype_mint_acc.data.borrow())?;
+    let to_supply = read_mint_supply(&to.hype_mint_acc.data.borrow())?;
+    if from_supply != (*from_token).supply || to_supply != (*to_token).supply {
+        return Err(InvalidTokenSupply.into());
+    }
+    from.check_curve_open(&*from_token)?;
+    to.check_curve_open(&*to_token)?;
+    let amount = u64::from_le_bytes(instruction_data[8..16].try_into().unwrap());
+    let min_amount_out = u64::from_le_bytes(instruction_data[16..24].try_into().unwrap());
+    let curve_params = CurveParams::from_root(&*ctx.root);
+    let base_crncy_cashout = quote_burn(&curve_params, from_supply, amount)?.base_crncy_amount;
+    let switch_fee =
+        ((base_crncy_cashout as f64 * (*ctx.root).switch_fee_rate) as u64).min(base_crncy_cashout);
+    let fee_split = ctx
+        .fee_split(switch_fee)
+        .with_creator_fees(switch_fee, ctx.creator_fee_ratio(&*to_token)?);
+    // A referral discount stays in the budget for the second token
+    let budget = base_crncy_cashout - fee_split.total();
+    let amount_out = mint_amount_for_funds(&curve_params, to_supply, budget)?;
+    if amount_out == 0 {
+        return Err(TooSmallQuantity.into());
+    }
+    // A switch spends its whole budget, so it can not stop at the graduation reserve
+    to.check_graduation_reserve(&*to_token, amount_out)?;
+    if amount_out < min_amount_out {
+        return Err(MaxTradeCostExceeded.into());
+    }
+    to.check_launch(&*to_token, amount_out)?;
+    let base_crncy_cost = quote_mint(&curve_params, to_supply, amount_out)?.base_crncy_amount;
+    // The part of the budget too small for another token goes to the operator
+    let dust = budget - base_crncy_cost;
+    let fees = base_crncy_cashout - base_crncy_cost;
+    ctx.accrue_ref_fees(&fee_split)?;
+    ctx.accrue_creator_fees(&mut *to_token, &fee_split)?;
+    invoke(
+        &spl_token_2022::instruction::transfer_checked(
+            &spl_token_2022::id(),
+            from.client_associated_hype_acc.key,
+            from.hype_mint_acc.key,
+            from.hype_program_acc.key,
+            ctx.signer.key,
+            &[ctx.signer.key],
+            amount,
+            (*ctx.root).decimals as u8,
+        )?,
+        &[
+            ctx.token_2022_program_id.clone(),
+            from.client_associated_hype_acc.clone(),
+            from.hype_mint_acc.clone(),
+            from.hype_program_acc.clone(),
+            ctx.signer.clone(),
+        ],
+    )?;
+    invoke_signed(
+        &spl_token_2022::instruction::burn(
+            &spl_token_2022::ID,
+            from.hype_program_acc.key,
+            from.hype_mint_acc.key,
+            ctx.hype_auth_acc.key,
+            &[ctx.hype_auth_acc.key],
+            amount,
+        )?,
+        &[
+            from.hype_mint_acc.clone(),
+            from.hype_program_acc.clone(),
+            ctx.hype_auth_acc.clone(),
+        ],
+        &[&[&HYPE_SEED[..], &[ctx.hype_bump_seed]]],
+    )?;
+    to.create_client_hype_account()?;
+    invoke_signed(
+        &spl_token_2022::instruction::mint_to(
+            &spl_token_2022::ID,
+            to.hype_mint_acc.key,
+            to.client_associated_hype_acc.key,
+            ctx.hype_auth_acc.key,
+            &[ctx.hype_auth_acc.key],
+            amount_out,
+        )?,
+        &[
+            to.hype_mint_acc.clone(),
+            to.client_associated_hype_acc.clone(),
+            ctx.hype_auth_acc.clone(),
+        ],
+        &[&[&HYPE_SEED[..], &[ctx.hype_bump_seed]]],
+    )?;
+    (*ctx.root).all_time_base_crncy_volume += (base_crncy_cashout + base_crncy_cost) as u128;
+    (*ctx.root).all_time_tokens_volume += amount as u128 + amount_out as u128;
+    (*ctx.root).counter += 1;
+    (*ctx.root).fees += fee_split.operator_fees + dust;
+    (*ctx.root).holder_fees += fee_split.holder_fees;
+    (*ctx.root).slot = ctx.slot;
+    (*ctx.root).time = ctx.time;
+    if (*ctx.root).supply < amount {
+        return Err(InvalidTotalSupply.into());
+    }
+    (*ctx.root).supply = ((*ctx.root).supply - amount)
+        .checked_add(amount_out)
+        .ok_or(ArithmeticOverflow)?;
+    if (*ctx.root).tvl < base_crncy_cashout {
+        return Err(InvalidTVL.into());
+    }
+    (*ctx.root).tvl = (*ctx.root).tvl - base_crncy_cashout + base_crncy_cost;
+    (*ctx.client).all_time_trades_count += 2;
+    (*ctx.client).all_time_base_crncy_volume += base_crncy_cashout + base_crncy_cost;
+    (*ctx.client).all_time_tokens_volume += amount + amount_out;
+    (*ctx.client).slot = ctx.slot;
+    (*ctx.client).time = ctx.time;
+    (*from_token).all_time_trades_count += 1;
+    (*from_token).all_time_base_crncy_volume += base_crncy_cashout as u128;
+    (*from_token).all_time_tokens_volume += amount as u128;
+    (*from_token).supply -= amount;
+    (*from_token).slot = ctx.slot;
+    (*from_token).time = ctx.time;
+    (*from_token).update_twap(spot_price(&curve_params, (*from_token).supply)?, ctx.time);
+    (*to_token).all_time_trades_count += 1;
+    (*to_token).all_time_base_crncy_volume += base_crncy_cost as u128;
+    (*to_token).all_time_tokens_volume += amount_out as u128;
+    (*to_token).supply += amount_out;
+    (*to_token).slot = ctx.slot;
+    (*to_token).time = ctx.time;
+    (*to_token).update_twap(spot_price(&curve_params, (*to_token).supply)?, ctx.time);
+    // The sale yields the budget left after the switch fee, all of which pays for the purchase
+    from_records.record_sell(
+        &Fill {
+            amount: amount,
+            base_crncy_amount: base_crncy_cashout,
+            payment: budget,
+            price_before: spot_price(&curve_params, from_supply)?,
+            price_after: spot_price(&curve_params, (*from_token).supply)?,
+        },
+        ctx.slot,
+        ctx.time,
+    )?;
+    to_records.record_buy(
+        &Fill {
+            amount: amount_out,
+            base_crncy_amount: base_crncy_cost,
+            payment: budget,
+            price_before: spot_price(&curve_params, to_supply)?,
+            price_after: spot_price(&curve_params, (*to_token).supply)?,
+        },
+        ctx.slot,
+        ctx.time,
+    )?;
+    log_burn(
+        (*ctx.client).id,
+        (*ctx.root).counter,
+        (*from_token).id,
+        (*from_token).network,
+        &(*from_token).mint,
+        &(*from_token).creator,
+        ctx.signer.key,
+        &(*from_token).address,
+        (*from_token).creation_time,
+        (*from_token).supply,
+        (*from_token).all_time_trades_count,
+        (*from_token).all_time_base_crncy_volume as u64,
+        (*from_token).all_time_tokens_volume as u64,
+        amount,
+        base_crncy_cashout,
+        ctx.time,
+        ctx.slot,
+        0,
+    );
+    log_mint(
+        (*ctx.client).id,
+        (*ctx.root).counter,
+        (*to_token).id,
+        (*to_token).network,
+        &(*to_token).mint,
+        &(*to_token).creator,
+        ctx.signer.key,
+        &(*to_token).address,
+        (*to_token).creation_time,
+        (*to_token).supply,
+        (*to_token).all_time_trades_count,
+        (*to_token).all_time_base_crncy_volume as u64,
+        (*to_token).all_time_tokens_volume as u64,
+        amount_out,
+        base_crncy_cost,
+        ctx.time,
+        ctx.slot,
+        0,
+    );
+    log_switch(
+        (*ctx.client).id,
+        (*ctx.root).counter,
+        (*from_token).id,
+        (*to_token).id,
+        ctx.signer.key,
+        amount,
+        amount_out,
+        base_crncy_cashout,
+        fees,
+        ctx.time,
+        ctx.slot,
+    );
+    Ok(())
+}
@@ -4,12 +4,8 @@ use solana_program::{
     account_info::{next_account_info, AccountInfo},
     entrypoint::ProgramResult,
     //msg,
-    program::invoke,
-    program::invoke_signed,
     pubkey::Pubkey,
-    system_program,
 };
-use spl_associated_token_account::{instruction::create_associated_token_account, *};
 
 pub unsafe fn withdraw_operator_funds(
     program_id: &Pubkey,
@@ -24,57 +20,29 @@ pub unsafe fn withdraw_operator_funds(
     let associated_token_acc = next_account_info(accounts_iter)?;
     let token_program_id = next_account_info(accounts_iter)?;
     let hype_auth_acc = next_account_info(accounts_iter)?;
-    let root = root_acc.data.borrow().as_ptr() as *mut RootAccount;
-    if (*root).tag != ROOT_TAG {
-        return Err(InvalidAccountTag.into());
-    }
+    let root = load_root_account(program_id, root_acc)?;
     if (*root).admin != *admin.key {
         return Err(InvalidAdmin.into());
     }
     if (*root).fee_wallet != *fee_wallet.key {
         return Err(InvalidFeeWallet.into());
     }
-    if *associated_token_acc.owner == system_program::ID {
-        invoke(
-            &create_associated_token_account(
-                admin.key,
-                fee_wallet.key,
-                base_crncy_mint.key,
-                token_program_id.key,
-            ),
-            &[
-                admin.clone(),
-                associated_token_acc.clone(),
-                fee_wallet.clone(),
-                base_crncy_mint.clone(),
-                token_program_id.clone(),
-            ],
-        )?;
+    if (*root).base_crncy_program_address != *base_crncy_program_acc.key {
+        return Err(InvalidBaseCrncyProgramAddress.into());
     }
-    let (hype_auth, hype_bump_seed) = Pubkey::find_program_address(&[HYPE_SEED], program_id);
-    if hype_auth != *hype_auth_acc.key {
-        return Err(InvalidHypeAuthority.into());
+    if *base_crncy_program_acc.owner != *token_program_id.key {
+        return Err(InvalidTokenProgramId.into());
     }
-    if (*root).fees > 0 {
-        let transfer_to_taker_ix = spl_token::instruction::transfer(
-            &spl_token::id(),
-            base_crncy_program_acc.key,
-            associated_token_acc.key,
-            &hype_auth,
-            &[&hype_auth],
-            (*root).fees,
-        )?;
-        invoke_signed(
-            &transfer_to_taker_ix,
-            &[
-                base_crncy_program_acc.clone(),
-                associated_token_acc.clone(),
-                hype_auth_acc.clone(),
-                token_program_id.clone(),
-            ],
-            &[&[&HYPE_SEED[..], &[hype_bump_seed]]],
-        )?;
+    VaultPayout {
+        payer: admin,
+        owner: fee_wallet,
+        base_crncy_mint_acc: base_crncy_mint,
+        base_crncy_program_acc: base_crncy_program_acc,
+        associated_token_acc: associated_token_acc,
+        token_program_id: token_program_id,
+        hype_auth_acc: hype_auth_acc,
     }
+    .pay(program_id, &*root, (*root).fees)?;
     (*root).fees = 0;
     Ok(())
 }
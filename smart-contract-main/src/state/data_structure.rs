@@ -1,4 +1,5 @@
 use super::log_new_client;
+use crate::program::error::HypeError;
 use crate::program::*;
 use core::slice::Iter;
 use solana_program::{
@@ -6,7 +7,8 @@ use solana_program::{
     clock::Clock,
     entrypoint::ProgramResult,
     //msg,
-    program::invoke_signed,
+    instruction::Instruction,
+    program::{invoke, invoke_signed},
     program_error::ProgramError,
     pubkey::Pubkey,
     rent::Rent,
@@ -14,7 +16,8 @@ use solana_program::{
     system_program,
     sysvar::Sysvar,
 };
-use spl_associated_token_account::*;
+use spl_associated_token_account::{instruction::create_associated_token_account, *};
+use std::convert::{TryFrom, TryInto};
 use std::str::FromStr;
 
 pub mod token_status {
@@ -36,12 +39,86 @@ pub struct TokenAccount {
     pub supply: u64,
     pub address: [u8; 24],
     pub network: u32,
-    pub reserved: u32,
+    pub graduated: u32,
     pub slot: u64,
     pub all_time_trades_count: u64,
     pub all_time_base_crncy_volume: u128,
     pub all_time_tokens_volume: u128,
     pub status: u64,
+    pub creator_fees: u64,
+    pub all_time_creator_fees: u64,
+    pub twap_price: f64,
+    pub price_cumulative: f64,
+    pub twap_time: u32,
+    pub observation_head: u32,
+    pub observations_count: u32,
+    pub price_history: u32,
+    pub observations: [Observation; TWAP_OBSERVATIONS],
+}
+
+impl TokenAccount {
+    /// Accrues the spot price held since the last trade and moves on to `price`,
+    /// the spot price after this trade. Prices set within a second weigh nothing.
+    pub fn update_twap(&mut self, price: f64, time: u32) {
+        // Tokens migrated from the original layout start accruing with their next trade
+        if self.twap_time != 0 {
+            self.price_cumulative += self.twap_price * time.saturating_sub(self.twap_time) as f64;
+        }
+        self.twap_price = price;
+        self.twap_time = self.twap_time.max(time);
+        let head = self.observation_head as usize;
+        if self.observations_count == 0
+            || self.twap_time
+                >= self.observations[head]
+                    .time
+                    .saturating_add(TWAP_OBSERVATION_PERIOD)
+        {
+            if self.observations_count > 0 {
+                self.observation_head = ((head + 1) % TWAP_OBSERVATIONS) as u32;
+            }
+            if (self.observations_count as usize) < TWAP_OBSERVATIONS {
+                self.observations_count += 1;
+            }
+            self.observations[self.observation_head as usize] = Observation {
+                time: self.twap_time,
+                reserved: 0,
+                price_cumulative: self.price_cumulative,
+            };
+        }
+    }
+
+    /// Time-weighted average spot price from the latest observation at least `window`
+    /// seconds before `time` up to `time`, and the number of seconds it covers.
+    pub fn twap(&self, window: u32, time: u32) -> Result<(f64, u32), ProgramError> {
+        let price_cumulative =
+            self.price_cumulative + self.twap_price * time.saturating_sub(self.twap_time) as f64;
+        let count = (self.observations_count as usize).min(TWAP_OBSERVATIONS);
+        for i in 0..count {
+            let index =
+                (self.observation_head as usize + TWAP_OBSERVATIONS - i) % TWAP_OBSERVATIONS;
+            let observation = &self.observations[index];
+            let elapsed = time.saturating_sub(observation.time);
+            if elapsed > 0 && elapsed >= window {
+                let price = (price_cumulative - observation.price_cumulative) / elapsed as f64;
+                return Ok((price, elapsed));
+            }
+        }
+        Err(TwapUnavailable.into())
+    }
+}
+
+/// Price accumulator of a token as it was at `time`.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Observation {
+    pub time: u32,
+    pub reserved: u32,
+    pub price_cumulative: f64,
+}
+
+pub mod observation_offsets {
+    pub const TIME: usize = 0;
+    pub const PRICE_CUMULATIVE: usize = 8;
 }
 
 pub mod token_account_offsets {
@@ -56,11 +133,21 @@ pub mod token_account_offsets {
     pub const SUPPLY: usize = 120;
     pub const ADDRESS: usize = 128;
     pub const NETWORK: usize = 152;
+    pub const GRADUATED: usize = 156;
     pub const SLOT: usize = 160;
     pub const ALL_TIME_TRADES_COUNT: usize = 168;
     pub const ALL_TIME_BASE_CRNCY_VOLUME: usize = 176;
     pub const ALL_TIME_TOKENS_VOLUME: usize = 192;
     pub const VALIDATION: usize = 208;
+    pub const CREATOR_FEES: usize = 216;
+    pub const ALL_TIME_CREATOR_FEES: usize = 224;
+    pub const TWAP_PRICE: usize = 232;
+    pub const PRICE_CUMULATIVE: usize = 240;
+    pub const TWAP_TIME: usize = 248;
+    pub const OBSERVATION_HEAD: usize = 252;
+    pub const OBSERVATIONS_COUNT: usize = 256;
+    pub const PRICE_HISTORY: usize = 260;
+    pub const OBSERVATIONS: usize = 264;
 }
 
 #[repr(C)]
@@ -81,6 +168,10 @@ pub struct ClientAccount {
     pub ref_paid: u64,
     pub ref_discount: f64,
     pub ref_ratio: f64,
+    pub ref_uplines: [Pubkey; MAX_REF_DEPTH - 1],
+    pub ref_upline_ratios: [f64; MAX_REF_DEPTH - 1],
+    pub creation_time: u32,
+    pub tracks_positions: u32,
 }
 
 pub mod client_account_offsets {
@@ -100,6 +191,412 @@ pub mod client_account_offsets {
     pub const REF_PAID: usize = 152;
     pub const REF_DISCOUNT: usize = 160;
     pub const REF_RATIO: usize = 168;
+    pub const REF_UPLINES: usize = 176;
+    pub const REF_UPLINE_RATIOS: usize = 240;
+    pub const CREATION_TIME: usize = 256;
+    pub const TRACKS_POSITIONS: usize = 260;
+}
+
+/// Referral fees earned by one referrer wallet, held in the vault until claimed.
+#[repr(C)]
+pub struct ReferrerAccount {
+    pub tag: u32,
+    pub version: u32,
+    pub wallet: Pubkey,
+    pub fees: u64,
+    pub all_time_fees: u64,
+    pub all_time_claimed: u64,
+    pub slot: u64,
+    pub time: u32,
+    pub reserved: u32,
+}
+
+impl ReferrerAccount {
+    pub fn accrue(&mut self, fees: u64, slot: u64, time: u32) -> ProgramResult {
+        self.fees = self.fees.checked_add(fees).ok_or(ArithmeticOverflow)?;
+        self.all_time_fees += fees;
+        self.slot = slot;
+        self.time = time;
+        Ok(())
+    }
+}
+
+pub mod referrer_account_offsets {
+    pub const TAG: usize = 0;
+    pub const VERSION: usize = 4;
+    pub const WALLET: usize = 8;
+    pub const FEES: usize = 40;
+    pub const ALL_TIME_FEES: usize = 48;
+    pub const ALL_TIME_CLAIMED: usize = 56;
+    pub const SLOT: usize = 64;
+    pub const TIME: usize = 72;
+}
+
+/// Share-link code resolving to a referrer wallet, registered by that wallet.
+#[repr(C)]
+pub struct RefCodeAccount {
+    pub tag: u32,
+    pub version: u32,
+    pub wallet: Pubkey,
+    pub code: [u8; 32],
+    pub slot: u64,
+    pub time: u32,
+    pub reserved: u32,
+}
+
+pub mod ref_code_account_offsets {
+    pub const TAG: usize = 0;
+    pub const VERSION: usize = 4;
+    pub const WALLET: usize = 8;
+    pub const CODE: usize = 40;
+    pub const SLOT: usize = 72;
+    pub const TIME: usize = 80;
+}
+
+/// Registry entry reserving a normalized nickname for the client of `wallet`.
+#[repr(C)]
+pub struct NicknameAccount {
+    pub tag: u32,
+    pub version: u32,
+    pub wallet: Pubkey,
+    pub nickname: [u8; 32],
+    pub slot: u64,
+    pub time: u32,
+    pub reserved: u32,
+}
+
+pub mod nickname_account_offsets {
+    pub const TAG: usize = 0;
+    pub const VERSION: usize = 4;
+    pub const WALLET: usize = 8;
+    pub const NICKNAME: usize = 40;
+    pub const SLOT: usize = 72;
+    pub const TIME: usize = 80;
+}
+
+/// Trades of one wallet in one token mint, in base currency units including fees.
+/// Tokens received outside of `mint` carry no cost and are not tracked.
+#[repr(C)]
+pub struct PositionAccount {
+    pub tag: u32,
+    pub version: u32,
+    pub wallet: Pubkey,
+    pub mint: Pubkey,
+    pub quantity: u64,
+    pub cost_basis: u64,
+    pub total_cost: u64,
+    pub total_proceeds: u64,
+    pub realized_pnl: i64,
+    pub slot: u64,
+    pub time: u32,
+    pub trades_count: u32,
+}
+
+impl PositionAccount {
+    pub fn add_buy(&mut self, amount: u64, cost: u64, slot: u64, time: u32) -> ProgramResult {
+        self.quantity = self
+            .quantity
+            .checked_add(amount)
+            .ok_or(ArithmeticOverflow)?;
+        self.cost_basis = self
+            .cost_basis
+            .checked_add(cost)
+            .ok_or(ArithmeticOverflow)?;
+        self.total_cost = self.total_cost.saturating_add(cost);
+        self.touch(slot, time);
+        Ok(())
+    }
+
+    /// Realizes the share of `proceeds` that falls on the tracked quantity
+    /// against the average cost of that quantity.
+    pub fn add_sell(&mut self, amount: u64, proceeds: u64, slot: u64, time: u32) -> ProgramResult {
+        let sold = amount.min(self.quantity);
+        if sold > 0 {
+            let basis = (self.cost_basis as u128 * sold as u128 / self.quantity as u128) as u64;
+            let tracked_proceeds = (proceeds as u128 * sold as u128 / amount as u128) as u64;
+            let pnl = tracked_proceeds as i128 - basis as i128;
+            self.realized_pnl =
+                i64::try_from(self.realized_pnl as i128 + pnl).map_err(|_| ArithmeticOverflow)?;
+            self.quantity -= sold;
+            self.cost_basis -= basis;
+        }
+        self.total_proceeds = self.total_proceeds.saturating_add(proceeds);
+        self.touch(slot, time);
+        Ok(())
+    }
+
+    fn touch(&mut self, slot: u64, time: u32) {
+        self.trades_count += 1;
+        self.slot = slot;
+        self.time = time;
+    }
+}
+
+pub mod position_account_offsets {
+    pub const TAG: usize = 0;
+    pub const VERSION: usize = 4;
+    pub const WALLET: usize = 8;
+    pub const MINT: usize = 40;
+    pub const QUANTITY: usize = 72;
+    pub const COST_BASIS: usize = 80;
+    pub const TOTAL_COST: usize = 88;
+    pub const TOTAL_PROCEEDS: usize = 96;
+    pub const REALIZED_PNL: usize = 104;
+    pub const SLOT: usize = 112;
+    pub const TIME: usize = 120;
+    pub const TRADES_COUNT: usize = 124;
+}
+
+/// Trading authority that a client's wallet grants to a delegate key. While it exists the
+/// delegate signs `mint` and `burn` for the wallet, and this account, as the SPL delegate
+/// of the wallet's token accounts, moves their funds. `spend_limit` caps the base currency
+/// paid per `period` seconds (0 for no limit, a `period` of 0 for the whole lifetime).
+/// Bit `n` of `networks` allows network `n`; all bits set allow every network.
+#[repr(C)]
+pub struct DelegateAccount {
+    pub tag: u32,
+    pub version: u32,
+    pub wallet: Pubkey,
+    pub delegate: Pubkey,
+    pub spend_limit: u64,
+    pub spent: u64,
+    pub networks: u64,
+    pub period: u32,
+    pub period_start: u32,
+    pub expiry: u32,
+    pub bump_seed: u32,
+    pub slot: u64,
+    pub time: u32,
+    pub trades_count: u32,
+}
+
+impl DelegateAccount {
+    pub fn allows_network(&self, network: u32) -> bool {
+        self.networks == u64::MAX || (network < 64 && self.networks & (1 << network) != 0)
+    }
+
+    /// Counts `amount` of base currency paid at `time` against the limit of its period.
+    pub fn spend(&mut self, amount: u64, time: u32) -> ProgramResult {
+        if self.period > 0 && time >= self.period_start.saturating_add(self.period) {
+            self.period_start = time - (time - self.period_start) % self.period;
+            self.spent = 0;
+        }
+        let spent = self.spent.checked_add(amount).ok_or(ArithmeticOverflow)?;
+        if self.spend_limit > 0 && spent > self.spend_limit {
+            return Err(DelegateSpendLimitExceeded.into());
+        }
+        self.spent = spent;
+        Ok(())
+    }
+
+    /// Checks a trade in `network` paying `spend` and records it.
+    pub fn add_trade(&mut self, network: u32, spend: u64, slot: u64, time: u32) -> ProgramResult {
+        if !self.allows_network(network) {
+            return Err(DelegateNetworkNotAllowed.into());
+        }
+        self.spend(spend, time)?;
+        self.trades_count += 1;
+        self.slot = slot;
+        self.time = time;
+        Ok(())
+    }
+}
+
+pub mod delegate_account_offsets {
+    pub const TAG: usize = 0;
+    pub const VERSION: usize = 4;
+    pub const WALLET: usize = 8;
+    pub const DELEGATE: usize = 40;
+    pub const SPEND_LIMIT: usize = 72;
+    pub const SPENT: usize = 80;
+    pub const NETWORKS: usize = 88;
+    pub const PERIOD: usize = 96;
+    pub const PERIOD_START: usize = 100;
+    pub const EXPIRY: usize = 104;
+    pub const BUMP_SEED: usize = 108;
+    pub const SLOT: usize = 112;
+    pub const TIME: usize = 120;
+    pub const TRADES_COUNT: usize = 124;
+}
+
+/// Lets `wallet` buy tokens in their restricted launch window, granted by the admin.
+#[repr(C)]
+pub struct LaunchAllowanceAccount {
+    pub tag: u32,
+    pub version: u32,
+    pub wallet: Pubkey,
+    pub slot: u64,
+    pub time: u32,
+    pub reserved: u32,
+}
+
+pub mod launch_allowance_account_offsets {
+    pub const TAG: usize = 0;
+    pub const VERSION: usize = 4;
+    pub const WALLET: usize = 8;
+    pub const SLOT: usize = 40;
+    pub const TIME: usize = 48;
+}
+
+/// Buys of `wallet` in the launch window of the token of `mint`. It is kept apart from
+/// the client account so that closing the client does not lift the launch limits.
+#[repr(C)]
+pub struct LaunchBuyAccount {
+    pub tag: u32,
+    pub version: u32,
+    pub wallet: Pubkey,
+    pub mint: Pubkey,
+    pub bought: u64,
+    pub slot: u64,
+    pub last_buy_time: u32,
+    pub buys_count: u32,
+}
+
+pub mod launch_buy_account_offsets {
+    pub const TAG: usize = 0;
+    pub const VERSION: usize = 4;
+    pub const WALLET: usize = 8;
+    pub const MINT: usize = 40;
+    pub const BOUGHT: usize = 72;
+    pub const SLOT: usize = 80;
+    pub const LAST_BUY_TIME: usize = 88;
+    pub const BUYS_COUNT: usize = 92;
+}
+
+/// Constant-product pool a token trades in after `graduate`. Its base currency stays in
+/// the root's vault, counted by `pool_reserves`, and its tokens in the token's hype
+/// program account.
+#[repr(C)]
+pub struct PoolAccount {
+    pub tag: u32,
+    pub version: u32,
+    pub mint: Pubkey,
+    pub token: Pubkey,
+    pub base_crncy_reserve: u64,
+    pub token_reserve: u64,
+    pub all_time_base_crncy_volume: u64,
+    pub all_time_tokens_volume: u64,
+    pub slot: u64,
+    pub time: u32,
+    pub swaps_count: u32,
+    pub creation_time: u32,
+    pub reserved: u32,
+}
+
+impl PoolAccount {
+    /// Base currency units per token unit.
+    pub fn price(&self) -> f64 {
+        self.base_crncy_reserve as f64 / self.token_reserve as f64
+    }
+}
+
+pub mod pool_account_offsets {
+    pub const TAG: usize = 0;
+    pub const VERSION: usize = 4;
+    pub const MINT: usize = 8;
+    pub const TOKEN: usize = 40;
+    pub const BASE_CRNCY_RESERVE: usize = 72;
+    pub const TOKEN_RESERVE: usize = 80;
+    pub const ALL_TIME_BASE_CRNCY_VOLUME: usize = 88;
+    pub const ALL_TIME_TOKENS_VOLUME: usize = 96;
+    pub const SLOT: usize = 104;
+    pub const TIME: usize = 112;
+    pub const SWAPS_COUNT: usize = 116;
+    pub const CREATION_TIME: usize = 120;
+}
+
+/// Spot prices and volumes of one `candle_interval` of trading in a token.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Candle {
+    pub start_time: u32,
+    pub trades_count: u32,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    pub base_crncy_volume: u64,
+    pub tokens_volume: u64,
+}
+
+pub mod candle_offsets {
+    pub const START_TIME: usize = 0;
+    pub const TRADES_COUNT: usize = 4;
+    pub const OPEN: usize = 8;
+    pub const HIGH: usize = 16;
+    pub const LOW: usize = 24;
+    pub const CLOSE: usize = 32;
+    pub const BASE_CRNCY_VOLUME: usize = 40;
+    pub const TOKENS_VOLUME: usize = 48;
+}
+
+/// Ring buffer of the last `PRICE_HISTORY_CANDLES` candles of one token mint.
+/// `head` is the index of the latest candle; intervals without trades have no candle.
+#[repr(C)]
+pub struct PriceHistoryAccount {
+    pub tag: u32,
+    pub version: u32,
+    pub mint: Pubkey,
+    pub interval: u32,
+    pub head: u32,
+    pub candles_count: u32,
+    pub reserved: u32,
+    pub slot: u64,
+    pub candles: [Candle; PRICE_HISTORY_CANDLES],
+}
+
+impl PriceHistoryAccount {
+    /// Records a trade that moved the spot price from `price_before` to `price_after`.
+    pub fn record(
+        &mut self,
+        price_before: f64,
+        price_after: f64,
+        base_crncy_volume: u64,
+        tokens_volume: u64,
+        slot: u64,
+        time: u32,
+    ) {
+        let start_time = time - time % self.interval;
+        let head = self.head as usize;
+        if self.candles_count > 0 && self.candles[head].start_time == start_time {
+            let candle = &mut self.candles[head];
+            candle.high = candle.high.max(price_before).max(price_after);
+            candle.low = candle.low.min(price_before).min(price_after);
+            candle.close = price_after;
+            candle.base_crncy_volume = candle.base_crncy_volume.saturating_add(base_crncy_volume);
+            candle.tokens_volume = candle.tokens_volume.saturating_add(tokens_volume);
+            candle.trades_count += 1;
+        } else {
+            if self.candles_count > 0 {
+                self.head = ((head + 1) % PRICE_HISTORY_CANDLES) as u32;
+            }
+            if (self.candles_count as usize) < PRICE_HISTORY_CANDLES {
+                self.candles_count += 1;
+            }
+            self.candles[self.head as usize] = Candle {
+                start_time: start_time,
+                trades_count: 1,
+                open: price_before,
+                high: price_before.max(price_after),
+                low: price_before.min(price_after),
+                close: price_after,
+                base_crncy_volume: base_crncy_volume,
+                tokens_volume: tokens_volume,
+            };
+        }
+        self.slot = slot;
+    }
+}
+
+pub mod price_history_account_offsets {
+    pub const TAG: usize = 0;
+    pub const VERSION: usize = 4;
+    pub const MINT: usize = 8;
+    pub const INTERVAL: usize = 40;
+    pub const HEAD: usize = 44;
+    pub const CANDLES_COUNT: usize = 48;
+    pub const SLOT: usize = 56;
+    pub const CANDLES: usize = 64;
 }
 
 #[repr(C)]
@@ -134,8 +631,8 @@ pub struct OperatorRecord {
 pub mod operator_record_offsets {
     pub const OPERATOR_ADDRESS: usize = 0;
     pub const VERSION: usize = 32;
-    pub const OPERATOR_NAME: usize = 36;
-    pub const MAX_NETWORKS_COUNT: usize = 68;
+    pub const MAX_NETWORKS_COUNT: usize = 36;
+    pub const OPERATOR_NAME: usize = 40;
 }
 
 #[repr(C)]
@@ -174,6 +671,40 @@ pub struct RootAccount {
     pub ref_discount: f64,
     pub ref_ratio: f64,
     pub url_prefix: [u8; 32],
+    pub ref_fees: u64,
+    pub ref_depth: u32,
+    pub ref_grace_period: u32,
+    pub ref_upline_ratios: [f64; MAX_REF_DEPTH - 1],
+    pub fee_tiers_count: u32,
+    pub reserved: u32,
+    pub fee_tiers: [FeeTier; MAX_FEE_TIERS],
+    pub creator_fee_ratio: f64,
+    pub creator_fees: u64,
+    pub retired_tokens_count: u64,
+    pub token_idle_period: u32,
+    pub candle_interval: u32,
+    pub switch_fee_rate: f64,
+    pub launch_window: u32,
+    pub restricted_window: u32,
+    pub launch_max_buy: u64,
+    pub buy_cooldown: u32,
+    pub reserved2: u32,
+    pub graduation_reserve: u64,
+    pub pool_fee_rate: f64,
+    pub pool_reserves: u64,
+}
+
+/// Discounted `fee_rate` for clients with at least `min_volume` of all-time base currency volume.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct FeeTier {
+    pub min_volume: u64,
+    pub fee_rate: f64,
+}
+
+pub mod fee_tier_offsets {
+    pub const MIN_VOLUME: usize = 0;
+    pub const FEE_RATE: usize = 8;
 }
 
 #[repr(C)]
@@ -183,6 +714,7 @@ pub struct NetworkRecord {
     pub validator: Pubkey,
     pub descriptor: [u8; 32],
     pub mask: [u8; 64],
+    pub creator_fee_cap: f64,
 }
 
 pub mod network_record_offsets {
@@ -190,6 +722,7 @@ pub mod network_record_offsets {
     pub const VALIDATOR: usize = 8;
     pub const DESCRIPTOR: usize = 40;
     pub const MASK: usize = 72;
+    pub const CREATOR_FEE_CAP: usize = 136;
 }
 
 pub mod root_account_offsets {
@@ -227,15 +760,37 @@ pub mod root_account_offsets {
     pub const REF_DISCOUNT: usize = 344;
     pub const REF_RATIO: usize = 352;
     pub const URL_PREFIX: usize = 360;
-    pub const NETWORK_RECORDS: usize = 392;
+    pub const REF_FEES: usize = 392;
+    pub const REF_DEPTH: usize = 400;
+    pub const REF_GRACE_PERIOD: usize = 404;
+    pub const REF_UPLINE_RATIOS: usize = 408;
+    pub const FEE_TIERS_COUNT: usize = 424;
+    pub const FEE_TIERS: usize = 432;
+    pub const CREATOR_FEE_RATIO: usize = 496;
+    pub const CREATOR_FEES: usize = 504;
+    pub const RETIRED_TOKENS_COUNT: usize = 512;
+    pub const TOKEN_IDLE_PERIOD: usize = 520;
+    pub const CANDLE_INTERVAL: usize = 524;
+    pub const SWITCH_FEE_RATE: usize = 528;
+    pub const LAUNCH_WINDOW: usize = 536;
+    pub const RESTRICTED_WINDOW: usize = 540;
+    pub const LAUNCH_MAX_BUY: usize = 544;
+    pub const BUY_COOLDOWN: usize = 552;
+    pub const GRADUATION_RESERVE: usize = 560;
+    pub const POOL_FEE_RATE: usize = 568;
+    pub const POOL_RESERVES: usize = 576;
+    pub const NETWORK_RECORDS: usize = 584;
 }
 
+#[derive(Clone, Copy)]
 pub struct Context<'a, 'info> {
     pub root: *mut RootAccount,
     pub client: *mut ClientAccount,
     pub hype_auth: Pubkey,
     pub hype_bump_seed: u8,
     pub signer: &'a AccountInfo<'info>,
+    pub payer: &'a AccountInfo<'info>,
+    pub authority: &'a AccountInfo<'info>,
     pub root_acc: &'a AccountInfo<'info>,
     pub client_associated_token_acc: &'a AccountInfo<'info>,
     pub client_associated_hype_acc: &'a AccountInfo<'info>,
@@ -247,13 +802,33 @@ pub struct Context<'a, 'info> {
     pub hype_auth_acc: &'a AccountInfo<'info>,
     pub token_program_id: &'a AccountInfo<'info>,
     pub token_2022_program_id: &'a AccountInfo<'info>,
-    pub ref_acc: &'a AccountInfo<'info>,
-    pub ref_associated_token_acc: &'a AccountInfo<'info>,
+    pub base_crncy_token_program_id: &'a AccountInfo<'info>,
+    pub referrer: *mut ReferrerAccount,
+    pub uplines: [*mut ReferrerAccount; MAX_REF_DEPTH - 1],
+    pub delegate: *mut DelegateAccount,
+    pub allow_listed: bool,
+    pub launch_buy: *mut LaunchBuyAccount,
     pub slot: u64,
     pub time: u32,
 }
 
 impl<'a, 'info> Context<'a, 'info> {
+    /// `ref_acc` (#16) is the referrer wallet or one of its referral code accounts, and #17
+    /// is the referrer account of that wallet. #17 used to be the referrer's base currency
+    /// associated token account, so clients built for that account list break.
+    /// While the client's referral is active, the 17 trade accounts are followed by one
+    /// referrer account per upline level; a trade that creates the client first passes
+    /// its referrer's client account, from which those levels are resolved.
+    /// A referrer or upline whose referrer account is not opened yet gets it opened by the
+    /// trade at the cost of the payer, so its referral fees are always booked.
+    /// A client created with a nickname passes its nickname account and the referral code
+    /// account of the same name next (see `claim_nickname`), followed by the
+    /// optional accounts declared in the trade's extras flags (see `next_trade_extras`);
+    /// the token's price history and, once the client tracks positions, its position
+    /// account are among them (see `load_trade_records`).
+    /// The wallet (#1) signs unless a delegate trades for it: the delegate key (Signer,
+    /// Writable) and its delegate account (Writable) then follow the 17 trade accounts, and
+    /// the delegate pays for any account the trade creates.
     pub unsafe fn new(
         program_id: &Pubkey,
         accounts_iter: &mut Iter<'a, AccountInfo<'info>>,
@@ -277,36 +852,115 @@ impl<'a, 'info> Context<'a, 'info> {
         let system_program_acc = next_account_info(accounts_iter)?;
         let associated_token_id = next_account_info(accounts_iter)?;
         let ref_acc = next_account_info(accounts_iter)?;
-        let ref_associated_token_acc = next_account_info(accounts_iter)?;
-        if *ref_acc.key != system_program::ID {
-            let ref_expected_address = get_associated_token_address_with_program_id(
-                ref_acc.key,
-                base_crncy_mint_acc.key,
-                token_program_id.key,
-            );
-            if ref_expected_address != *ref_associated_token_acc.key {
-                return Err(InvalidRefAddress.into());
-            }
-        }
+        let referrer_acc = next_account_info(accounts_iter)?;
         if *token_program_id.key != spl_token::id() {
             return Err(InvalidTokenProgramId.into());
         }
-        if *token_2022_program_id.key != spl_token_2022::id() {
-            return Err(InvalidToken2022ProgramId.into());
-        }
         if *associated_token_id.key != spl_associated_token_account::id() {
             return Err(InvalidAssociatedTokenId.into());
         }
-        if !system_program::check_id(system_program_acc.key) {
-            return Err(InvalidSystemProgramId.into());
+        Self::load(
+            program_id,
+            accounts_iter,
+            instruction_data,
+            nickname_offset,
+            [
+                signer,
+                client_acc,
+                client_associated_token_acc,
+                client_associated_hype_acc,
+                root_acc,
+                token_acc,
+                base_crncy_mint_acc,
+                base_crncy_program_acc,
+                hype_mint_acc,
+                hype_program_acc,
+                hype_auth_acc,
+                token_program_id,
+                token_2022_program_id,
+                system_program_acc,
+            ],
+            Some((ref_acc, referrer_acc)),
+        )
+    }
+
+    /// Context of `cpi_trade` from its 14 accounts: those of `mint` without the SPL Token
+    /// and Associated Token programs and the referral pair. #12 is the token program of
+    /// the base currency. The referral pair follows, after any delegate accounts, only
+    /// while the client's referral is active; clients created here have no referrer.
+    pub unsafe fn new_cpi(
+        program_id: &Pubkey,
+        accounts_iter: &mut Iter<'a, AccountInfo<'info>>,
+        instruction_data: &[u8],
+    ) -> Result<Self, ProgramError> {
+        let signer = next_account_info(accounts_iter)?;
+        let client_acc = next_account_info(accounts_iter)?;
+        let client_associated_token_acc = next_account_info(accounts_iter)?;
+        let client_associated_hype_acc = next_account_info(accounts_iter)?;
+        let root_acc = next_account_info(accounts_iter)?;
+        let token_acc = next_account_info(accounts_iter)?;
+        let base_crncy_mint_acc = next_account_info(accounts_iter)?;
+        let base_crncy_program_acc = next_account_info(accounts_iter)?;
+        let hype_mint_acc = next_account_info(accounts_iter)?;
+        let hype_program_acc = next_account_info(accounts_iter)?;
+        let hype_auth_acc = next_account_info(accounts_iter)?;
+        let base_crncy_token_program_id = next_account_info(accounts_iter)?;
+        let token_2022_program_id = next_account_info(accounts_iter)?;
+        let system_program_acc = next_account_info(accounts_iter)?;
+        if *base_crncy_token_program_id.key != *base_crncy_mint_acc.owner
+            || (*base_crncy_token_program_id.key != spl_token::id()
+                && *base_crncy_token_program_id.key != spl_token_2022::id())
+        {
+            return Err(InvalidTokenProgramId.into());
         }
-        if root_acc.owner != program_id {
-            return Err(ProgramError::IncorrectProgramId);
+        // Clients created here start without a nickname
+        Self::load(
+            program_id,
+            accounts_iter,
+            instruction_data,
+            instruction_data.len(),
+            [
+                signer,
+                client_acc,
+                client_associated_token_acc,
+                client_associated_hype_acc,
+                root_acc,
+                token_acc,
+                base_crncy_mint_acc,
+                base_crncy_program_acc,
+                hype_mint_acc,
+                hype_program_acc,
+                hype_auth_acc,
+                base_crncy_token_program_id,
+                token_2022_program_id,
+                system_program_acc,
+            ],
+            None,
+        )
+    }
+
+    unsafe fn load(
+        program_id: &Pubkey,
+        accounts_iter: &mut Iter<'a, AccountInfo<'info>>,
+        instruction_data: &[u8],
+        nickname_offset: usize,
+        accounts: [&'a AccountInfo<'info>; 14],
+        refs: Option<(&'a AccountInfo<'info>, &'a AccountInfo<'info>)>,
+    ) -> Result<Self, ProgramError> {
+        let [signer, client_acc, client_associated_token_acc, client_associated_hype_acc, root_acc, token_acc, base_crncy_mint_acc, base_crncy_program_acc, hype_mint_acc, hype_program_acc, hype_auth_acc, token_program_id, token_2022_program_id, system_program_acc] =
+            accounts;
+        let base_crncy_token_program_id = if *base_crncy_mint_acc.owner == spl_token_2022::id() {
+            token_2022_program_id
+        } else {
+            token_program_id
+        };
+        if *token_2022_program_id.key != spl_token_2022::id() {
+            return Err(InvalidToken2022ProgramId.into());
         }
-        let root = root_acc.data.borrow().as_ptr() as *mut RootAccount;
-        if (*root).tag != ROOT_TAG {
-            return Err(InvalidAccountTag.into());
+        if !system_program::check_id(system_program_acc.key) {
+            return Err(InvalidSystemProgramId.into());
         }
+        let root = load_root_account(program_id, root_acc)?;
         if (*root).base_crncy_mint != *base_crncy_mint_acc.key {
             return Err(InvalidBaseCrncyMint.into());
         }
@@ -316,7 +970,7 @@ impl<'a, 'info> Context<'a, 'info> {
         let expected_address = get_associated_token_address_with_program_id(
             signer.key,
             base_crncy_mint_acc.key,
-            token_program_id.key,
+            base_crncy_token_program_id.key,
         );
         if expected_address != *client_associated_token_acc.key {
             return Err(InvalidAssociatedTokenAddress.into());
@@ -332,8 +986,37 @@ impl<'a, 'info> Context<'a, 'info> {
         let clock = Clock::get()?;
         let time = clock.unix_timestamp as u32;
         let slot = clock.slot;
+        let mut delegate: *mut DelegateAccount = std::ptr::null_mut();
+        let mut payer = signer;
+        let mut authority = signer;
+        if !signer.is_signer {
+            let delegate_key_acc = next_account_info(accounts_iter)?;
+            let delegate_acc = next_account_info(accounts_iter)?;
+            if !delegate_key_acc.is_signer {
+                return Err(ProgramError::MissingRequiredSignature);
+            }
+            delegate =
+                load_delegate_account(program_id, delegate_acc, signer.key, delegate_key_acc.key)?;
+            if (*delegate).expiry != 0 && time > (*delegate).expiry {
+                return Err(DelegateExpired.into());
+            }
+            payer = delegate_key_acc;
+            authority = delegate_acc;
+        }
+        let (ref_wallet, ref_funded) = match refs {
+            Some((ref_acc, _)) => (
+                resolve_ref_wallet(program_id, &*root, ref_acc)?,
+                ref_acc.lamports() > 0,
+            ),
+            None => (system_program::ID, false),
+        };
         let client: *mut ClientAccount;
-        if client_acc.owner == &system_program::ID {
+        let new_client = client_acc.owner == &system_program::ID;
+        if new_client {
+            // Only the wallet itself opens its client account
+            if !delegate.is_null() {
+                return Err(ProgramError::MissingRequiredSignature);
+            }
             let client_seed = get_seed_by_tag((*root).version, CLIENT_TAG as u32);
             let client_bump_seed = check_account(client_acc, signer.key, program_id, &client_seed)?;
             let rent = &Rent::default();
@@ -350,8 +1033,14 @@ impl<'a, 'info> Context<'a, 'info> {
                 &[&[&client_seed, signer.key.as_ref(), &[client_bump_seed]]],
             )?;
             client = client_acc.data.borrow().as_ptr() as *mut ClientAccount;
+            let mut nickname = [0; NICKNAME_STRING_LENGTH];
+            if let Some(bytes) =
+                instruction_data.get(nickname_offset..nickname_offset + NICKNAME_STRING_LENGTH)
+            {
+                nickname.copy_from_slice(bytes);
+            }
             let ref_stop: u32;
-            if *ref_acc.key != system_program::ID && ref_acc.lamports() > 0 {
+            if ref_wallet != system_program::ID && ref_wallet != *signer.key && ref_funded {
                 ref_stop = clock.unix_timestamp as u32 + (*root).ref_duration;
             } else {
                 ref_stop = clock.unix_timestamp as u32;
@@ -369,11 +1058,14 @@ impl<'a, 'info> Context<'a, 'info> {
                 slot: clock.slot,
                 time: clock.unix_timestamp as u32,
                 tokens_created: 0,
-                nickname: *((instruction_data[nickname_offset..]).as_ptr()
-                    as *const [u8; NICKNAME_STRING_LENGTH]),
-                ref_address: *ref_acc.key,
+                nickname: nickname,
+                ref_address: ref_wallet,
                 ref_discount: (*root).ref_discount,
                 ref_ratio: (*root).ref_ratio,
+                ref_uplines: [Pubkey::default(); MAX_REF_DEPTH - 1],
+                ref_upline_ratios: (*root).ref_upline_ratios,
+                creation_time: clock.unix_timestamp as u32,
+                tracks_positions: 0,
             };
             log_new_client(
                 (*root).clients_count,
@@ -388,11 +1080,72 @@ impl<'a, 'info> Context<'a, 'info> {
             if client_acc.owner != program_id {
                 return Err(InvalidClientAccount.into());
             }
+            check_account_size(client_acc, CLIENT_ACCOUNT_SIZE)?;
             client = client_acc.data.borrow().as_ptr() as *mut ClientAccount;
             if (*client).tag != CLIENT_TAG || (*client).wallet != *signer.key {
                 return Err(InvalidClientAccount.into());
             }
         }
+        let mut referrer: *mut ReferrerAccount = std::ptr::null_mut();
+        let mut uplines: [*mut ReferrerAccount; MAX_REF_DEPTH - 1] =
+            [std::ptr::null_mut(); MAX_REF_DEPTH - 1];
+        if (*client).ref_stop > time {
+            let (ref_wallet, referrer_acc) = match refs {
+                Some((_, referrer_acc)) => (ref_wallet, referrer_acc),
+                None => {
+                    let ref_acc = next_account_info(accounts_iter)?;
+                    (
+                        resolve_ref_wallet(program_id, &*root, ref_acc)?,
+                        next_account_info(accounts_iter)?,
+                    )
+                }
+            };
+            if (*client).ref_address != ref_wallet {
+                return Err(InvalidRefAddress.into());
+            }
+            if new_client {
+                let ref_client_acc = next_account_info(accounts_iter)?;
+                (*client).ref_uplines = resolve_ref_uplines(
+                    program_id,
+                    &*root,
+                    ref_client_acc,
+                    &ref_wallet,
+                    signer.key,
+                )?;
+            }
+            referrer = load_referrer_account(
+                program_id,
+                &*root,
+                payer,
+                referrer_acc,
+                &ref_wallet,
+                slot,
+                time,
+            )?;
+            for (upline, wallet) in uplines.iter_mut().zip((*client).ref_uplines.iter()) {
+                if *wallet == Pubkey::default() {
+                    break;
+                }
+                let upline_acc = next_account_info(accounts_iter)?;
+                *upline = load_referrer_account(
+                    program_id, &*root, payer, upline_acc, wallet, slot, time,
+                )?;
+            }
+        }
+        if new_client && (*client).nickname.iter().any(|c| *c != 0) {
+            let nickname_acc = next_account_info(accounts_iter)?;
+            let code_acc = next_account_info(accounts_iter)?;
+            claim_nickname(
+                program_id,
+                (*root).version,
+                signer,
+                nickname_acc,
+                code_acc,
+                &(*client).nickname,
+                slot,
+                time,
+            )?;
+        }
         let (hype_auth, hype_bump_seed) = Pubkey::find_program_address(&[HYPE_SEED], program_id);
         if hype_auth != *hype_auth_acc.key {
             return Err(InvalidHypeAuthority.into());
@@ -404,6 +1157,8 @@ impl<'a, 'info> Context<'a, 'info> {
             hype_bump_seed: hype_bump_seed,
             root_acc: root_acc,
             signer: signer,
+            payer: payer,
+            authority: authority,
             client_associated_token_acc: client_associated_token_acc,
             client_associated_hype_acc: client_associated_hype_acc,
             token_acc: token_acc,
@@ -414,21 +1169,1304 @@ impl<'a, 'info> Context<'a, 'info> {
             hype_auth_acc: hype_auth_acc,
             token_program_id: token_program_id,
             token_2022_program_id: token_2022_program_id,
-            ref_acc: ref_acc,
-            ref_associated_token_acc: ref_associated_token_acc,
+            base_crncy_token_program_id: base_crncy_token_program_id,
+            referrer: referrer,
+            uplines: uplines,
+            delegate: delegate,
+            allow_listed: false,
+            launch_buy: std::ptr::null_mut(),
             time: time,
             slot: slot,
         })
     }
 }
 
-pub unsafe fn check_holder_account(
-    account: &AccountInfo,
-    program_id: &Pubkey,
+impl<'a, 'info> Context<'a, 'info> {
+    /// Rejects a trade past its optional deadline: a slot at `offset` followed by a unix time,
+    /// each 0 for no limit. Absent fields mean no deadline at all.
+    pub fn check_valid_until(&self, instruction_data: &[u8], offset: usize) -> ProgramResult {
+        if instruction_data.len() > offset {
+            if instruction_data.len() < offset + 12 {
+                return Err(InvalidDataLength.into());
+            }
+            let slot = u64::from_le_bytes(instruction_data[offset..offset + 8].try_into().unwrap());
+            let time = u32::from_le_bytes(
+                instruction_data[offset + 8..offset + 12]
+                    .try_into()
+                    .unwrap(),
+            );
+            if (slot != 0 && self.slot > slot) || (time != 0 && self.time > time) {
+                return Err(TradeExpired.into());
+            }
+        }
+        Ok(())
+    }
+
+    /// Fails for a delegated trade, in instructions that only the wallet itself may sign.
+    pub fn check_wallet_signed(&self) -> ProgramResult {
+        if !self.delegate.is_null() {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        Ok(())
+    }
+
+    /// Invokes `instruction`, which moves funds out of the wallet's token accounts on behalf
+    /// of `authority`, signing for the delegate account in a delegated trade.
+    pub unsafe fn invoke_as_wallet(
+        &self,
+        instruction: &Instruction,
+        account_infos: &[AccountInfo<'info>],
+    ) -> ProgramResult {
+        if self.delegate.is_null() {
+            return invoke(instruction, account_infos);
+        }
+        let delegate_seed = get_seed_by_tag((*self.delegate).version, DELEGATE_TAG);
+        invoke_signed(
+            instruction,
+            account_infos,
+            &[&[
+                &delegate_seed,
+                self.signer.key.as_ref(),
+                (*self.delegate).delegate.as_ref(),
+                &[(*self.delegate).bump_seed as u8],
+            ]],
+        )
+    }
+
+    /// Fails a delegated buy into a hype account whose SPL delegate is not the delegate
+    /// account. Only the wallet can approve one (see `set_delegate`), and without that
+    /// the delegate could not sell what it bought.
+    pub fn check_delegate_approved(&self) -> ProgramResult {
+        if self.delegate.is_null() {
+            return Ok(());
+        }
+        if *self.client_associated_hype_acc.owner == system_program::ID
+            || read_token_delegate(&self.client_associated_hype_acc.data.borrow())?
+                != Some(*self.authority.key)
+        {
+            return Err(DelegateNotApproved.into());
+        }
+        Ok(())
+    }
+
+    /// Creates the wallet's associated hype account of the traded token if it does not exist yet.
+    pub fn create_client_hype_account(&self) -> ProgramResult {
+        if *self.client_associated_hype_acc.owner == system_program::ID {
+            invoke(
+                &create_associated_token_account(
+                    self.payer.key,
+                    self.signer.key,
+                    self.hype_mint_acc.key,
+                    self.token_2022_program_id.key,
+                ),
+                &[
+                    self.payer.clone(),
+                    self.client_associated_hype_acc.clone(),
+                    self.signer.clone(),
+                    self.hype_mint_acc.clone(),
+                    self.token_2022_program_id.clone(),
+                ],
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Creates the wallet's associated base currency account if it does not exist yet.
+    pub fn create_client_token_account(&self) -> ProgramResult {
+        if *self.client_associated_token_acc.owner == system_program::ID {
+            invoke(
+                &create_associated_token_account(
+                    self.payer.key,
+                    self.signer.key,
+                    self.base_crncy_mint_acc.key,
+                    self.base_crncy_token_program_id.key,
+                ),
+                &[
+                    self.payer.clone(),
+                    self.client_associated_token_acc.clone(),
+                    self.signer.clone(),
+                    self.base_crncy_mint_acc.clone(),
+                    self.base_crncy_token_program_id.clone(),
+                ],
+            )?;
+        }
+        Ok(())
+    }
+
+    /// This context trading the token of the next four accounts: token account, hype mint,
+    /// hype program account and the client's associated hype account.
+    pub fn next_token(
+        &self,
+        accounts_iter: &mut Iter<'a, AccountInfo<'info>>,
+    ) -> Result<Context<'a, 'info>, ProgramError> {
+        let token_acc = next_account_info(accounts_iter)?;
+        let hype_mint_acc = next_account_info(accounts_iter)?;
+        let hype_program_acc = next_account_info(accounts_iter)?;
+        let client_associated_hype_acc = next_account_info(accounts_iter)?;
+        let expected_hype_address = get_associated_token_address_with_program_id(
+            self.signer.key,
+            hype_mint_acc.key,
+            self.token_2022_program_id.key,
+        );
+        if expected_hype_address != *client_associated_hype_acc.key {
+            return Err(InvalidAssociatedTokenAddress.into());
+        }
+        Ok(Context {
+            token_acc: token_acc,
+            hype_mint_acc: hype_mint_acc,
+            hype_program_acc: hype_program_acc,
+            client_associated_hype_acc: client_associated_hype_acc,
+            ..*self
+        })
+    }
+
+    /// This context delivering the traded tokens to `recipient` through its associated
+    /// hype account instead of the signer's.
+    pub fn with_recipient(
+        &self,
+        recipient: &'a AccountInfo<'info>,
+        recipient_associated_hype_acc: &'a AccountInfo<'info>,
+    ) -> Result<Context<'a, 'info>, ProgramError> {
+        let expected_hype_address = get_associated_token_address_with_program_id(
+            recipient.key,
+            self.hype_mint_acc.key,
+            self.token_2022_program_id.key,
+        );
+        if expected_hype_address != *recipient_associated_hype_acc.key {
+            return Err(InvalidAssociatedTokenAddress.into());
+        }
+        Ok(Context {
+            client_associated_hype_acc: recipient_associated_hype_acc,
+            ..*self
+        })
+    }
+
+    /// This context with the signer let into restricted launch windows by its launch
+    /// allowance account, created by `set_launch_allowance`.
+    pub unsafe fn with_launch_allowance(
+        &self,
+        program_id: &Pubkey,
+        launch_allowance_acc: &AccountInfo,
+    ) -> Result<Context<'a, 'info>, ProgramError> {
+        if launch_allowance_acc.owner != program_id {
+            return Err(InvalidLaunchAllowance.into());
+        }
+        check_account_size(launch_allowance_acc, LAUNCH_ALLOWANCE_ACCOUNT_SIZE)?;
+        let launch_allowance =
+            launch_allowance_acc.data.borrow().as_ptr() as *const LaunchAllowanceAccount;
+        if (*launch_allowance).tag != LAUNCH_ALLOWANCE_TAG
+            || (*launch_allowance).version != (*self.root).version
+            || (*launch_allowance).wallet != *self.signer.key
+        {
+            return Err(InvalidLaunchAllowance.into());
+        }
+        Ok(Context {
+            allow_listed: true,
+            ..*self
+        })
+    }
+
+    /// This context with the launch allowance and launch buy account of `extras`, those
+    /// passed by the trade.
+    pub unsafe fn with_launch_extras(
+        &self,
+        program_id: &Pubkey,
+        extras: &TradeExtras<'a, 'info>,
+    ) -> Result<Context<'a, 'info>, ProgramError> {
+        let mut ctx = match extras.launch_allowance {
+            Some(launch_allowance_acc) => {
+                self.with_launch_allowance(program_id, launch_allowance_acc)?
+            }
+            None => *self,
+        };
+        if let Some(launch_buy_acc) = extras.launch_buy {
+            ctx.launch_buy = load_launch_buy_account(
+                program_id,
+                &*ctx.root,
+                ctx.payer,
+                ctx.signer,
+                launch_buy_acc,
+                ctx.hype_mint_acc.key,
+            )?;
+        }
+        Ok(ctx)
+    }
+
+    /// Position account and price history a trade of `token` keeps up to date, from the
+    /// trade's `extras`. Once a client passed a position account every trade of it has to
+    /// pass the position account of its token, so that positions follow all its trades;
+    /// a token's price history is required once it has one.
+    pub unsafe fn load_trade_records(
+        &self,
+        program_id: &Pubkey,
+        token: &TokenAccount,
+        extras: &TradeExtras<'a, 'info>,
+    ) -> Result<TradeRecords, ProgramError> {
+        let price_history = load_price_history(program_id, token, extras.price_history)?;
+        let position = match extras.position {
+            Some(position_acc) => {
+                (*self.client).tracks_positions = 1;
+                load_position_account(
+                    program_id,
+                    &*self.root,
+                    self.payer,
+                    self.signer,
+                    position_acc,
+                    self.hype_mint_acc.key,
+                    self.slot,
+                    self.time,
+                )?
+            }
+            None if (*self.client).tracks_positions != 0 => {
+                return Err(PositionRequired.into());
+            }
+            None => std::ptr::null_mut(),
+        };
+        Ok(TradeRecords {
+            position: position,
+            price_history: price_history,
+        })
+    }
+
+    /// Applies the launch protections of the root to a buy of `amount` of `token`. Until
+    /// `restricted_window` seconds after its creation only the creator and allow-listed
+    /// wallets buy a token. Until `launch_window` seconds a wallet buys at most
+    /// `launch_max_buy` of it in total, each buy `buy_cooldown` seconds after its previous
+    /// one; those buys are counted in the wallet's launch buy account of the token, which
+    /// every buy in the window has to pass.
+    pub unsafe fn check_launch(&self, token: &TokenAccount, amount: u64) -> ProgramResult {
+        let root = &*self.root;
+        let age = self.time.saturating_sub(token.creation_time);
+        if age < root.restricted_window && token.creator != *self.signer.key && !self.allow_listed {
+            return Err(LaunchRestricted.into());
+        }
+        if age >= root.launch_window || (root.launch_max_buy == 0 && root.buy_cooldown == 0) {
+            return Ok(());
+        }
+        if self.launch_buy.is_null() {
+            return Err(LaunchBuyAccountRequired.into());
+        }
+        let launch_buy = &mut *self.launch_buy;
+        let bought = launch_buy
+            .bought
+            .checked_add(amount)
+            .ok_or(ArithmeticOverflow)?;
+        if root.launch_max_buy > 0 && bought > root.launch_max_buy {
+            return Err(LaunchMaxBuyExceeded.into());
+        }
+        if root.buy_cooldown > 0
+            && launch_buy.buys_count > 0
+            && self.time < launch_buy.last_buy_time.saturating_add(root.buy_cooldown)
+        {
+            return Err(BuyCooldownActive.into());
+        }
+        launch_buy.bought = bought;
+        launch_buy.buys_count += 1;
+        launch_buy.last_buy_time = self.time;
+        launch_buy.slot = self.slot;
+        Ok(())
+    }
+
+    /// Rejects curve trades of a token that graduated or whose curve reserve reached
+    /// the root's `graduation_reserve`, which only `graduate` moves on.
+    pub fn check_curve_open(&self, token: &TokenAccount) -> ProgramResult {
+        if token.graduated != 0 {
+            return Err(TokenGraduated.into());
+        }
+        let root = unsafe { &*self.root };
+        if root.graduation_reserve > 0
+            && reserve_units(&CurveParams::from_root(root), token.supply)?
+                >= root.graduation_reserve
+        {
+            return Err(TokenGraduated.into());
+        }
+        Ok(())
+    }
+
+    /// Fails a buy of `amount` that would carry the curve reserve of `token` past the
+    /// root's `graduation_reserve`. The buy is never cut short, so a client reaching the
+    /// reserve buys exactly `graduation_cap`.
+    pub fn check_graduation_reserve(&self, token: &TokenAccount, amount: u64) -> ProgramResult {
+        let root = unsafe { &*self.root };
+        if root.graduation_reserve == 0 {
+            return Ok(());
+        }
+        let cap = graduation_cap(
+            &CurveParams::from_root(root),
+            token.supply,
+            root.graduation_reserve,
+        )?;
+        if amount > cap {
+            return Err(GraduationReserveExceeded.into());
+        }
+        Ok(())
+    }
+
+    /// Moves `amount` of base currency from the client's associated account to the vault.
+    pub unsafe fn deposit_base_crncy(&self, amount: u64) -> ProgramResult {
+        if *self.base_crncy_program_acc.owner == spl_token_2022::id() {
+            let transfer_to_taker_ix = spl_token_2022::instruction::transfer_checked(
+                &spl_token_2022::id(),
+                self.client_associated_token_acc.key,
+                self.base_crncy_mint_acc.key,
+                self.base_crncy_program_acc.key,
+                self.authority.key,
+                &[self.authority.key],
+                amount,
+                (*self.root).decimals as u8,
+            )?;
+            self.invoke_as_wallet(
+                &transfer_to_taker_ix,
+                &[
+                    self.token_2022_program_id.clone(),
+                    self.client_associated_token_acc.clone(),
+                    self.base_crncy_mint_acc.clone(),
+                    self.base_crncy_program_acc.clone(),
+                    self.authority.clone(),
+                ],
+            )
+        } else {
+            let transfer_to_taker_ix = spl_token::instruction::transfer(
+                &spl_token::id(),
+                self.client_associated_token_acc.key,
+                self.base_crncy_program_acc.key,
+                self.authority.key,
+                &[self.authority.key],
+                amount,
+            )?;
+            self.invoke_as_wallet(
+                &transfer_to_taker_ix,
+                &[
+                    self.client_associated_token_acc.clone(),
+                    self.base_crncy_program_acc.clone(),
+                    self.authority.clone(),
+                    self.token_program_id.clone(),
+                ],
+            )
+        }
+    }
+
+    /// Pays `amount` of base currency out of the vault to the client's associated account.
+    pub unsafe fn withdraw_base_crncy(&self, amount: u64) -> ProgramResult {
+        if *self.base_crncy_program_acc.owner == spl_token_2022::id() {
+            let transfer_to_taker_ix = spl_token_2022::instruction::transfer_checked(
+                &spl_token_2022::id(),
+                self.base_crncy_program_acc.key,
+                self.base_crncy_mint_acc.key,
+                self.client_associated_token_acc.key,
+                &self.hype_auth,
+                &[&self.hype_auth],
+                amount,
+                (*self.root).decimals as u8,
+            )?;
+            invoke_signed(
+                &transfer_to_taker_ix,
+                &[
+                    self.token_2022_program_id.clone(),
+                    self.base_crncy_program_acc.clone(),
+                    self.base_crncy_mint_acc.clone(),
+                    self.client_associated_token_acc.clone(),
+                    self.hype_auth_acc.clone(),
+                ],
+                &[&[&HYPE_SEED[..], &[self.hype_bump_seed]]],
+            )
+        } else {
+            let transfer_to_taker_ix = spl_token::instruction::transfer(
+                &spl_token::id(),
+                self.base_crncy_program_acc.key,
+                self.client_associated_token_acc.key,
+                &self.hype_auth,
+                &[&self.hype_auth],
+                amount,
+            )?;
+            invoke_signed(
+                &transfer_to_taker_ix,
+                &[
+                    self.base_crncy_program_acc.clone(),
+                    self.client_associated_token_acc.clone(),
+                    self.hype_auth_acc.clone(),
+                    self.token_program_id.clone(),
+                ],
+                &[&[&HYPE_SEED[..], &[self.hype_bump_seed]]],
+            )
+        }
+    }
+
+    /// Moves `amount` of the token from the client's associated hype account to the
+    /// hype program account.
+    pub unsafe fn deposit_hype(&self, amount: u64) -> ProgramResult {
+        let transfer_to_taker_ix = spl_token_2022::instruction::transfer_checked(
+            &spl_token_2022::id(),
+            self.client_associated_hype_acc.key,
+            self.hype_mint_acc.key,
+            self.hype_program_acc.key,
+            self.authority.key,
+            &[self.authority.key],
+            amount,
+            (*self.root).decimals as u8,
+        )?;
+        self.invoke_as_wallet(
+            &transfer_to_taker_ix,
+            &[
+                self.token_2022_program_id.clone(),
+                self.client_associated_hype_acc.clone(),
+                self.hype_mint_acc.clone(),
+                self.hype_program_acc.clone(),
+                self.authority.clone(),
+            ],
+        )
+    }
+
+    /// Pays `amount` of the token out of the hype program account to the client's
+    /// associated hype account.
+    pub unsafe fn withdraw_hype(&self, amount: u64) -> ProgramResult {
+        invoke_signed(
+            &spl_token_2022::instruction::transfer_checked(
+                &spl_token_2022::id(),
+                self.hype_program_acc.key,
+                self.hype_mint_acc.key,
+                self.client_associated_hype_acc.key,
+                self.hype_auth_acc.key,
+                &[self.hype_auth_acc.key],
+                amount,
+                (*self.root).decimals as u8,
+            )?,
+            &[
+                self.token_2022_program_id.clone(),
+                self.hype_program_acc.clone(),
+                self.hype_mint_acc.clone(),
+                self.client_associated_hype_acc.clone(),
+                self.hype_auth_acc.clone(),
+            ],
+            &[&[&HYPE_SEED[..], &[self.hype_bump_seed]]],
+        )
+    }
+
+    /// Existing token account of this context, matching its hype mint and program account.
+    pub unsafe fn load_token_account(
+        &self,
+        program_id: &Pubkey,
+    ) -> Result<*mut TokenAccount, ProgramError> {
+        if self.token_acc.owner != program_id {
+            return Err(InvalidTokenAccount.into());
+        }
+        check_account_size(self.token_acc, TOKEN_ACCOUNT_SIZE)?;
+        let token_account = self.token_acc.data.borrow().as_ptr() as *mut TokenAccount;
+        if (*token_account).tag != TOKEN_TAG
+            || (*token_account).mint != *self.hype_mint_acc.key
+            || (*token_account).program_address != *self.hype_program_acc.key
+        {
+            return Err(InvalidTokenAccount.into());
+        }
+        Ok(token_account)
+    }
+
+    /// Fee tier of this client, 0 without a discount.
+    pub unsafe fn fee_tier(&self) -> u32 {
+        let count = ((*self.root).fee_tiers_count as usize).min(MAX_FEE_TIERS);
+        let tiers = (*self.root).fee_tiers;
+        fee_tier(&tiers[..count], (*self.client).all_time_base_crncy_volume)
+    }
+
+    /// Curve of this root with the `fee_rate` of the client's fee tier.
+    pub unsafe fn curve_params(&self, fee_tier: u32) -> CurveParams {
+        let mut params = CurveParams::from_root(&*self.root);
+        if fee_tier > 0 {
+            params.fee_rate = (*self.root).fee_tiers[fee_tier as usize - 1].fee_rate;
+        }
+        params
+    }
+
+    /// Fee split of a trade by this client, including its active referral levels.
+    pub unsafe fn fee_split(&self, total_fees: u64) -> FeeSplit {
+        let fee_ratio = (*self.root).fee_ratio;
+        if (*self.client).ref_stop <= self.time {
+            return split_fees(total_fees, fee_ratio, None);
+        }
+        let levels = (*self.client)
+            .ref_uplines
+            .iter()
+            .take_while(|u| **u != Pubkey::default())
+            .count();
+        let ratios = (*self.client).ref_upline_ratios;
+        split_fees(
+            total_fees,
+            fee_ratio,
+            Some(((*self.client).ref_discount, (*self.client).ref_ratio)),
+        )
+        .with_upline_fees(&ratios[..levels])
+    }
+
+    /// Books the referral fees of this trade to the client's referrer and uplines, whose
+    /// referrer accounts `Context::load` opened for an active referral.
+    /// The fees stay in the vault until each referrer claims them.
+    pub unsafe fn accrue_ref_fees(&self, fee_split: &FeeSplit) -> ProgramResult {
+        let mut ref_fees = 0;
+        if fee_split.ref_fees > 0 {
+            (*self.referrer).accrue(fee_split.ref_fees, self.slot, self.time)?;
+            ref_fees += fee_split.ref_fees;
+        }
+        for (upline, fees) in self.uplines.iter().zip(fee_split.upline_fees.iter()) {
+            if *fees == 0 {
+                continue;
+            }
+            (**upline).accrue(*fees, self.slot, self.time)?;
+            ref_fees += *fees;
+        }
+        (*self.client).ref_paid += ref_fees;
+        (*self.root).ref_fees = (*self.root)
+            .ref_fees
+            .checked_add(ref_fees)
+            .ok_or(ArithmeticOverflow)?;
+        Ok(())
+    }
+
+    /// Creator share of the trading fees on `token`: the root `creator_fee_ratio`, capped
+    /// by the token's network. Creators earn nothing on their own trades.
+    pub unsafe fn creator_fee_ratio(&self, token: &TokenAccount) -> Result<f64, ProgramError> {
+        if token.creator == *self.signer.key || (*self.root).creator_fee_ratio <= 0.0 {
+            return Ok(0.0);
+        }
+        let network_record = read_network_record(self.root_acc, token.network)?;
+        Ok((*self.root)
+            .creator_fee_ratio
+            .min(network_record.creator_fee_cap))
+    }
+
+    /// Books the creator fees of this trade to `token`.
+    /// The fees stay in the vault until the creator claims them.
+    pub unsafe fn accrue_creator_fees(
+        &self,
+        token: &mut TokenAccount,
+        fee_split: &FeeSplit,
+    ) -> ProgramResult {
+        if fee_split.creator_fees == 0 {
+            return Ok(());
+        }
+        token.creator_fees = token
+            .creator_fees
+            .checked_add(fee_split.creator_fees)
+            .ok_or(ArithmeticOverflow)?;
+        token.all_time_creator_fees += fee_split.creator_fees;
+        (*self.root).creator_fees = (*self.root)
+            .creator_fees
+            .checked_add(fee_split.creator_fees)
+            .ok_or(ArithmeticOverflow)?;
+        Ok(())
+    }
+}
+
+/// Outcome of one buy or sell on the curve or in a pool. `payment` is what the client paid,
+/// fees included, or received, fees deducted.
+pub struct Fill {
+    pub amount: u64,
+    pub base_crncy_amount: u64,
+    pub payment: u64,
+    pub price_before: f64,
+    pub price_after: f64,
+}
+
+/// Return data of `cpi_trade`. `payment` is what the client paid, fees included, or
+/// received, fees deducted; `supply` is the token supply after the trade.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct TradeResult {
+    pub amount: u64,
+    pub base_crncy_amount: u64,
+    pub fees: u64,
+    pub payment: u64,
+    pub supply: u64,
+}
+
+impl TradeResult {
+    pub fn to_bytes(&self) -> [u8; TRADE_RESULT_SIZE] {
+        let mut data = [0; TRADE_RESULT_SIZE];
+        data[0..8].copy_from_slice(&self.amount.to_le_bytes());
+        data[8..16].copy_from_slice(&self.base_crncy_amount.to_le_bytes());
+        data[16..24].copy_from_slice(&self.fees.to_le_bytes());
+        data[24..32].copy_from_slice(&self.payment.to_le_bytes());
+        data[32..40].copy_from_slice(&self.supply.to_le_bytes());
+        data
+    }
+
+    pub fn from_bytes(data: &[u8]) -> Option<TradeResult> {
+        if data.len() != TRADE_RESULT_SIZE {
+            return None;
+        }
+        let field =
+            |offset: usize| u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap());
+        Some(TradeResult {
+            amount: field(0),
+            base_crncy_amount: field(8),
+            fees: field(16),
+            payment: field(24),
+            supply: field(32),
+        })
+    }
+}
+
+/// Fails with `error` if the `tag` registry account `registry_acc` of `key` is held by
+/// a wallet other than `wallet`. Nicknames and referral codes share one namespace, so
+/// nobody can register another wallet's nickname as a code or take a code as a nickname.
+pub unsafe fn check_name_free(
+    program_id: &Pubkey,
+    version: u32,
+    tag: u32,
+    registry_acc: &AccountInfo,
+    key: &[u8; NICKNAME_STRING_LENGTH],
+    wallet: &Pubkey,
+    error: HypeError,
+) -> ProgramResult {
+    let seed = get_seed_by_tag(version, tag);
+    let (address, _) = Pubkey::find_program_address(&[&seed, key], program_id);
+    if address != *registry_acc.key {
+        return Err(InvalidAccountKey.into());
+    }
+    if registry_acc.owner != program_id {
+        return Ok(());
+    }
+    let (holder_tag, holder) = match tag {
+        NICKNAME_TAG => {
+            check_account_size(registry_acc, NICKNAME_ACCOUNT_SIZE)?;
+            let nickname_account = registry_acc.data.borrow().as_ptr() as *const NicknameAccount;
+            ((*nickname_account).tag, (*nickname_account).wallet)
+        }
+        _ => {
+            check_account_size(registry_acc, REF_CODE_ACCOUNT_SIZE)?;
+            let code_account = registry_acc.data.borrow().as_ptr() as *const RefCodeAccount;
+            ((*code_account).tag, (*code_account).wallet)
+        }
+    };
+    if holder_tag == tag && holder != *wallet {
+        return Err(error.into());
+    }
+    Ok(())
+}
+
+/// Reserves `nickname` for the client of `signer` by creating its registry account,
+/// unless another wallet registered it as a referral code (`code_acc`).
+pub unsafe fn claim_nickname<'info>(
+    program_id: &Pubkey,
+    version: u32,
+    signer: &AccountInfo<'info>,
+    nickname_acc: &AccountInfo<'info>,
+    code_acc: &AccountInfo<'info>,
+    nickname: &[u8],
+    slot: u64,
+    time: u32,
+) -> ProgramResult {
+    let key = normalize_nickname(nickname)?;
+    check_name_free(
+        program_id,
+        version,
+        REF_CODE_TAG,
+        code_acc,
+        &key,
+        signer.key,
+        NicknameAlreadyTaken,
+    )?;
+    let nickname_seed = get_seed_by_tag(version, NICKNAME_TAG);
+    let (nickname_address, nickname_bump_seed) =
+        Pubkey::find_program_address(&[&nickname_seed, &key], program_id);
+    if nickname_address != *nickname_acc.key {
+        return Err(InvalidAccountKey.into());
+    }
+    if nickname_acc.owner != &system_program::ID {
+        return Err(NicknameAlreadyTaken.into());
+    }
+    let rent = &Rent::default();
+    let nickname_lamports = rent.minimum_balance(NICKNAME_ACCOUNT_SIZE);
+    invoke_signed(
+        &system_instruction::create_account(
+            signer.key,
+            nickname_acc.key,
+            nickname_lamports,
+            NICKNAME_ACCOUNT_SIZE as u64,
+            program_id,
+        ),
+        &[signer.clone(), nickname_acc.clone()],
+        &[&[&nickname_seed, &key, &[nickname_bump_seed]]],
+    )?;
+    *(nickname_acc.data.borrow().as_ptr() as *mut NicknameAccount) = NicknameAccount {
+        tag: NICKNAME_TAG,
+        version: version,
+        wallet: *signer.key,
+        nickname: key,
+        slot: slot,
+        time: time,
+        reserved: 0,
+    };
+    Ok(())
+}
+
+/// Closes the registry account of the normalized nickname `key` into `signer`.
+/// An entry the signer does not hold is left as it is.
+pub unsafe fn release_nickname(
+    program_id: &Pubkey,
+    version: u32,
+    signer: &AccountInfo,
+    nickname_acc: &AccountInfo,
+    key: &[u8; NICKNAME_STRING_LENGTH],
+) -> ProgramResult {
+    let nickname_seed = get_seed_by_tag(version, NICKNAME_TAG);
+    let (nickname_address, _) = Pubkey::find_program_address(&[&nickname_seed, key], program_id);
+    if nickname_address != *nickname_acc.key {
+        return Err(InvalidAccountKey.into());
+    }
+    if nickname_acc.owner != program_id {
+        return Ok(());
+    }
+    check_account_size(nickname_acc, NICKNAME_ACCOUNT_SIZE)?;
+    let nickname_account = nickname_acc.data.borrow().as_ptr() as *const NicknameAccount;
+    if (*nickname_account).tag != NICKNAME_TAG || (*nickname_account).wallet != *signer.key {
+        return Ok(());
+    }
+    let lamports = nickname_acc.lamports();
+    **signer.lamports.borrow_mut() = signer
+        .lamports()
+        .checked_add(lamports)
+        .ok_or(ArithmeticOverflow)?;
+    **nickname_acc.lamports.borrow_mut() = 0;
+    nickname_acc.data.borrow_mut().fill(0);
+    Ok(())
+}
+
+/// Wallet named by a trade's `ref_acc`: a registered referral code resolves to the
+/// wallet that registered it, any other account is the referrer wallet itself.
+pub unsafe fn resolve_ref_wallet(
+    program_id: &Pubkey,
+    root: &RootAccount,
+    ref_acc: &AccountInfo,
+) -> Result<Pubkey, ProgramError> {
+    if ref_acc.owner != program_id {
+        return Ok(*ref_acc.key);
+    }
+    check_account_size(ref_acc, REF_CODE_ACCOUNT_SIZE)?;
+    let code_account = ref_acc.data.borrow().as_ptr() as *const RefCodeAccount;
+    if (*code_account).tag != REF_CODE_TAG || (*code_account).version != root.version {
+        return Err(InvalidRefCode.into());
+    }
+    Ok((*code_account).wallet)
+}
+
+/// Upline wallets of a new client, read from its referrer's client account: the
+/// referrer's own referrer first, then that one's, up to `ref_depth` levels in total.
+pub unsafe fn resolve_ref_uplines(
+    program_id: &Pubkey,
+    root: &RootAccount,
+    ref_client_acc: &AccountInfo,
+    ref_wallet: &Pubkey,
+    wallet: &Pubkey,
+) -> Result<[Pubkey; MAX_REF_DEPTH - 1], ProgramError> {
+    let mut uplines = [Pubkey::default(); MAX_REF_DEPTH - 1];
+    if root.ref_depth < 2 {
+        return Ok(uplines);
+    }
+    let client_seed = get_seed_by_tag(root.version, CLIENT_TAG);
+    check_account(ref_client_acc, ref_wallet, program_id, &client_seed)?;
+    if ref_client_acc.owner != program_id {
+        // The referrer has never traded, so nobody referred it
+        return Ok(uplines);
+    }
+    check_account_size(ref_client_acc, CLIENT_ACCOUNT_SIZE)?;
+    let ref_client = ref_client_acc.data.borrow().as_ptr() as *const ClientAccount;
+    if (*ref_client).tag != CLIENT_TAG {
+        return Err(InvalidClientAccount.into());
+    }
+    let levels = (root.ref_depth as usize - 1).min(MAX_REF_DEPTH - 1);
+    for i in 0..levels {
+        let upline = if i == 0 {
+            (*ref_client).ref_address
+        } else {
+            (*ref_client).ref_uplines[i - 1]
+        };
+        // The chain ends at the first empty level or at anything leading back into it
+        if upline == Pubkey::default()
+            || upline == *wallet
+            || upline == *ref_wallet
+            || uplines[..i].contains(&upline)
+        {
+            break;
+        }
+        uplines[i] = upline;
+    }
+    Ok(uplines)
+}
+
+/// Creates the `size` bytes program account at the PDA `account` of `seeds` on the rent of
+/// `payer`. Anybody can send lamports to a PDA before it is created, which would fail a plain
+/// `create_account`, so a funded address is only topped up to the rent before being
+/// allocated and assigned.
+pub unsafe fn create_pda_account<'info>(
+    program_id: &Pubkey,
+    payer: &AccountInfo<'info>,
+    account: &AccountInfo<'info>,
+    size: usize,
+    seeds: &[&[u8]],
+) -> ProgramResult {
+    let rent = &Rent::default();
+    let lamports = rent.minimum_balance(size);
+    if account.lamports() == 0 {
+        return invoke_signed(
+            &system_instruction::create_account(
+                payer.key,
+                account.key,
+                lamports,
+                size as u64,
+                program_id,
+            ),
+            &[payer.clone(), account.clone()],
+            &[seeds],
+        );
+    }
+    let shortfall = lamports.saturating_sub(account.lamports());
+    if shortfall > 0 {
+        invoke(
+            &system_instruction::transfer(payer.key, account.key, shortfall),
+            &[payer.clone(), account.clone()],
+        )?;
+    }
+    invoke_signed(
+        &system_instruction::allocate(account.key, size as u64),
+        std::slice::from_ref(account),
+        &[seeds],
+    )?;
+    invoke_signed(
+        &system_instruction::assign(account.key, program_id),
+        std::slice::from_ref(account),
+        &[seeds],
+    )
+}
+
+/// Opens the referrer account of `wallet` on the rent of `payer` unless it exists already.
+pub unsafe fn create_referrer_account<'info>(
+    program_id: &Pubkey,
+    version: u32,
+    payer: &AccountInfo<'info>,
+    referrer_acc: &AccountInfo<'info>,
+    wallet: &Pubkey,
+    slot: u64,
+    time: u32,
+) -> ProgramResult {
+    let referrer_seed = get_seed_by_tag(version, REFERRER_TAG);
+    let referrer_bump_seed = check_account(referrer_acc, wallet, program_id, &referrer_seed)?;
+    if referrer_acc.owner != &system_program::ID {
+        return Ok(());
+    }
+    create_pda_account(
+        program_id,
+        payer,
+        referrer_acc,
+        REFERRER_ACCOUNT_SIZE,
+        &[&referrer_seed, wallet.as_ref(), &[referrer_bump_seed]],
+    )?;
+    *(referrer_acc.data.borrow().as_ptr() as *mut ReferrerAccount) = ReferrerAccount {
+        tag: REFERRER_TAG,
+        version: version,
+        wallet: *wallet,
+        fees: 0,
+        all_time_fees: 0,
+        all_time_claimed: 0,
+        slot: slot,
+        time: time,
+        reserved: 0,
+    };
+    Ok(())
+}
+
+/// Accounts paying base currency out of the vault to the associated account of `owner`.
+pub struct VaultPayout<'a, 'info> {
+    pub payer: &'a AccountInfo<'info>,
+    pub owner: &'a AccountInfo<'info>,
+    pub base_crncy_mint_acc: &'a AccountInfo<'info>,
+    pub base_crncy_program_acc: &'a AccountInfo<'info>,
+    pub associated_token_acc: &'a AccountInfo<'info>,
+    pub token_program_id: &'a AccountInfo<'info>,
+    pub hype_auth_acc: &'a AccountInfo<'info>,
+}
+
+impl<'a, 'info> VaultPayout<'a, 'info> {
+    /// Transfers `amount` from the vault, first creating the owner's associated account on
+    /// the payer's rent if it does not exist yet.
+    pub unsafe fn pay(
+        &self,
+        program_id: &Pubkey,
+        root: &RootAccount,
+        amount: u64,
+    ) -> ProgramResult {
+        let expected_address = get_associated_token_address_with_program_id(
+            self.owner.key,
+            self.base_crncy_mint_acc.key,
+            self.token_program_id.key,
+        );
+        if expected_address != *self.associated_token_acc.key {
+            return Err(InvalidAssociatedTokenAddress.into());
+        }
+        let (hype_auth, hype_bump_seed) = Pubkey::find_program_address(&[HYPE_SEED], program_id);
+        if hype_auth != *self.hype_auth_acc.key {
+            return Err(InvalidHypeAuthority.into());
+        }
+        if *self.associated_token_acc.owner == system_program::ID {
+            invoke(
+                &create_associated_token_account(
+                    self.payer.key,
+                    self.owner.key,
+                    self.base_crncy_mint_acc.key,
+                    self.token_program_id.key,
+                ),
+                &[
+                    self.payer.clone(),
+                    self.associated_token_acc.clone(),
+                    self.owner.clone(),
+                    self.base_crncy_mint_acc.clone(),
+                    self.token_program_id.clone(),
+                ],
+            )?;
+        }
+        if amount == 0 {
+            return Ok(());
+        }
+        if *self.base_crncy_program_acc.owner == spl_token_2022::id() {
+            let transfer_ix = spl_token_2022::instruction::transfer_checked(
+                &spl_token_2022::id(),
+                self.base_crncy_program_acc.key,
+                self.base_crncy_mint_acc.key,
+                self.associated_token_acc.key,
+                &hype_auth,
+                &[&hype_auth],
+                amount,
+                root.decimals as u8,
+            )?;
+            invoke_signed(
+                &transfer_ix,
+                &[
+                    self.token_program_id.clone(),
+                    self.base_crncy_program_acc.clone(),
+                    self.base_crncy_mint_acc.clone(),
+                    self.associated_token_acc.clone(),
+                    self.hype_auth_acc.clone(),
+                ],
+                &[&[&HYPE_SEED[..], &[hype_bump_seed]]],
+            )
+        } else {
+            let transfer_ix = spl_token::instruction::transfer(
+                &spl_token::id(),
+                self.base_crncy_program_acc.key,
+                self.associated_token_acc.key,
+                &hype_auth,
+                &[&hype_auth],
+                amount,
+            )?;
+            invoke_signed(
+                &transfer_ix,
+                &[
+                    self.base_crncy_program_acc.clone(),
+                    self.associated_token_acc.clone(),
+                    self.hype_auth_acc.clone(),
+                    self.token_program_id.clone(),
+                ],
+                &[&[&HYPE_SEED[..], &[hype_bump_seed]]],
+            )
+        }
+    }
+}
+
+/// Loads the referrer account of `wallet`, opening it at the cost of `payer` if the
+/// referrer has not opened it yet.
+unsafe fn load_referrer_account<'info>(
+    program_id: &Pubkey,
+    root: &RootAccount,
+    payer: &AccountInfo<'info>,
+    referrer_acc: &AccountInfo<'info>,
+    wallet: &Pubkey,
+    slot: u64,
+    time: u32,
+) -> Result<*mut ReferrerAccount, ProgramError> {
+    create_referrer_account(
+        program_id,
+        root.version,
+        payer,
+        referrer_acc,
+        wallet,
+        slot,
+        time,
+    )?;
+    if referrer_acc.owner != program_id {
+        return Err(InvalidReferrerAccount.into());
+    }
+    check_account_size(referrer_acc, REFERRER_ACCOUNT_SIZE)?;
+    let referrer = referrer_acc.data.borrow().as_ptr() as *mut ReferrerAccount;
+    if (*referrer).tag != REFERRER_TAG || (*referrer).wallet != *wallet {
+        return Err(InvalidReferrerAccount.into());
+    }
+    Ok(referrer)
+}
+
+/// Position of `signer` in `hype_mint`, created on its first tracked trade at the cost of `payer`.
+pub unsafe fn load_position_account<'info>(
+    program_id: &Pubkey,
+    root: &RootAccount,
+    payer: &AccountInfo<'info>,
+    signer: &AccountInfo<'info>,
+    position_acc: &AccountInfo<'info>,
+    hype_mint: &Pubkey,
+    slot: u64,
+    time: u32,
+) -> Result<*mut PositionAccount, ProgramError> {
+    let position_seed = get_seed_by_tag(root.version, POSITION_TAG);
+    let (position_address, position_bump_seed) = Pubkey::find_program_address(
+        &[&position_seed, signer.key.as_ref(), hype_mint.as_ref()],
+        program_id,
+    );
+    if position_address != *position_acc.key {
+        return Err(InvalidAccountKey.into());
+    }
+    if position_acc.owner == &system_program::ID {
+        create_pda_account(
+            program_id,
+            payer,
+            position_acc,
+            POSITION_ACCOUNT_SIZE,
+            &[
+                &position_seed,
+                signer.key.as_ref(),
+                hype_mint.as_ref(),
+                &[position_bump_seed],
+            ],
+        )?;
+        *(position_acc.data.borrow().as_ptr() as *mut PositionAccount) = PositionAccount {
+            tag: POSITION_TAG,
+            version: root.version,
+            wallet: *signer.key,
+            mint: *hype_mint,
+            quantity: 0,
+            cost_basis: 0,
+            total_cost: 0,
+            total_proceeds: 0,
+            realized_pnl: 0,
+            slot: slot,
+            time: time,
+            trades_count: 0,
+        };
+    } else if position_acc.owner != program_id {
+        return Err(InvalidPositionAccount.into());
+    }
+    check_account_size(position_acc, POSITION_ACCOUNT_SIZE)?;
+    let position = position_acc.data.borrow().as_ptr() as *mut PositionAccount;
+    if (*position).tag != POSITION_TAG
+        || (*position).wallet != *signer.key
+        || (*position).mint != *hype_mint
+    {
+        return Err(InvalidPositionAccount.into());
+    }
+    Ok(position)
+}
+
+/// Launch buy account of `signer` for the token of `hype_mint`, created on its first use.
+pub unsafe fn load_launch_buy_account<'info>(
+    program_id: &Pubkey,
+    root: &RootAccount,
+    payer: &AccountInfo<'info>,
+    signer: &AccountInfo<'info>,
+    launch_buy_acc: &AccountInfo<'info>,
+    hype_mint: &Pubkey,
+) -> Result<*mut LaunchBuyAccount, ProgramError> {
+    let launch_buy_seed = get_seed_by_tag(root.version, LAUNCH_BUY_TAG);
+    let (launch_buy_address, launch_buy_bump_seed) = Pubkey::find_program_address(
+        &[&launch_buy_seed, signer.key.as_ref(), hype_mint.as_ref()],
+        program_id,
+    );
+    if launch_buy_address != *launch_buy_acc.key {
+        return Err(InvalidAccountKey.into());
+    }
+    if launch_buy_acc.owner == &system_program::ID {
+        create_pda_account(
+            program_id,
+            payer,
+            launch_buy_acc,
+            LAUNCH_BUY_ACCOUNT_SIZE,
+            &[
+                &launch_buy_seed,
+                signer.key.as_ref(),
+                hype_mint.as_ref(),
+                &[launch_buy_bump_seed],
+            ],
+        )?;
+        *(launch_buy_acc.data.borrow().as_ptr() as *mut LaunchBuyAccount) = LaunchBuyAccount {
+            tag: LAUNCH_BUY_TAG,
+            version: root.version,
+            wallet: *signer.key,
+            mint: *hype_mint,
+            bought: 0,
+            slot: 0,
+            last_buy_time: 0,
+            buys_count: 0,
+        };
+    } else if launch_buy_acc.owner != program_id {
+        return Err(InvalidLaunchBuyAccount.into());
+    }
+    check_account_size(launch_buy_acc, LAUNCH_BUY_ACCOUNT_SIZE)?;
+    let launch_buy = launch_buy_acc.data.borrow().as_ptr() as *mut LaunchBuyAccount;
+    if (*launch_buy).tag != LAUNCH_BUY_TAG
+        || (*launch_buy).wallet != *signer.key
+        || (*launch_buy).mint != *hype_mint
+    {
+        return Err(InvalidLaunchBuyAccount.into());
+    }
+    Ok(launch_buy)
+}
+
+/// Delegate account through which `delegate` trades for `wallet`, created by `set_delegate`.
+pub unsafe fn load_delegate_account(
+    program_id: &Pubkey,
+    delegate_acc: &AccountInfo,
+    wallet: &Pubkey,
+    delegate: &Pubkey,
+) -> Result<*mut DelegateAccount, ProgramError> {
+    if delegate_acc.owner != program_id {
+        return Err(InvalidDelegateAccount.into());
+    }
+    check_account_size(delegate_acc, DELEGATE_ACCOUNT_SIZE)?;
+    let delegate_account = delegate_acc.data.borrow().as_ptr() as *mut DelegateAccount;
+    if (*delegate_account).tag != DELEGATE_TAG
+        || (*delegate_account).wallet != *wallet
+        || (*delegate_account).delegate != *delegate
+    {
+        return Err(InvalidDelegateAccount.into());
+    }
+    Ok(delegate_account)
+}
+
+/// Pool of `hype_mint`, created by `graduate`.
+pub unsafe fn load_pool_account(
+    program_id: &Pubkey,
+    pool_acc: &AccountInfo,
+    hype_mint: &Pubkey,
+) -> Result<*mut PoolAccount, ProgramError> {
+    if pool_acc.owner != program_id {
+        return Err(InvalidPoolAccount.into());
+    }
+    check_account_size(pool_acc, POOL_ACCOUNT_SIZE)?;
+    let pool = pool_acc.data.borrow().as_ptr() as *mut PoolAccount;
+    if (*pool).tag != POOL_TAG || (*pool).mint != *hype_mint {
+        return Err(InvalidPoolAccount.into());
+    }
+    Ok(pool)
+}
+
+/// `TRADE_EXTRA_*` flags of a trade at `offset` of its data; none if the data ends there.
+pub fn read_trade_extras(instruction_data: &[u8], offset: usize) -> Result<u32, ProgramError> {
+    if instruction_data.len() <= offset {
+        return Ok(0);
+    }
+    if instruction_data.len() < offset + 4 {
+        return Err(InvalidDataLength.into());
+    }
+    let flags = u32::from_le_bytes(instruction_data[offset..offset + 4].try_into().unwrap());
+    if flags & !TRADE_EXTRAS_MASK != 0 {
+        return Err(InvalidTradeExtras.into());
+    }
+    Ok(flags)
+}
+
+/// Optional accounts passed after a trade's accounts.
+pub struct TradeExtras<'a, 'info> {
+    pub position: Option<&'a AccountInfo<'info>>,
+    pub price_history: Option<&'a AccountInfo<'info>>,
+    pub launch_allowance: Option<&'a AccountInfo<'info>>,
+    pub launch_buy: Option<&'a AccountInfo<'info>>,
+}
+
+/// Optional accounts after the trade accounts: a position account, a price history, a
+/// launch allowance and a launch buy account, in this order, each passed only if `flags`
+/// declares it.
+pub fn next_trade_extras<'a, 'info>(
+    accounts_iter: &mut Iter<'a, AccountInfo<'info>>,
+    flags: u32,
+) -> Result<TradeExtras<'a, 'info>, ProgramError> {
+    let mut next = |flag: u32| -> Result<Option<&'a AccountInfo<'info>>, ProgramError> {
+        if flags & flag == 0 {
+            return Ok(None);
+        }
+        next_account_info(accounts_iter).map(Some)
+    };
+    Ok(TradeExtras {
+        position: next(TRADE_EXTRA_POSITION)?,
+        price_history: next(TRADE_EXTRA_PRICE_HISTORY)?,
+        launch_allowance: next(TRADE_EXTRA_LAUNCH_ALLOWANCE)?,
+        launch_buy: next(TRADE_EXTRA_LAUNCH_BUY)?,
+    })
+}
+
+/// Position account and price history of a trade, null if it keeps none.
+#[derive(Clone, Copy)]
+pub struct TradeRecords {
+    pub position: *mut PositionAccount,
+    pub price_history: *mut PriceHistoryAccount,
+}
+
+impl TradeRecords {
+    pub unsafe fn record_buy(&self, fill: &Fill, slot: u64, time: u32) -> ProgramResult {
+        if !self.position.is_null() {
+            (*self.position).add_buy(fill.amount, fill.payment, slot, time)?;
+        }
+        self.record_price(fill, slot, time);
+        Ok(())
+    }
+
+    pub unsafe fn record_sell(&self, fill: &Fill, slot: u64, time: u32) -> ProgramResult {
+        if !self.position.is_null() {
+            (*self.position).add_sell(fill.amount, fill.payment, slot, time)?;
+        }
+        self.record_price(fill, slot, time);
+        Ok(())
+    }
+
+    unsafe fn record_price(&self, fill: &Fill, slot: u64, time: u32) {
+        if !self.price_history.is_null() {
+            (*self.price_history).record(
+                fill.price_before,
+                fill.price_after,
+                fill.base_crncy_amount,
+                fill.amount,
+                slot,
+                time,
+            );
+        }
+    }
+}
+
+/// Price history of `token`, created by `init_price_history`; null if the token has none.
+/// Once it has one every trade of the token has to pass it.
+pub unsafe fn load_price_history(
+    program_id: &Pubkey,
+    token: &TokenAccount,
+    price_history_acc: Option<&AccountInfo>,
+) -> Result<*mut PriceHistoryAccount, ProgramError> {
+    let price_history_acc = match price_history_acc {
+        Some(price_history_acc) => price_history_acc,
+        None if token.price_history != 0 => return Err(PriceHistoryRequired.into()),
+        None => return Ok(std::ptr::null_mut()),
+    };
+    if price_history_acc.owner != program_id {
+        return Err(InvalidPriceHistoryAccount.into());
+    }
+    check_account_size(price_history_acc, PRICE_HISTORY_ACCOUNT_SIZE)?;
+    let price_history = price_history_acc.data.borrow().as_ptr() as *mut PriceHistoryAccount;
+    if (*price_history).tag != PRICE_HISTORY_TAG || (*price_history).mint != token.mint {
+        return Err(InvalidPriceHistoryAccount.into());
+    }
+    Ok(price_history)
+}
+
+pub unsafe fn check_holder_account(
+    account: &AccountInfo,
+    program_id: &Pubkey,
     writable: bool,
 ) -> ProgramResult {
     if account.owner != program_id
         || account.is_writable != writable
+        || account.data_len() < HOLDER_ACCOUNT_SIZE
         || *(account.data.borrow().as_ptr() as *const u32) != HOLDER_TAG
     {
         return Err(InvalidHolderAccount.into());
@@ -436,8 +2474,45 @@ pub unsafe fn check_holder_account(
     Ok(())
 }
 
+/// Checks the owner, tag and layout of a root account. A root still of the original
+/// layout is shorter than the current layout needs for its networks and has to go
+/// through `MigrateAccount` before anything reads the fields appended since.
+pub unsafe fn load_root_account(
+    program_id: &Pubkey,
+    root_acc: &AccountInfo,
+) -> Result<*mut RootAccount, ProgramError> {
+    if root_acc.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    check_account_size(root_acc, ROOT_ACCOUNT_SIZE)?;
+    let root = root_acc.data.borrow().as_ptr() as *mut RootAccount;
+    if (*root).tag != ROOT_TAG {
+        return Err(InvalidAccountTag.into());
+    }
+    let size = root_account_offsets::NETWORK_RECORDS
+        + (*root).networks_count as usize * NETWORK_RECORD_SIZE;
+    if root_acc.data_len() < size {
+        return Err(AccountNotMigrated.into());
+    }
+    Ok(root)
+}
+
+/// Copies network record `network` out of a root account whose size has already been checked.
+pub unsafe fn read_network_record(
+    root_acc: &AccountInfo,
+    network: u32,
+) -> Result<NetworkRecord, ProgramError> {
+    let root = root_acc.data.borrow().as_ptr() as *const RootAccount;
+    if network >= (*root).networks_count {
+        return Err(InvalidNetworkId.into());
+    }
+    let offset = root_account_offsets::NETWORK_RECORDS + network as usize * NETWORK_RECORD_SIZE;
+    check_account_size(root_acc, offset + NETWORK_RECORD_SIZE)?;
+    Ok(*(root_acc.data.borrow()[offset..].as_ptr() as *const NetworkRecord))
+}
+
 pub fn check_holder_admin(account: &AccountInfo) -> ProgramResult {
-    let admin = Pubkey::from_str("5V5zbRbs7wFAu5bE2JgYC3aLXKmfwA1rKfg1cXCsuk1p").unwrap();
+    let admin = Pubkey::from_str(HOLDER_ADMIN).unwrap();
     if *account.key != admin || !account.is_writable || !account.is_signer {
         return Err(InvalidHolderAdmin.into());
     }
@@ -461,7 +2536,7 @@ pub fn check_name(
         let mut valid = false;
         while mask_offset < MASK_STRING_LENGTH {
             mask_offset += 1;
-            if mask[mask_offset] == 0 {
+            if mask_offset == MASK_STRING_LENGTH || mask[mask_offset] == 0 {
                 break;
             }
             if name[name_offset] == mask[mask_offset] {
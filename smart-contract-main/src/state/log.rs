@@ -64,6 +64,7 @@ pub unsafe fn log_mint(
     base_crncy_amount: u64,
     time: u32,
     slot: u64,
+    fee_tier: u32,
 ) {
     solana_program::log::sol_log_data(&[
         &[4],
@@ -84,6 +85,7 @@ pub unsafe fn log_mint(
         &time.to_le_bytes(),
         &slot.to_le_bytes(),
         &wallet.to_bytes(),
+        &fee_tier.to_le_bytes(),
     ]);
 }
 
@@ -105,6 +107,7 @@ pub unsafe fn log_burn(
     base_crncy_amount: u64,
     time: u32,
     slot: u64,
+    fee_tier: u32,
 ) {
     solana_program::log::sol_log_data(&[
         &[5],
@@ -125,5 +128,253 @@ pub unsafe fn log_burn(
         &time.to_le_bytes(),
         &slot.to_le_bytes(),
         &wallet.to_bytes(),
+        &fee_tier.to_le_bytes(),
+    ]);
+}
+
+pub unsafe fn log_claim_ref_fees(
+    wallet: &Pubkey,
+    amount: u64,
+    all_time_claimed: u64,
+    time: u32,
+    slot: u64,
+) {
+    solana_program::log::sol_log_data(&[
+        &[6],
+        &wallet.to_bytes(),
+        &amount.to_le_bytes(),
+        &all_time_claimed.to_le_bytes(),
+        &time.to_le_bytes(),
+        &slot.to_le_bytes(),
+    ]);
+}
+
+pub unsafe fn log_claim_creator_fees(
+    token_id: u64,
+    wallet: &Pubkey,
+    amount: u64,
+    all_time_fees: u64,
+    time: u32,
+    slot: u64,
+) {
+    solana_program::log::sol_log_data(&[
+        &[10],
+        &token_id.to_le_bytes(),
+        &wallet.to_bytes(),
+        &amount.to_le_bytes(),
+        &all_time_fees.to_le_bytes(),
+        &time.to_le_bytes(),
+        &slot.to_le_bytes(),
+    ]);
+}
+
+pub unsafe fn log_close_client(client_id: u64, wallet: &Pubkey, time: u32, slot: u64) {
+    solana_program::log::sol_log_data(&[
+        &[11],
+        &client_id.to_le_bytes(),
+        &wallet.to_bytes(),
+        &time.to_le_bytes(),
+        &slot.to_le_bytes(),
+    ]);
+}
+
+pub unsafe fn log_retire_token(
+    token_id: u64,
+    network_id: u32,
+    mint: &Pubkey,
+    creator: &Pubkey,
+    address: &[u8],
+    time: u32,
+    slot: u64,
+) {
+    solana_program::log::sol_log_data(&[
+        &[12],
+        &token_id.to_le_bytes(),
+        &network_id.to_le_bytes(),
+        &mint.to_bytes(),
+        &creator.to_bytes(),
+        address,
+        &time.to_le_bytes(),
+        &slot.to_le_bytes(),
+    ]);
+}
+
+pub unsafe fn log_register_ref_code(wallet: &Pubkey, code: &[u8], time: u32, slot: u64) {
+    solana_program::log::sol_log_data(&[
+        &[7],
+        &wallet.to_bytes(),
+        code,
+        &time.to_le_bytes(),
+        &slot.to_le_bytes(),
+    ]);
+}
+
+pub unsafe fn log_release_ref_code(wallet: &Pubkey, code: &[u8], time: u32, slot: u64) {
+    solana_program::log::sol_log_data(&[
+        &[8],
+        &wallet.to_bytes(),
+        code,
+        &time.to_le_bytes(),
+        &slot.to_le_bytes(),
+    ]);
+}
+
+pub unsafe fn log_set_referrer(
+    client_id: u64,
+    wallet: &Pubkey,
+    ref_address: &Pubkey,
+    ref_stop: u32,
+    time: u32,
+    slot: u64,
+) {
+    solana_program::log::sol_log_data(&[
+        &[9],
+        &client_id.to_le_bytes(),
+        &wallet.to_bytes(),
+        &ref_address.to_bytes(),
+        &ref_stop.to_le_bytes(),
+        &time.to_le_bytes(),
+        &slot.to_le_bytes(),
+    ]);
+}
+
+pub unsafe fn log_switch(
+    client_id: u64,
+    order_id: u64,
+    from_token_id: u64,
+    to_token_id: u64,
+    wallet: &Pubkey,
+    from_amount: u64,
+    to_amount: u64,
+    base_crncy_amount: u64,
+    fees: u64,
+    time: u32,
+    slot: u64,
+) {
+    solana_program::log::sol_log_data(&[
+        &[13],
+        &client_id.to_le_bytes(),
+        &order_id.to_le_bytes(),
+        &from_token_id.to_le_bytes(),
+        &to_token_id.to_le_bytes(),
+        &wallet.to_bytes(),
+        &from_amount.to_le_bytes(),
+        &to_amount.to_le_bytes(),
+        &base_crncy_amount.to_le_bytes(),
+        &fees.to_le_bytes(),
+        &time.to_le_bytes(),
+        &slot.to_le_bytes(),
+    ]);
+}
+
+pub unsafe fn log_gift(
+    client_id: u64,
+    order_id: u64,
+    token_id: u64,
+    wallet: &Pubkey,
+    recipient: &Pubkey,
+    tokens_amount: u64,
+    base_crncy_amount: u64,
+    time: u32,
+    slot: u64,
+) {
+    solana_program::log::sol_log_data(&[
+        &[14],
+        &client_id.to_le_bytes(),
+        &order_id.to_le_bytes(),
+        &token_id.to_le_bytes(),
+        &wallet.to_bytes(),
+        &recipient.to_bytes(),
+        &tokens_amount.to_le_bytes(),
+        &base_crncy_amount.to_le_bytes(),
+        &time.to_le_bytes(),
+        &slot.to_le_bytes(),
+    ]);
+}
+
+pub unsafe fn log_set_delegate(
+    client_id: u64,
+    wallet: &Pubkey,
+    delegate: &Pubkey,
+    spend_limit: u64,
+    networks: u64,
+    period: u32,
+    expiry: u32,
+    time: u32,
+    slot: u64,
+) {
+    solana_program::log::sol_log_data(&[
+        &[15],
+        &client_id.to_le_bytes(),
+        &wallet.to_bytes(),
+        &delegate.to_bytes(),
+        &spend_limit.to_le_bytes(),
+        &networks.to_le_bytes(),
+        &period.to_le_bytes(),
+        &expiry.to_le_bytes(),
+        &time.to_le_bytes(),
+        &slot.to_le_bytes(),
+    ]);
+}
+
+pub unsafe fn log_revoke_delegate(wallet: &Pubkey, delegate: &Pubkey, time: u32, slot: u64) {
+    solana_program::log::sol_log_data(&[
+        &[16],
+        &wallet.to_bytes(),
+        &delegate.to_bytes(),
+        &time.to_le_bytes(),
+        &slot.to_le_bytes(),
+    ]);
+}
+
+pub unsafe fn log_graduate(
+    order_id: u64,
+    token_id: u64,
+    mint: &Pubkey,
+    base_crncy_reserve: u64,
+    token_reserve: u64,
+    time: u32,
+    slot: u64,
+) {
+    solana_program::log::sol_log_data(&[
+        &[17],
+        &order_id.to_le_bytes(),
+        &token_id.to_le_bytes(),
+        &mint.to_bytes(),
+        &base_crncy_reserve.to_le_bytes(),
+        &token_reserve.to_le_bytes(),
+        &time.to_le_bytes(),
+        &slot.to_le_bytes(),
+    ]);
+}
+
+pub unsafe fn log_swap(
+    client_id: u64,
+    order_id: u64,
+    token_id: u64,
+    wallet: &Pubkey,
+    side: u32,
+    tokens_amount: u64,
+    base_crncy_amount: u64,
+    fees: u64,
+    base_crncy_reserve: u64,
+    token_reserve: u64,
+    time: u32,
+    slot: u64,
+) {
+    solana_program::log::sol_log_data(&[
+        &[18],
+        &client_id.to_le_bytes(),
+        &order_id.to_le_bytes(),
+        &token_id.to_le_bytes(),
+        &wallet.to_bytes(),
+        &side.to_le_bytes(),
+        &tokens_amount.to_le_bytes(),
+        &base_crncy_amount.to_le_bytes(),
+        &fees.to_le_bytes(),
+        &base_crncy_reserve.to_le_bytes(),
+        &token_reserve.to_le_bytes(),
+        &time.to_le_bytes(),
+        &slot.to_le_bytes(),
     ]);
 }
@@ -0,0 +1,155 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use common::*;
+use hype_watch::{program::error::HypeError, program::*};
+use solana_sdk::signature::Signer;
+
+const TRADER_FUNDS: u64 = 1_000_000_000_000_000;
+
+#[tokio::test]
+async fn batch_applies_every_leg() {
+    let mut env = TestEnv::setup(spl_token::id()).await;
+    let params = env.params;
+    let creator = env.new_trader(TRADER_FUNDS).await;
+    let trader = env.new_trader(TRADER_FUNDS).await;
+    let wallet = trader.pubkey();
+    let supply = 1_000_000_000;
+    let (doge, doge_mint, doge_program_acc, _) =
+        env.create_token(&creator, 0, "doge", supply).await.unwrap();
+    let (shib, shib_mint, shib_program_acc, _) =
+        env.create_token(&creator, 0, "shib", supply).await.unwrap();
+    let buy = |token, hype_mint, hype_program_acc, amount, limit| Leg {
+        token,
+        hype_mint,
+        hype_program_acc,
+        side: BATCH_SIDE_BUY,
+        amount,
+        limit,
+    };
+
+    // Two buys in one instruction, also creating the client
+    let amount = 3_000_000;
+    let ix = env.batch_ix(
+        &wallet,
+        &[
+            buy(doge, doge_mint, doge_program_acc, amount, 0),
+            buy(shib, shib_mint, shib_program_acc, 2 * amount, 0),
+        ],
+    );
+    let base = env.base_ata(&wallet);
+    let before = env.balance(&base).await;
+    env.process(&[ix], &[&trader]).await.unwrap();
+    let doge_quote = expected_mint(&params, supply, amount, false);
+    let shib_quote = expected_mint(&params, supply, 2 * amount, false);
+    assert_eq!(
+        before - env.balance(&base).await,
+        doge_quote.base_crncy_amount
+            + doge_quote.fees
+            + shib_quote.base_crncy_amount
+            + shib_quote.fees
+    );
+    assert_eq!(
+        env.balance(&env.hype_ata(&wallet, &doge_mint)).await,
+        amount
+    );
+    assert_eq!(
+        env.balance(&env.hype_ata(&wallet, &shib_mint)).await,
+        2 * amount
+    );
+    assert_eq!(env.client_account(&wallet).await.all_time_trades_count, 2);
+
+    // A sell and a buy; the tight limit of the buy reverts the sell too
+    let sell = Leg {
+        side: BATCH_SIDE_SELL,
+        ..buy(doge, doge_mint, doge_program_acc, amount, 0)
+    };
+    let ix = env.batch_ix(
+        &wallet,
+        &[sell, buy(shib, shib_mint, shib_program_acc, amount, 1)],
+    );
+    assert_hype_error(
+        env.process(&[ix], &[&trader]).await,
+        HypeError::MaxTradeCostExceeded,
+    );
+    assert_eq!(env.token_account(&doge).await.supply, supply + amount);
+    let ix = env.batch_ix(
+        &wallet,
+        &[sell, buy(shib, shib_mint, shib_program_acc, amount, 0)],
+    );
+    env.process(&[ix], &[&trader]).await.unwrap();
+    assert_eq!(env.token_account(&doge).await.supply, supply);
+    assert_eq!(env.token_account(&shib).await.supply, supply + 3 * amount);
+    env.check_invariants(&[(doge, doge_mint), (shib, shib_mint)])
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn malformed_batches_fail() {
+    let mut env = TestEnv::setup(spl_token::id()).await;
+    let trader = env.new_trader(TRADER_FUNDS).await;
+    let wallet = trader.pubkey();
+    let (doge, doge_mint, doge_program_acc, _) =
+        env.create_token(&trader, 0, "doge", 1_000).await.unwrap();
+    let (shib, shib_mint, shib_program_acc, _) =
+        env.create_token(&trader, 0, "shib", 1_000).await.unwrap();
+    let leg = Leg {
+        token: doge,
+        hype_mint: doge_mint,
+        hype_program_acc: doge_program_acc,
+        side: BATCH_SIDE_BUY,
+        amount: 1_000,
+        limit: 0,
+    };
+
+    let ix = env.batch_ix(&wallet, &[leg; MAX_BATCH_LEGS + 1]);
+    assert_hype_error(
+        env.process(&[ix], &[&trader]).await,
+        HypeError::InvalidBatch,
+    );
+    let ix = env.batch_ix(&wallet, &[Leg { side: 2, ..leg }]);
+    assert_hype_error(
+        env.process(&[ix], &[&trader]).await,
+        HypeError::InvalidBatch,
+    );
+
+    // A leg's token account has to match its mint
+    let ix = env.batch_ix(
+        &wallet,
+        &[
+            leg,
+            Leg {
+                token: shib,
+                hype_mint: doge_mint,
+                hype_program_acc: shib_program_acc,
+                ..leg
+            },
+        ],
+    );
+    assert_hype_error(
+        env.process(&[ix], &[&trader]).await,
+        HypeError::InvalidTokenAccount,
+    );
+
+    // and its client account to the mint and the signer
+    let mut ix = env.batch_ix(
+        &wallet,
+        &[
+            leg,
+            Leg {
+                token: shib,
+                hype_mint: shib_mint,
+                hype_program_acc: shib_program_acc,
+                ..leg
+            },
+        ],
+    );
+    let last = ix.accounts.len() - 1;
+    ix.accounts[last].pubkey = env.hype_ata(&wallet, &doge_mint);
+    assert_hype_error(
+        env.process(&[ix], &[&trader]).await,
+        HypeError::InvalidAssociatedTokenAddress,
+    );
+}
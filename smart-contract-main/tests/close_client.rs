@@ -0,0 +1,74 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use common::*;
+use hype_watch::program::error::HypeError;
+use solana_sdk::signature::Signer;
+
+const TRADER_FUNDS: u64 = 1_000_000_000_000_000;
+
+#[tokio::test]
+async fn close_client_refunds_rent() {
+    let mut env = TestEnv::setup(spl_token::id()).await;
+    let referrer = env.new_trader(TRADER_FUNDS).await;
+    let trader = env.new_trader(TRADER_FUNDS).await;
+    let amount = 1_000_000;
+    let (_, hype_mint, hype_program_acc, _) = env
+        .create_token(&referrer, 0, "doge", amount)
+        .await
+        .unwrap();
+    env.change_nickname(&referrer, "Ref").await.unwrap();
+    env.open_referrer_account(&referrer).await;
+    let ix = env.mint_ix(
+        &trader.pubkey(),
+        0,
+        "doge",
+        &hype_mint,
+        &hype_program_acc,
+        amount,
+        0,
+        &[referrer.pubkey()],
+    );
+    let ix = TestEnv::signing_up(ix, &referrer.pubkey());
+    env.process(&[ix], &[&trader]).await.unwrap();
+    let client_id = env.client_account(&referrer.pubkey()).await.id;
+    assert_eq!(env.root_account().await.clients_count, 2);
+
+    // Unclaimed referral fees keep the client open
+    assert_hype_error(env.close_client(&referrer).await, HypeError::UnclaimedFees);
+    env.claim_referral_fees(&referrer).await.unwrap();
+
+    let client = client_address(VERSION, &referrer.pubkey());
+    let nickname = nickname_address(VERSION, "ref");
+    let rent = env.account(&client).await.unwrap().lamports
+        + env.account(&nickname).await.unwrap().lamports;
+    let balance = env.account(&referrer.pubkey()).await.unwrap().lamports;
+    let logs = env.close_client(&referrer).await.unwrap();
+    let event = &events_with_tag(&logs, 11)[0];
+    assert_eq!(field_u64(event, 1), client_id);
+    assert_eq!(event[2], referrer.pubkey().to_bytes());
+    assert!(env.account(&client).await.is_none());
+    assert!(env.account(&nickname).await.is_none());
+    assert_eq!(
+        env.account(&referrer.pubkey()).await.unwrap().lamports,
+        balance + rent
+    );
+    assert_eq!(env.root_account().await.clients_count, 2);
+
+    // Trading again opens a client under a fresh id
+    let ix = env.mint_ix(
+        &referrer.pubkey(),
+        0,
+        "doge",
+        &hype_mint,
+        &hype_program_acc,
+        amount,
+        0,
+        &[],
+    );
+    let logs = env.process(&[ix], &[&referrer]).await.unwrap();
+    assert_eq!(field_u64(&events_with_tag(&logs, 1)[0], 1), 2);
+    assert_eq!(env.client_account(&referrer.pubkey()).await.id, 2);
+    assert_eq!(env.root_account().await.clients_count, 3);
+}
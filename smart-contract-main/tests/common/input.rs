@@ -0,0 +1,110 @@
+// Runs `process_instruction` natively on input serialized the way the runtime does it.
+// Shared by tests/malformed.rs and the `instruction` fuzz target.
+
+use solana_program::{
+    account_info::AccountInfo,
+    clock::Clock,
+    entrypoint::{
+        deserialize, ProgramResult, BPF_ALIGN_OF_U128, MAX_PERMITTED_DATA_INCREASE, NON_DUP_MARKER,
+    },
+    instruction::Instruction,
+    program_error::ProgramError,
+    program_stubs::{set_syscall_stubs, SyscallStubs},
+    pubkey::Pubkey,
+    rent::Rent,
+};
+use std::sync::Once;
+
+#[derive(Clone, Debug)]
+pub struct RawAccount {
+    pub key: Pubkey,
+    pub owner: Pubkey,
+    pub is_signer: bool,
+    pub is_writable: bool,
+    pub lamports: u64,
+    pub data: Vec<u8>,
+}
+
+// Fixed sysvars and quiet logs. CPIs always fail, so a processor never goes on
+// with accounts that a CPI was supposed to create or fund.
+struct Stubs;
+
+impl SyscallStubs for Stubs {
+    fn sol_log(&self, _message: &str) {}
+
+    fn sol_log_data(&self, _fields: &[&[u8]]) {}
+
+    fn sol_invoke_signed(
+        &self,
+        _instruction: &Instruction,
+        _account_infos: &[AccountInfo],
+        _signers_seeds: &[&[&[u8]]],
+    ) -> ProgramResult {
+        Err(ProgramError::Custom(u32::MAX))
+    }
+
+    fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+        let clock = Clock {
+            slot: 1_000,
+            unix_timestamp: 1_700_000_000,
+            ..Clock::default()
+        };
+        unsafe { std::ptr::write_unaligned(var_addr as *mut Clock, clock) };
+        0
+    }
+
+    fn sol_get_rent_sysvar(&self, var_addr: *mut u8) -> u64 {
+        unsafe { std::ptr::write_unaligned(var_addr as *mut Rent, Rent::default()) };
+        0
+    }
+}
+
+pub fn install_stubs() {
+    static STUBS: Once = Once::new();
+    STUBS.call_once(|| {
+        set_syscall_stubs(Box::new(Stubs));
+    });
+}
+
+/// Serialized program input; repeated keys become duplicate markers like on-chain.
+/// The buffer is u128-backed so the alignment `deserialize` relies on holds.
+pub fn serialize(program_id: &Pubkey, accounts: &[RawAccount], data: &[u8]) -> Vec<u128> {
+    let mut bytes = Vec::new();
+    bytes.extend_from_slice(&(accounts.len() as u64).to_le_bytes());
+    for (i, account) in accounts.iter().enumerate() {
+        if let Some(first) = accounts[..i].iter().position(|a| a.key == account.key) {
+            bytes.push(first as u8);
+            bytes.extend_from_slice(&[0; 7]);
+            continue;
+        }
+        bytes.push(NON_DUP_MARKER);
+        bytes.push(account.is_signer as u8);
+        bytes.push(account.is_writable as u8);
+        bytes.push(0);
+        bytes.extend_from_slice(&[0; 4]);
+        bytes.extend_from_slice(account.key.as_ref());
+        bytes.extend_from_slice(account.owner.as_ref());
+        bytes.extend_from_slice(&account.lamports.to_le_bytes());
+        bytes.extend_from_slice(&(account.data.len() as u64).to_le_bytes());
+        bytes.extend_from_slice(&account.data);
+        bytes.resize(bytes.len() + MAX_PERMITTED_DATA_INCREASE, 0);
+        let padding = (BPF_ALIGN_OF_U128 - bytes.len() % BPF_ALIGN_OF_U128) % BPF_ALIGN_OF_U128;
+        bytes.resize(bytes.len() + padding, 0);
+        bytes.extend_from_slice(&0u64.to_le_bytes());
+    }
+    bytes.extend_from_slice(&(data.len() as u64).to_le_bytes());
+    bytes.extend_from_slice(data);
+    bytes.extend_from_slice(program_id.as_ref());
+    let mut input = vec![0u128; bytes.len().div_ceil(16)];
+    unsafe {
+        std::ptr::copy_nonoverlapping(bytes.as_ptr(), input.as_mut_ptr() as *mut u8, bytes.len());
+    }
+    input
+}
+
+pub fn process(program_id: &Pubkey, accounts: &[RawAccount], data: &[u8]) -> ProgramResult {
+    install_stubs();
+    let mut input = serialize(program_id, accounts, data);
+    let (program_id, accounts, data) = unsafe { deserialize(input.as_mut_ptr() as *mut u8) };
+    hype_watch::process_instruction(program_id, &accounts, data)
+}
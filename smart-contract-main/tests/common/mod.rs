@@ -0,0 +1,1723 @@
+#![allow(dead_code)]
+
+pub mod input;
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use hype_watch::{program::error::HypeError, program::*, state::*};
+use solana_program_test::{BanksClientError, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    account::Account,
+    bpf_loader,
+    clock::Clock,
+    instruction::{AccountMeta, Instruction, InstructionError},
+    native_token::LAMPORTS_PER_SOL,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    signer::keypair::keypair_from_seed,
+    system_instruction, system_program,
+    transaction::{Transaction, TransactionError},
+};
+use spl_associated_token_account::get_associated_token_address_with_program_id;
+use std::convert::TryInto;
+
+pub const VERSION: u32 = 1;
+pub const HOLDER_SEED: &[u8] = b"holder";
+pub const BASE_DECIMALS: u8 = 6;
+
+pub fn program_id() -> Pubkey {
+    Pubkey::new_from_array([0x48; 32])
+}
+
+pub fn hype_auth() -> Pubkey {
+    Pubkey::find_program_address(&[HYPE_SEED], &program_id()).0
+}
+
+pub fn root_address(version: u32) -> Pubkey {
+    let seed = unsafe { get_seed_by_tag(version, ROOT_TAG) };
+    Pubkey::find_program_address(&[&seed, hype_auth().as_ref()], &program_id()).0
+}
+
+pub fn client_address(version: u32, wallet: &Pubkey) -> Pubkey {
+    let seed = unsafe { get_seed_by_tag(version, CLIENT_TAG) };
+    Pubkey::find_program_address(&[&seed, wallet.as_ref()], &program_id()).0
+}
+
+pub fn referrer_address(version: u32, wallet: &Pubkey) -> Pubkey {
+    let seed = unsafe { get_seed_by_tag(version, REFERRER_TAG) };
+    Pubkey::find_program_address(&[&seed, wallet.as_ref()], &program_id()).0
+}
+
+pub fn ref_code_address(version: u32, code: &str) -> Pubkey {
+    let seed = unsafe { get_seed_by_tag(version, REF_CODE_TAG) };
+    let code = fixed::<NICKNAME_STRING_LENGTH>(code);
+    Pubkey::find_program_address(&[&seed, &code], &program_id()).0
+}
+
+/// Registry account of `nickname`, which is case-folded first.
+pub fn nickname_address(version: u32, nickname: &str) -> Pubkey {
+    let seed = unsafe { get_seed_by_tag(version, NICKNAME_TAG) };
+    let key = normalize_nickname(&fixed::<NICKNAME_STRING_LENGTH>(nickname)).unwrap();
+    Pubkey::find_program_address(&[&seed, &key], &program_id()).0
+}
+
+/// Registry account of a raw client nickname; the system program if it has none.
+pub fn client_nickname_address(version: u32, nickname: &[u8]) -> Pubkey {
+    match normalize_nickname(nickname) {
+        Ok(key) => {
+            let seed = unsafe { get_seed_by_tag(version, NICKNAME_TAG) };
+            Pubkey::find_program_address(&[&seed, &key], &program_id()).0
+        }
+        Err(_) => system_program::ID,
+    }
+}
+
+/// Referral code account of a raw name; the system program if it is not a valid name.
+pub fn client_ref_code_address(version: u32, name: &[u8]) -> Pubkey {
+    match normalize_ref_code(name) {
+        Ok(code) => {
+            let seed = unsafe { get_seed_by_tag(version, REF_CODE_TAG) };
+            Pubkey::find_program_address(&[&seed, &code], &program_id()).0
+        }
+        Err(_) => system_program::ID,
+    }
+}
+
+pub fn position_address(version: u32, wallet: &Pubkey, hype_mint: &Pubkey) -> Pubkey {
+    let seed = unsafe { get_seed_by_tag(version, POSITION_TAG) };
+    Pubkey::find_program_address(&[&seed, wallet.as_ref(), hype_mint.as_ref()], &program_id()).0
+}
+
+pub fn price_history_address(version: u32, hype_mint: &Pubkey) -> Pubkey {
+    let seed = unsafe { get_seed_by_tag(version, PRICE_HISTORY_TAG) };
+    Pubkey::find_program_address(&[&seed, hype_mint.as_ref()], &program_id()).0
+}
+
+pub fn delegate_address(version: u32, wallet: &Pubkey, delegate: &Pubkey) -> Pubkey {
+    let seed = unsafe { get_seed_by_tag(version, DELEGATE_TAG) };
+    Pubkey::find_program_address(&[&seed, wallet.as_ref(), delegate.as_ref()], &program_id()).0
+}
+
+pub fn launch_allowance_address(version: u32, wallet: &Pubkey) -> Pubkey {
+    let seed = unsafe { get_seed_by_tag(version, LAUNCH_ALLOWANCE_TAG) };
+    Pubkey::find_program_address(&[&seed, wallet.as_ref()], &program_id()).0
+}
+
+pub fn launch_buy_address(version: u32, wallet: &Pubkey, hype_mint: &Pubkey) -> Pubkey {
+    let seed = unsafe { get_seed_by_tag(version, LAUNCH_BUY_TAG) };
+    Pubkey::find_program_address(&[&seed, wallet.as_ref(), hype_mint.as_ref()], &program_id()).0
+}
+
+pub fn pool_address(version: u32, hype_mint: &Pubkey) -> Pubkey {
+    let seed = unsafe { get_seed_by_tag(version, POOL_TAG) };
+    Pubkey::find_program_address(&[&seed, hype_mint.as_ref()], &program_id()).0
+}
+
+/// `ix`, a `mint`, `burn`, `mint_for`, `switch` or `cpi_trade`, declaring the `TRADE_EXTRA_*` accounts of `flags`,
+/// passed as `accounts` in the order of their bits.
+pub fn with_trade_extras(mut ix: Instruction, flags: u32, accounts: &[Pubkey]) -> Instruction {
+    let offset = match ix.data[0] {
+        4 => 92,
+        5 | 23 => 68,
+        22 => 36,
+        26 => 12,
+        tag => panic!("instruction {} has no trade extras", tag),
+    };
+    if ix.data.len() < offset + 4 {
+        ix.data.resize(offset + 4, 0);
+    }
+    ix.data[offset..offset + 4].copy_from_slice(&flags.to_le_bytes());
+    for address in accounts {
+        ix.accounts.push(AccountMeta::new(*address, false));
+    }
+    ix
+}
+
+/// `ix`, a trade of `wallet`, signed by `delegate` in its place.
+pub fn delegated(mut ix: Instruction, wallet: &Pubkey, delegate: &Pubkey) -> Instruction {
+    ix.accounts[0].is_signer = false;
+    ix.accounts.insert(17, AccountMeta::new(*delegate, true));
+    ix.accounts.insert(
+        18,
+        AccountMeta::new(delegate_address(VERSION, wallet, delegate), false),
+    );
+    ix
+}
+
+pub fn token_address(version: u32, network: u32, address: &[u8; ADDRESS_STRING_LENGTH]) -> Pubkey {
+    let seed = unsafe { get_token_seed_bytes(version, network, address) };
+    Pubkey::find_program_address(&[&seed, hype_auth().as_ref()], &program_id()).0
+}
+
+pub fn fixed<const N: usize>(s: &str) -> [u8; N] {
+    let mut res = [0; N];
+    res[..s.len()].copy_from_slice(s.as_bytes());
+    res
+}
+
+pub unsafe fn read_struct<T>(data: &[u8]) -> T {
+    assert!(data.len() >= std::mem::size_of::<T>());
+    std::ptr::read_unaligned(data.as_ptr() as *const T)
+}
+
+#[derive(Clone, Copy)]
+pub struct RootParams {
+    pub fee_ratio: f64,
+    pub init_price: f64,
+    pub max_supply: u64,
+    pub fee_rate: f64,
+    pub creation_fee: f64,
+    pub min_fee: f64,
+    pub mask: u32,
+    pub ref_duration: u32,
+    pub ref_discount: f64,
+    pub ref_ratio: f64,
+    pub ref_depth: u32,
+    pub ref_upline_ratios: [f64; MAX_REF_DEPTH - 1],
+    pub ref_grace_period: u32,
+    pub creator_fee_ratio: f64,
+    pub token_idle_period: u32,
+    pub candle_interval: u32,
+    pub switch_fee_rate: f64,
+    pub launch_window: u32,
+    pub restricted_window: u32,
+    pub launch_max_buy: u64,
+    pub buy_cooldown: u32,
+    pub graduation_reserve: u64,
+    pub pool_fee_rate: f64,
+}
+
+impl Default for RootParams {
+    fn default() -> Self {
+        RootParams {
+            fee_ratio: 0.5,
+            init_price: 1.0,
+            max_supply: 1_000_000_000_000_000,
+            // trade_fees scales the rate by base_crncy_decs_factor: 0.1% of a trade at 6 decimals
+            fee_rate: 0.000_000_001,
+            creation_fee: 1.0,
+            min_fee: 0.0,
+            mask: 0,
+            ref_duration: 30 * 24 * 3600,
+            ref_discount: 0.1,
+            ref_ratio: 0.5,
+            ref_depth: 3,
+            ref_upline_ratios: [0.2, 0.1],
+            ref_grace_period: DEFAULT_REF_GRACE_PERIOD,
+            creator_fee_ratio: 0.0,
+            token_idle_period: 0,
+            candle_interval: DEFAULT_CANDLE_INTERVAL,
+            switch_fee_rate: 0.002,
+            launch_window: 0,
+            restricted_window: 0,
+            launch_max_buy: 0,
+            buy_cooldown: 0,
+            graduation_reserve: 0,
+            pool_fee_rate: 0.001,
+        }
+    }
+}
+
+impl RootParams {
+    pub fn curve(&self) -> CurveParams {
+        CurveParams {
+            init_price: self.init_price,
+            max_supply: self.max_supply,
+            fee_rate: self.fee_rate,
+            min_fee: self.min_fee,
+            fee_ratio: self.fee_ratio,
+            base_crncy_decs_factor: 10u32.pow(BASE_DECIMALS as u32),
+        }
+    }
+}
+
+/// Expected amounts of a `mint`/`burn`; `fees` is what the trader actually pays.
+#[derive(Debug, Default, PartialEq)]
+pub struct Quote {
+    pub base_crncy_amount: u64,
+    pub fees: u64,
+    pub holder_fees: u64,
+    pub operator_fees: u64,
+    pub ref_fees: u64,
+    pub upline_fees: [u64; MAX_REF_DEPTH - 1],
+    pub creator_fees: u64,
+}
+
+/// `uplines` is `None` without an active referral, else the number of upline levels paid.
+fn quote(params: &RootParams, base_crncy_amount: u64, fees: u64, uplines: Option<usize>) -> Quote {
+    let split = match uplines {
+        None => split_fees(fees, params.fee_ratio, None),
+        Some(levels) => split_fees(
+            fees,
+            params.fee_ratio,
+            Some((params.ref_discount, params.ref_ratio)),
+        )
+        .with_upline_fees(&params.ref_upline_ratios[..levels]),
+    };
+    quote_with_split(base_crncy_amount, &split)
+}
+
+fn quote_with_split(base_crncy_amount: u64, split: &FeeSplit) -> Quote {
+    Quote {
+        base_crncy_amount: base_crncy_amount,
+        fees: split.total(),
+        holder_fees: split.holder_fees,
+        operator_fees: split.operator_fees,
+        ref_fees: split.ref_fees,
+        upline_fees: split.upline_fees,
+        creator_fees: split.creator_fees,
+    }
+}
+
+fn mint_quote(
+    params: &RootParams,
+    supply: u64,
+    amount: u64,
+    new_token: bool,
+    uplines: Option<usize>,
+) -> Quote {
+    let curve = params.curve();
+    let mint = quote_mint(&curve, supply, amount).unwrap();
+    let creation_fee = if new_token {
+        (params.creation_fee * curve.base_crncy_decs_factor as f64) as u64
+    } else {
+        0
+    };
+    quote(
+        params,
+        mint.base_crncy_amount,
+        mint.fees + creation_fee,
+        uplines,
+    )
+}
+
+/// Mint by a client without an active referral.
+pub fn expected_mint(params: &RootParams, supply: u64, amount: u64, new_token: bool) -> Quote {
+    mint_quote(params, supply, amount, new_token, None)
+}
+
+/// Mint by a client whose referral is still active and reaches `uplines` upline levels.
+pub fn expected_referred_mint(
+    params: &RootParams,
+    supply: u64,
+    amount: u64,
+    new_token: bool,
+    uplines: usize,
+) -> Quote {
+    mint_quote(params, supply, amount, new_token, Some(uplines))
+}
+
+/// Burn by a client without an active referral.
+pub fn expected_burn(params: &RootParams, supply: u64, amount: u64) -> Quote {
+    let burn = quote_burn(&params.curve(), supply, amount).unwrap();
+    quote(params, burn.base_crncy_amount, burn.fees, None)
+}
+
+/// Burn by a client whose referral is still active and reaches `uplines` upline levels.
+pub fn expected_referred_burn(
+    params: &RootParams,
+    supply: u64,
+    amount: u64,
+    uplines: usize,
+) -> Quote {
+    let burn = quote_burn(&params.curve(), supply, amount).unwrap();
+    quote(params, burn.base_crncy_amount, burn.fees, Some(uplines))
+}
+
+/// Mint of an existing token by a client without an active referral, paying
+/// `creator_fee_ratio` of the trading fees to the token creator.
+pub fn expected_creator_mint(
+    params: &RootParams,
+    supply: u64,
+    amount: u64,
+    creator_fee_ratio: f64,
+) -> Quote {
+    let mint = quote_mint(&params.curve(), supply, amount).unwrap();
+    let split = split_fees(mint.fees, params.fee_ratio, None)
+        .with_creator_fees(mint.fees, creator_fee_ratio);
+    quote_with_split(mint.base_crncy_amount, &split)
+}
+
+/// Burn by a client without an active referral, paying `creator_fee_ratio`
+/// of the trading fees to the token creator.
+pub fn expected_creator_burn(
+    params: &RootParams,
+    supply: u64,
+    amount: u64,
+    creator_fee_ratio: f64,
+) -> Quote {
+    let burn = quote_burn(&params.curve(), supply, amount).unwrap();
+    let split = split_fees(burn.fees, params.fee_ratio, None)
+        .with_creator_fees(burn.fees, creator_fee_ratio);
+    quote_with_split(burn.base_crncy_amount, &split)
+}
+
+/// Splits `Program data:` log lines into the fields passed to `sol_log_data`.
+pub fn events(logs: &[String]) -> Vec<Vec<Vec<u8>>> {
+    logs.iter()
+        .filter_map(|line| line.strip_prefix("Program data: "))
+        .map(|data| {
+            data.split_whitespace()
+                .map(|field| STANDARD.decode(field).unwrap())
+                .collect()
+        })
+        .collect()
+}
+
+pub fn events_with_tag(logs: &[String], tag: u8) -> Vec<Vec<Vec<u8>>> {
+    events(logs)
+        .into_iter()
+        .filter(|event| event[0] == [tag])
+        .collect()
+}
+
+pub fn field_u64(event: &[Vec<u8>], index: usize) -> u64 {
+    u64::from_le_bytes(event[index][..8].try_into().unwrap())
+}
+
+pub fn field_u32(event: &[Vec<u8>], index: usize) -> u32 {
+    u32::from_le_bytes(event[index][..4].try_into().unwrap())
+}
+
+/// Appends the optional deadline of `mint_ix` and `burn_ix`, 0 for no limit.
+pub fn with_valid_until(mut ix: Instruction, slot: u64, time: u32) -> Instruction {
+    ix.data.extend_from_slice(&slot.to_le_bytes());
+    ix.data.extend_from_slice(&time.to_le_bytes());
+    ix
+}
+
+/// One leg of `batch_ix`.
+#[derive(Clone, Copy)]
+pub struct Leg {
+    pub token: Pubkey,
+    pub hype_mint: Pubkey,
+    pub hype_program_acc: Pubkey,
+    pub side: u32,
+    pub amount: u64,
+    pub limit: u64,
+}
+
+pub fn assert_hype_error(result: Result<Vec<String>, BanksClientError>, error: HypeError) {
+    match result {
+        Err(BanksClientError::TransactionError(TransactionError::InstructionError(
+            _,
+            InstructionError::Custom(code),
+        )))
+        | Err(BanksClientError::SimulationError {
+            err: TransactionError::InstructionError(_, InstructionError::Custom(code)),
+            ..
+        }) => assert_eq!(code, error as u32, "expected {:?}", error),
+        other => panic!("expected {:?}, got {:?}", error, other),
+    }
+}
+
+/// Loads the built program and swaps the production `HOLDER_ADMIN` for `admin`, so the
+/// suite can sign admin instructions without a test-only constant in the program.
+fn program_elf(admin: &Pubkey) -> Vec<u8> {
+    let dir = std::env::var("SBF_OUT_DIR")
+        .or_else(|_| std::env::var("BPF_OUT_DIR"))
+        .unwrap_or_else(|_| "target/deploy".to_string());
+    let path = std::path::Path::new(&dir).join("hype_watch.so");
+    let mut elf =
+        std::fs::read(&path).unwrap_or_else(|e| panic!("cannot read {}: {}", path.display(), e));
+    let production = HOLDER_ADMIN.as_bytes();
+    let replacement = admin.to_string();
+    assert_eq!(replacement.len(), production.len());
+    let at = elf
+        .windows(production.len())
+        .position(|w| w == production)
+        .expect("HOLDER_ADMIN not found in program binary");
+    elf[at..at + production.len()].copy_from_slice(replacement.as_bytes());
+    elf
+}
+
+pub struct TestEnv {
+    pub context: ProgramTestContext,
+    pub params: RootParams,
+    pub holder_admin: Keypair,
+    pub holder_wallet: Keypair,
+    pub operator: Keypair,
+    pub validator: Keypair,
+    pub fee_wallet: Keypair,
+    pub vault: Keypair,
+    pub base_token_program: Pubkey,
+    pub base_mint: Pubkey,
+    pub holder: Pubkey,
+    pub root: Pubkey,
+}
+
+impl TestEnv {
+    /// Starts a bank with the program and a base currency mint owned by `base_token_program`.
+    pub async fn new(base_token_program: Pubkey) -> Self {
+        let holder_admin = keypair_from_seed(&[7; 32]).unwrap();
+        let mut program_test = ProgramTest::default();
+        let elf = program_elf(&holder_admin.pubkey());
+        program_test.add_account(
+            program_id(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                data: elf,
+                owner: bpf_loader::id(),
+                executable: true,
+                rent_epoch: 0,
+            },
+        );
+        program_test.set_compute_max_units(1_400_000);
+        let operator = Keypair::new();
+        for wallet in [holder_admin.pubkey(), operator.pubkey()] {
+            program_test.add_account(
+                wallet,
+                Account::new(100 * LAMPORTS_PER_SOL, 0, &system_program::ID),
+            );
+        }
+        let context = program_test.start_with_context().await;
+        let holder = Pubkey::find_program_address(
+            &[HOLDER_SEED, holder_admin.pubkey().as_ref()],
+            &program_id(),
+        )
+        .0;
+        let mut env = TestEnv {
+            context,
+            params: RootParams::default(),
+            holder_admin,
+            holder_wallet: Keypair::new(),
+            operator,
+            validator: Keypair::new(),
+            fee_wallet: Keypair::new(),
+            vault: Keypair::new(),
+            base_token_program,
+            base_mint: Pubkey::default(),
+            holder,
+            root: root_address(VERSION),
+        };
+        env.base_mint = env.create_base_mint().await;
+        env
+    }
+
+    /// Runs `initialize_holder`, `add_operator`, `initialize_root` and one `add_network`.
+    pub async fn setup(base_token_program: Pubkey) -> Self {
+        let mut env = Self::new(base_token_program).await;
+        env.initialize_holder().await.unwrap();
+        env.add_operator(10).await.unwrap();
+        env.initialize_root().await.unwrap();
+        env.add_network("ethereum", "abcdefghijklmnopqrstuvwxyz0123456789", 24)
+            .await
+            .unwrap();
+        env
+    }
+
+    pub async fn process(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> Result<Vec<String>, BanksClientError> {
+        let blockhash = self.context.get_new_latest_blockhash().await?;
+        let mut all_signers: Vec<&Keypair> = vec![&self.context.payer];
+        all_signers.extend_from_slice(signers);
+        let tx = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&self.context.payer.pubkey()),
+            &all_signers,
+            blockhash,
+        );
+        let res = self
+            .context
+            .banks_client
+            .process_transaction_with_metadata(tx)
+            .await?;
+        res.result?;
+        Ok(res.metadata.map(|m| m.log_messages).unwrap_or_default())
+    }
+
+    /// Runs the instructions in simulation only, like a monitoring job would.
+    pub async fn simulate(
+        &mut self,
+        instructions: &[Instruction],
+    ) -> Result<Vec<String>, BanksClientError> {
+        let blockhash = self.context.get_new_latest_blockhash().await?;
+        let tx = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&self.context.payer.pubkey()),
+            &[&self.context.payer],
+            blockhash,
+        );
+        let res = self.context.banks_client.simulate_transaction(tx).await?;
+        let details = res.simulation_details.unwrap();
+        match res.result.unwrap() {
+            Ok(()) => Ok(details.logs),
+            Err(err) => Err(BanksClientError::SimulationError {
+                err,
+                logs: details.logs,
+                units_consumed: details.units_consumed,
+                return_data: details.return_data,
+            }),
+        }
+    }
+
+    /// Return data of `get_twap`: average price, seconds covered and current spot price.
+    pub async fn twap(
+        &mut self,
+        token: &Pubkey,
+        window: u32,
+    ) -> Result<(f64, u32, f64), BanksClientError> {
+        let mut data = vec![0u8; 12];
+        data[0] = 20;
+        data[8..12].copy_from_slice(&window.to_le_bytes());
+        let ix = Instruction::new_with_bytes(
+            program_id(),
+            &data,
+            vec![AccountMeta::new_readonly(*token, false)],
+        );
+        let data = self.return_data(ix, &[]).await?;
+        Ok((
+            f64::from_le_bytes(data[0..8].try_into().unwrap()),
+            u32::from_le_bytes(data[8..12].try_into().unwrap()),
+            f64::from_le_bytes(data[12..20].try_into().unwrap()),
+        ))
+    }
+
+    /// Return data of `ix`, simulated without changing any account.
+    pub async fn return_data(
+        &mut self,
+        ix: Instruction,
+        signers: &[&Keypair],
+    ) -> Result<Vec<u8>, BanksClientError> {
+        let blockhash = self.context.get_new_latest_blockhash().await?;
+        let mut all_signers = vec![&self.context.payer];
+        all_signers.extend_from_slice(signers);
+        let tx = Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&self.context.payer.pubkey()),
+            &all_signers,
+            blockhash,
+        );
+        let res = self.context.banks_client.simulate_transaction(tx).await?;
+        let details = res.simulation_details.unwrap();
+        if let Err(err) = res.result.unwrap() {
+            return Err(BanksClientError::SimulationError {
+                err,
+                logs: details.logs,
+                units_consumed: details.units_consumed,
+                return_data: details.return_data,
+            });
+        }
+        Ok(details.return_data.unwrap().data)
+    }
+
+    pub async fn clock(&mut self) -> Clock {
+        self.context.banks_client.get_sysvar().await.unwrap()
+    }
+
+    /// Moves the bank's unix timestamp forward by `seconds`.
+    pub async fn advance_clock(&mut self, seconds: i64) {
+        let mut clock: Clock = self.context.banks_client.get_sysvar().await.unwrap();
+        clock.unix_timestamp += seconds;
+        self.context.set_sysvar(&clock);
+    }
+
+    pub async fn account(&mut self, address: &Pubkey) -> Option<Account> {
+        self.context
+            .banks_client
+            .get_account(*address)
+            .await
+            .unwrap()
+    }
+
+    pub async fn root_account(&mut self) -> RootAccount {
+        let root = self.root;
+        let data = self.account(&root).await.unwrap().data;
+        unsafe { read_struct(&data) }
+    }
+
+    pub async fn token_account(&mut self, address: &Pubkey) -> TokenAccount {
+        let data = self.account(address).await.unwrap().data;
+        unsafe { read_struct(&data) }
+    }
+
+    pub async fn client_account(&mut self, wallet: &Pubkey) -> ClientAccount {
+        let data = self
+            .account(&client_address(VERSION, wallet))
+            .await
+            .unwrap()
+            .data;
+        unsafe { read_struct(&data) }
+    }
+
+    pub async fn launch_buy_account(
+        &mut self,
+        wallet: &Pubkey,
+        hype_mint: &Pubkey,
+    ) -> LaunchBuyAccount {
+        let data = self
+            .account(&launch_buy_address(VERSION, wallet, hype_mint))
+            .await
+            .unwrap()
+            .data;
+        unsafe { read_struct(&data) }
+    }
+
+    /// Amount of an spl-token or Token-2022 account, 0 if it does not exist.
+    pub async fn referrer_account(&mut self, wallet: &Pubkey) -> ReferrerAccount {
+        let data = self
+            .account(&referrer_address(VERSION, wallet))
+            .await
+            .unwrap()
+            .data;
+        unsafe { read_struct(&data) }
+    }
+
+    pub async fn position_account(
+        &mut self,
+        wallet: &Pubkey,
+        hype_mint: &Pubkey,
+    ) -> PositionAccount {
+        let data = self
+            .account(&position_address(VERSION, wallet, hype_mint))
+            .await
+            .unwrap()
+            .data;
+        unsafe { read_struct(&data) }
+    }
+
+    pub async fn pool_account(&mut self, hype_mint: &Pubkey) -> PoolAccount {
+        let data = self
+            .account(&pool_address(VERSION, hype_mint))
+            .await
+            .unwrap()
+            .data;
+        unsafe { read_struct(&data) }
+    }
+
+    pub async fn price_history_account(&mut self, hype_mint: &Pubkey) -> PriceHistoryAccount {
+        let data = self
+            .account(&price_history_address(VERSION, hype_mint))
+            .await
+            .unwrap()
+            .data;
+        unsafe { read_struct(&data) }
+    }
+
+    pub async fn balance(&mut self, address: &Pubkey) -> u64 {
+        match self.account(address).await {
+            Some(account) if account.data.len() >= 72 => {
+                u64::from_le_bytes(account.data[64..72].try_into().unwrap())
+            }
+            _ => 0,
+        }
+    }
+
+    pub async fn mint_supply(&mut self, mint: &Pubkey) -> u64 {
+        let data = self.account(mint).await.unwrap().data;
+        u64::from_le_bytes(data[36..44].try_into().unwrap())
+    }
+
+    pub fn base_ata(&self, wallet: &Pubkey) -> Pubkey {
+        get_associated_token_address_with_program_id(
+            wallet,
+            &self.base_mint,
+            &self.base_token_program,
+        )
+    }
+
+    pub fn hype_ata(&self, wallet: &Pubkey, hype_mint: &Pubkey) -> Pubkey {
+        get_associated_token_address_with_program_id(wallet, hype_mint, &spl_token_2022::id())
+    }
+
+    async fn create_base_mint(&mut self) -> Pubkey {
+        let mint = Keypair::new();
+        let rent = self.context.banks_client.get_rent().await.unwrap();
+        let payer = self.context.payer.pubkey();
+        let init = if self.base_token_program == spl_token_2022::id() {
+            spl_token_2022::instruction::initialize_mint2(
+                &spl_token_2022::id(),
+                &mint.pubkey(),
+                &payer,
+                None,
+                BASE_DECIMALS,
+            )
+            .unwrap()
+        } else {
+            spl_token::instruction::initialize_mint2(
+                &spl_token::id(),
+                &mint.pubkey(),
+                &payer,
+                None,
+                BASE_DECIMALS,
+            )
+            .unwrap()
+        };
+        self.process(
+            &[
+                system_instruction::create_account(
+                    &payer,
+                    &mint.pubkey(),
+                    rent.minimum_balance(spl_token::state::Mint::LEN),
+                    spl_token::state::Mint::LEN as u64,
+                    &self.base_token_program,
+                ),
+                init,
+            ],
+            &[&mint],
+        )
+        .await
+        .unwrap();
+        mint.pubkey()
+    }
+
+    /// Creates a funded trader wallet with a base currency ATA holding `amount`.
+    pub async fn new_trader(&mut self, amount: u64) -> Keypair {
+        let trader = Keypair::new();
+        let payer = self.context.payer.pubkey();
+        let ata = self.base_ata(&trader.pubkey());
+        let mint_to = if self.base_token_program == spl_token_2022::id() {
+            spl_token_2022::instruction::mint_to(
+                &spl_token_2022::id(),
+                &self.base_mint,
+                &ata,
+                &payer,
+                &[],
+                amount,
+            )
+            .unwrap()
+        } else {
+            spl_token::instruction::mint_to(
+                &spl_token::id(),
+                &self.base_mint,
+                &ata,
+                &payer,
+                &[],
+                amount,
+            )
+            .unwrap()
+        };
+        self.process(
+            &[
+                system_instruction::transfer(&payer, &trader.pubkey(), 10 * LAMPORTS_PER_SOL),
+                spl_associated_token_account::instruction::create_associated_token_account(
+                    &payer,
+                    &trader.pubkey(),
+                    &self.base_mint,
+                    &self.base_token_program,
+                ),
+                mint_to,
+            ],
+            &[],
+        )
+        .await
+        .unwrap();
+        trader
+    }
+
+    /// Sends `lamports` to `address`, as anybody can before a program account is opened there.
+    pub async fn fund(&mut self, address: &Pubkey, lamports: u64) {
+        let payer = self.context.payer.pubkey();
+        self.process(
+            &[system_instruction::transfer(&payer, address, lamports)],
+            &[],
+        )
+        .await
+        .unwrap();
+    }
+
+    pub async fn initialize_holder(&mut self) -> Result<Vec<String>, BanksClientError> {
+        let (holder, bump_seed) = Pubkey::find_program_address(
+            &[HOLDER_SEED, self.holder_admin.pubkey().as_ref()],
+            &program_id(),
+        );
+        let mut data = vec![0u8; 10];
+        data[1..1 + HOLDER_SEED.len()].copy_from_slice(HOLDER_SEED);
+        data[9] = bump_seed;
+        let ix = Instruction::new_with_bytes(
+            program_id(),
+            &data,
+            vec![
+                AccountMeta::new(self.holder_admin.pubkey(), true),
+                AccountMeta::new(holder, false),
+                AccountMeta::new_readonly(self.holder_wallet.pubkey(), false),
+                AccountMeta::new_readonly(system_program::ID, false),
+            ],
+        );
+        let admin = self.holder_admin.insecure_clone();
+        self.process(&[ix], &[&admin]).await
+    }
+
+    pub async fn add_operator(
+        &mut self,
+        max_networks_count: u32,
+    ) -> Result<Vec<String>, BanksClientError> {
+        let mut data = vec![1u8];
+        data.extend_from_slice(&VERSION.to_le_bytes());
+        data.extend_from_slice(&max_networks_count.to_le_bytes());
+        data.extend_from_slice(&fixed::<OPERATOR_NAME_STRING_LENGTH>("Test Operator"));
+        let ix = Instruction::new_with_bytes(
+            program_id(),
+            &data,
+            vec![
+                AccountMeta::new(self.holder_admin.pubkey(), true),
+                AccountMeta::new(self.holder, false),
+                AccountMeta::new_readonly(self.operator.pubkey(), false),
+                AccountMeta::new_readonly(system_program::ID, false),
+            ],
+        );
+        let admin = self.holder_admin.insecure_clone();
+        self.process(&[ix], &[&admin]).await
+    }
+
+    pub async fn initialize_root(&mut self) -> Result<Vec<String>, BanksClientError> {
+        let p = self.params;
+        let mut data = vec![0u8; 8];
+        data[0..4].copy_from_slice(&2u32.to_le_bytes());
+        data[4..8].copy_from_slice(&VERSION.to_le_bytes());
+        data.extend_from_slice(&p.fee_ratio.to_le_bytes());
+        data.extend_from_slice(&p.init_price.to_le_bytes());
+        data.extend_from_slice(&p.max_supply.to_le_bytes());
+        data.extend_from_slice(&p.fee_rate.to_le_bytes());
+        data.extend_from_slice(&p.creation_fee.to_le_bytes());
+        data.extend_from_slice(&p.min_fee.to_le_bytes());
+        data.extend_from_slice(&fixed::<URL_PREFIX_STRING_LENGTH>("https://hype.watch/"));
+        data.extend_from_slice(&p.mask.to_le_bytes());
+        data.extend_from_slice(&p.ref_duration.to_le_bytes());
+        data.extend_from_slice(&p.ref_discount.to_le_bytes());
+        data.extend_from_slice(&p.ref_ratio.to_le_bytes());
+        data.extend_from_slice(&p.ref_depth.to_le_bytes());
+        for ratio in p.ref_upline_ratios.iter() {
+            data.extend_from_slice(&ratio.to_le_bytes());
+        }
+        data.extend_from_slice(&p.ref_grace_period.to_le_bytes());
+        data.extend_from_slice(&p.creator_fee_ratio.to_le_bytes());
+        data.extend_from_slice(&p.token_idle_period.to_le_bytes());
+        data.extend_from_slice(&p.candle_interval.to_le_bytes());
+        data.extend_from_slice(&p.switch_fee_rate.to_le_bytes());
+        data.extend_from_slice(&p.launch_window.to_le_bytes());
+        data.extend_from_slice(&p.restricted_window.to_le_bytes());
+        data.extend_from_slice(&p.launch_max_buy.to_le_bytes());
+        data.extend_from_slice(&p.buy_cooldown.to_le_bytes());
+        data.extend_from_slice(&p.graduation_reserve.to_le_bytes());
+        data.extend_from_slice(&p.pool_fee_rate.to_le_bytes());
+        let ix = Instruction::new_with_bytes(
+            program_id(),
+            &data,
+            vec![
+                AccountMeta::new(self.operator.pubkey(), true),
+                AccountMeta::new_readonly(self.holder, false),
+                AccountMeta::new(self.root, false),
+                AccountMeta::new_readonly(self.base_mint, false),
+                AccountMeta::new(self.vault.pubkey(), true),
+                AccountMeta::new_readonly(self.fee_wallet.pubkey(), false),
+                AccountMeta::new_readonly(hype_auth(), false),
+                AccountMeta::new_readonly(self.base_token_program, false),
+                AccountMeta::new_readonly(system_program::ID, false),
+            ],
+        );
+        let operator = self.operator.insecure_clone();
+        let vault = self.vault.insecure_clone();
+        self.process(&[ix], &[&operator, &vault]).await
+    }
+
+    pub async fn add_network(
+        &mut self,
+        descriptor: &str,
+        mask: &str,
+        max_length: usize,
+    ) -> Result<Vec<String>, BanksClientError> {
+        self.add_capped_network(descriptor, mask, max_length, None)
+            .await
+    }
+
+    /// `add_network` with an optional cap on the creator fee ratio of its tokens.
+    pub async fn add_capped_network(
+        &mut self,
+        descriptor: &str,
+        mask: &str,
+        max_length: usize,
+        creator_fee_cap: Option<f64>,
+    ) -> Result<Vec<String>, BanksClientError> {
+        let mut data = vec![0u8; 8];
+        data[0] = 3;
+        data.extend_from_slice(&fixed::<NETWORK_STRING_LENGTH>(descriptor));
+        data.extend_from_slice(&fixed::<MASK_STRING_LENGTH>(mask));
+        data.extend_from_slice(&(max_length as u64).to_le_bytes());
+        if let Some(cap) = creator_fee_cap {
+            data.extend_from_slice(&cap.to_le_bytes());
+        }
+        let ix = Instruction::new_with_bytes(
+            program_id(),
+            &data,
+            vec![
+                AccountMeta::new(self.operator.pubkey(), true),
+                AccountMeta::new(self.root, false),
+                AccountMeta::new_readonly(self.validator.pubkey(), false),
+                AccountMeta::new_readonly(system_program::ID, false),
+            ],
+        );
+        let operator = self.operator.insecure_clone();
+        self.process(&[ix], &[&operator]).await
+    }
+
+    /// The accounts read by `Context::new`; `referrers` is the client's referral chain,
+    /// starting with its own referrer, and must be empty once the referral expired.
+    pub fn trade_accounts(
+        &self,
+        wallet: &Pubkey,
+        token: &Pubkey,
+        hype_mint: &Pubkey,
+        hype_program_acc: &Pubkey,
+        referrers: &[Pubkey],
+    ) -> Vec<AccountMeta> {
+        let (ref_acc, referrer_acc) = match referrers.first() {
+            Some(referrer) => (*referrer, referrer_address(VERSION, referrer)),
+            None => (system_program::ID, system_program::ID),
+        };
+        let mut accounts = vec![
+            AccountMeta::new(*wallet, true),
+            AccountMeta::new(client_address(VERSION, wallet), false),
+            AccountMeta::new(self.base_ata(wallet), false),
+            AccountMeta::new(self.hype_ata(wallet, hype_mint), false),
+            AccountMeta::new(self.root, false),
+            AccountMeta::new(*token, false),
+            AccountMeta::new_readonly(self.base_mint, false),
+            AccountMeta::new(self.vault.pubkey(), false),
+            AccountMeta::new(*hype_mint, true),
+            AccountMeta::new(*hype_program_acc, true),
+            AccountMeta::new_readonly(hype_auth(), false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(spl_token_2022::id(), false),
+            AccountMeta::new_readonly(system_program::ID, false),
+            AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+            AccountMeta::new(ref_acc, false),
+            AccountMeta::new(referrer_acc, false),
+        ];
+        for upline in referrers.iter().skip(1) {
+            accounts.push(AccountMeta::new(referrer_address(VERSION, upline), false));
+        }
+        accounts
+    }
+
+    /// Turns a trade of `trade_accounts` into one creating the referred client, which
+    /// also passes the client account of its `referrer` after the 17 accounts.
+    pub fn signing_up(mut ix: Instruction, referrer: &Pubkey) -> Instruction {
+        ix.accounts.insert(
+            17,
+            AccountMeta::new_readonly(client_address(VERSION, referrer), false),
+        );
+        ix
+    }
+
+    pub fn mint_ix(
+        &self,
+        trader: &Pubkey,
+        network: u32,
+        address: &str,
+        hype_mint: &Pubkey,
+        hype_program_acc: &Pubkey,
+        amount: u64,
+        max_cost: u64,
+        referrers: &[Pubkey],
+    ) -> Instruction {
+        let address = fixed::<ADDRESS_STRING_LENGTH>(address);
+        let token = token_address(VERSION, network, &address);
+        let mut accounts =
+            self.trade_accounts(trader, &token, hype_mint, hype_program_acc, referrers);
+        // Only a token-creating mint has to sign for the new mint and holding account
+        accounts[8].is_signer = false;
+        accounts[9].is_signer = false;
+        let mut data = vec![0u8; 4];
+        data[0] = 4;
+        data.extend_from_slice(&network.to_le_bytes());
+        data.extend_from_slice(&address);
+        data.extend_from_slice(&amount.to_le_bytes());
+        data.extend_from_slice(&max_cost.to_le_bytes());
+        data.extend_from_slice(&[0; NICKNAME_STRING_LENGTH]);
+        Instruction::new_with_bytes(program_id(), &data, accounts)
+    }
+
+    pub fn burn_ix(
+        &self,
+        trader: &Pubkey,
+        token: &Pubkey,
+        hype_mint: &Pubkey,
+        hype_program_acc: &Pubkey,
+        amount: u64,
+        min_cashout: u64,
+        referrers: &[Pubkey],
+    ) -> Instruction {
+        let mut accounts =
+            self.trade_accounts(trader, token, hype_mint, hype_program_acc, referrers);
+        accounts[8].is_signer = false;
+        accounts[9].is_signer = false;
+        let mut data = vec![0u8; 8];
+        data[0] = 5;
+        data.extend_from_slice(&amount.to_le_bytes());
+        data.extend_from_slice(&min_cashout.to_le_bytes());
+        data.extend_from_slice(&[0; NICKNAME_STRING_LENGTH]);
+        Instruction::new_with_bytes(program_id(), &data, accounts)
+    }
+
+    /// `cpi_trade` of `trader`; the referral accounts of `referrers` follow the 14 accounts.
+    pub fn cpi_trade_ix(
+        &self,
+        trader: &Pubkey,
+        token: &Pubkey,
+        hype_mint: &Pubkey,
+        hype_program_acc: &Pubkey,
+        side: u32,
+        amount: u64,
+        limit: u64,
+        referrers: &[Pubkey],
+    ) -> Instruction {
+        let accounts = self.trade_accounts(trader, token, hype_mint, hype_program_acc, referrers);
+        let mut ix_accounts = accounts[..11].to_vec();
+        ix_accounts[8].is_signer = false;
+        ix_accounts[9].is_signer = false;
+        ix_accounts.push(AccountMeta::new_readonly(self.base_token_program, false));
+        ix_accounts.push(AccountMeta::new_readonly(spl_token_2022::id(), false));
+        ix_accounts.push(AccountMeta::new_readonly(system_program::ID, false));
+        if !referrers.is_empty() {
+            ix_accounts.extend_from_slice(&accounts[15..]);
+        }
+        let mut data = vec![0u8; 8];
+        data[0] = 26;
+        data.extend_from_slice(&side.to_le_bytes());
+        data.extend_from_slice(&[0; 4]);
+        data.extend_from_slice(&amount.to_le_bytes());
+        data.extend_from_slice(&limit.to_le_bytes());
+        Instruction::new_with_bytes(program_id(), &data, ix_accounts)
+    }
+
+    /// `swap` of `trader` in the pool of `hype_mint`, paying in `amount` for at least `min_amount_out`.
+    pub fn swap_ix(
+        &self,
+        trader: &Pubkey,
+        token: &Pubkey,
+        hype_mint: &Pubkey,
+        hype_program_acc: &Pubkey,
+        side: u32,
+        amount: u64,
+        min_amount_out: u64,
+    ) -> Instruction {
+        let mut ix = self.cpi_trade_ix(
+            trader,
+            token,
+            hype_mint,
+            hype_program_acc,
+            side,
+            amount,
+            min_amount_out,
+            &[],
+        );
+        ix.data[0] = 29;
+        ix.accounts
+            .insert(0, AccountMeta::new(pool_address(VERSION, hype_mint), false));
+        ix
+    }
+
+    /// `set_delegate` granting `delegate` the given limits, also for selling `hype_mints`.
+    pub fn set_delegate_ix(
+        &self,
+        wallet: &Pubkey,
+        delegate: &Pubkey,
+        spend_limit: u64,
+        networks: u64,
+        period: u32,
+        expiry: u32,
+        hype_mints: &[Pubkey],
+    ) -> Instruction {
+        let mut accounts = vec![
+            AccountMeta::new(*wallet, true),
+            AccountMeta::new_readonly(client_address(VERSION, wallet), false),
+            AccountMeta::new_readonly(*delegate, false),
+            AccountMeta::new(delegate_address(VERSION, wallet, delegate), false),
+            AccountMeta::new(self.base_ata(wallet), false),
+            AccountMeta::new_readonly(self.base_mint, false),
+            AccountMeta::new_readonly(self.base_token_program, false),
+            AccountMeta::new_readonly(spl_token_2022::id(), false),
+            AccountMeta::new_readonly(system_program::ID, false),
+            AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+        ];
+        for hype_mint in hype_mints {
+            accounts.push(AccountMeta::new_readonly(*hype_mint, false));
+            accounts.push(AccountMeta::new(self.hype_ata(wallet, hype_mint), false));
+        }
+        let mut data = vec![0u8; 8];
+        data[0] = 24;
+        data.extend_from_slice(&spend_limit.to_le_bytes());
+        data.extend_from_slice(&networks.to_le_bytes());
+        data.extend_from_slice(&period.to_le_bytes());
+        data.extend_from_slice(&expiry.to_le_bytes());
+        Instruction::new_with_bytes(program_id(), &data, accounts)
+    }
+
+    pub fn revoke_delegate_ix(
+        &self,
+        wallet: &Pubkey,
+        delegate: &Pubkey,
+        hype_mints: &[Pubkey],
+    ) -> Instruction {
+        let mut accounts = vec![
+            AccountMeta::new(*wallet, true),
+            AccountMeta::new(delegate_address(VERSION, wallet, delegate), false),
+            AccountMeta::new(self.base_ata(wallet), false),
+            AccountMeta::new_readonly(self.base_token_program, false),
+            AccountMeta::new_readonly(spl_token_2022::id(), false),
+        ];
+        for hype_mint in hype_mints {
+            accounts.push(AccountMeta::new(self.hype_ata(wallet, hype_mint), false));
+        }
+        Instruction::new_with_bytes(program_id(), &[25], accounts)
+    }
+
+    /// `mint_for` paid by `payer` and delivered to `recipient`.
+    pub fn mint_for_ix(
+        &self,
+        payer: &Pubkey,
+        recipient: &Pubkey,
+        token: &Pubkey,
+        hype_mint: &Pubkey,
+        hype_program_acc: &Pubkey,
+        amount: u64,
+        max_cost: u64,
+    ) -> Instruction {
+        let mut accounts = self.trade_accounts(payer, token, hype_mint, hype_program_acc, &[]);
+        accounts[8].is_signer = false;
+        accounts[9].is_signer = false;
+        accounts.push(AccountMeta::new_readonly(*recipient, false));
+        accounts.push(AccountMeta::new(self.hype_ata(recipient, hype_mint), false));
+        let mut data = vec![0u8; 8];
+        data[0] = 23;
+        data.extend_from_slice(&amount.to_le_bytes());
+        data.extend_from_slice(&max_cost.to_le_bytes());
+        data.extend_from_slice(&[0; NICKNAME_STRING_LENGTH]);
+        Instruction::new_with_bytes(program_id(), &data, accounts)
+    }
+
+    /// `batch_trade` of `legs` without a referral; the first leg fills the `mint` slots.
+    pub fn batch_ix(&self, trader: &Pubkey, legs: &[Leg]) -> Instruction {
+        let first = legs[0];
+        let mut accounts = self.trade_accounts(
+            trader,
+            &first.token,
+            &first.hype_mint,
+            &first.hype_program_acc,
+            &[],
+        );
+        accounts[8].is_signer = false;
+        accounts[9].is_signer = false;
+        for leg in &legs[1..] {
+            accounts.push(AccountMeta::new(leg.token, false));
+            accounts.push(AccountMeta::new(leg.hype_mint, false));
+            accounts.push(AccountMeta::new(leg.hype_program_acc, false));
+            accounts.push(AccountMeta::new(
+                self.hype_ata(trader, &leg.hype_mint),
+                false,
+            ));
+        }
+        let mut data = vec![0u8; 8];
+        data[0] = 21;
+        data.extend_from_slice(&(legs.len() as u32).to_le_bytes());
+        data.extend_from_slice(&[0; 4]);
+        for leg in legs {
+            data.extend_from_slice(&leg.side.to_le_bytes());
+            data.extend_from_slice(&[0; 4]);
+            data.extend_from_slice(&leg.amount.to_le_bytes());
+            data.extend_from_slice(&leg.limit.to_le_bytes());
+        }
+        Instruction::new_with_bytes(program_id(), &data, accounts)
+    }
+
+    /// `switch` of `amount` of the `from` token into at least `min_amount_out` of the `to` token.
+    pub fn switch_ix(
+        &self,
+        trader: &Pubkey,
+        from: (Pubkey, Pubkey, Pubkey),
+        to: (Pubkey, Pubkey, Pubkey),
+        amount: u64,
+        min_amount_out: u64,
+        referrers: &[Pubkey],
+    ) -> Instruction {
+        let mut accounts = self.trade_accounts(trader, &from.0, &from.1, &from.2, referrers);
+        accounts[8].is_signer = false;
+        accounts[9].is_signer = false;
+        accounts.push(AccountMeta::new(to.0, false));
+        accounts.push(AccountMeta::new(to.1, false));
+        accounts.push(AccountMeta::new(to.2, false));
+        accounts.push(AccountMeta::new(self.hype_ata(trader, &to.1), false));
+        let mut data = vec![0u8; 8];
+        data[0] = 22;
+        data.extend_from_slice(&amount.to_le_bytes());
+        data.extend_from_slice(&min_amount_out.to_le_bytes());
+        Instruction::new_with_bytes(program_id(), &data, accounts)
+    }
+
+    /// First `mint` on `address`: creates the token account, the Token-2022 mint and its holding account.
+    pub async fn create_token(
+        &mut self,
+        trader: &Keypair,
+        network: u32,
+        address: &str,
+        amount: u64,
+    ) -> Result<(Pubkey, Pubkey, Pubkey, Vec<String>), BanksClientError> {
+        self.create_referred_token(trader, network, address, amount, &[])
+            .await
+    }
+
+    /// `create_token` signing `trader` up with the referral chain `referrers`; a self-referral is not recorded.
+    pub async fn create_referred_token(
+        &mut self,
+        trader: &Keypair,
+        network: u32,
+        address: &str,
+        amount: u64,
+        referrers: &[Pubkey],
+    ) -> Result<(Pubkey, Pubkey, Pubkey, Vec<String>), BanksClientError> {
+        self.create_token_with(trader, network, address, amount, referrers, false)
+            .await
+    }
+
+    /// `create_token` passing `trader`'s launch buy account of the new token, which buys
+    /// in the launch window of a root with launch limits need.
+    pub async fn create_launch_token(
+        &mut self,
+        trader: &Keypair,
+        network: u32,
+        address: &str,
+        amount: u64,
+    ) -> Result<(Pubkey, Pubkey, Pubkey, Vec<String>), BanksClientError> {
+        self.create_token_with(trader, network, address, amount, &[], true)
+            .await
+    }
+
+    async fn create_token_with(
+        &mut self,
+        trader: &Keypair,
+        network: u32,
+        address: &str,
+        amount: u64,
+        referrers: &[Pubkey],
+        launch_buy: bool,
+    ) -> Result<(Pubkey, Pubkey, Pubkey, Vec<String>), BanksClientError> {
+        let hype_mint = Keypair::new();
+        let hype_program_acc = Keypair::new();
+        let mut ix = self.mint_ix(
+            &trader.pubkey(),
+            network,
+            address,
+            &hype_mint.pubkey(),
+            &hype_program_acc.pubkey(),
+            amount,
+            0,
+            referrers,
+        );
+        ix.accounts[8].is_signer = true;
+        ix.accounts[9].is_signer = true;
+        if let Some(referrer) = referrers.first().filter(|key| **key != trader.pubkey()) {
+            ix = Self::signing_up(ix, referrer);
+        }
+        if launch_buy {
+            ix = with_trade_extras(
+                ix,
+                TRADE_EXTRA_LAUNCH_BUY,
+                &[launch_buy_address(
+                    VERSION,
+                    &trader.pubkey(),
+                    &hype_mint.pubkey(),
+                )],
+            );
+        }
+        let token = ix.accounts[5].pubkey;
+        let logs = self
+            .process(&[ix], &[trader, &hype_mint, &hype_program_acc])
+            .await?;
+        Ok((token, hype_mint.pubkey(), hype_program_acc.pubkey(), logs))
+    }
+
+    pub async fn graduate(
+        &mut self,
+        token: &Pubkey,
+        hype_mint: &Pubkey,
+        hype_program_acc: &Pubkey,
+    ) -> Result<Vec<String>, BanksClientError> {
+        let ix = Instruction::new_with_bytes(
+            program_id(),
+            &[28],
+            vec![
+                AccountMeta::new(self.context.payer.pubkey(), true),
+                AccountMeta::new(self.root, false),
+                AccountMeta::new(*token, false),
+                AccountMeta::new(*hype_mint, false),
+                AccountMeta::new(*hype_program_acc, false),
+                AccountMeta::new_readonly(hype_auth(), false),
+                AccountMeta::new(pool_address(VERSION, hype_mint), false),
+                AccountMeta::new_readonly(spl_token_2022::id(), false),
+                AccountMeta::new_readonly(system_program::ID, false),
+            ],
+        );
+        self.process(&[ix], &[]).await
+    }
+
+    pub async fn migrate_account(
+        &mut self,
+        account: &Pubkey,
+    ) -> Result<Vec<String>, BanksClientError> {
+        let ix = Instruction::new_with_bytes(
+            program_id(),
+            &[30],
+            vec![
+                AccountMeta::new(self.context.payer.pubkey(), true),
+                AccountMeta::new(*account, false),
+                AccountMeta::new_readonly(system_program::ID, false),
+            ],
+        );
+        self.process(&[ix], &[]).await
+    }
+
+    pub async fn change_token_status(
+        &mut self,
+        token: &Pubkey,
+        verified: bool,
+    ) -> Result<Vec<String>, BanksClientError> {
+        let ix = Instruction::new_with_bytes(
+            program_id(),
+            &[7, verified as u8],
+            vec![
+                AccountMeta::new_readonly(self.validator.pubkey(), true),
+                AccountMeta::new_readonly(self.root, false),
+                AccountMeta::new(*token, false),
+            ],
+        );
+        let validator = self.validator.insecure_clone();
+        self.process(&[ix], &[&validator]).await
+    }
+
+    /// `tokens` are (token account, hype mint) pairs in token id order.
+    pub async fn check_invariants(
+        &mut self,
+        tokens: &[(Pubkey, Pubkey)],
+    ) -> Result<Vec<String>, BanksClientError> {
+        let mut accounts = vec![
+            AccountMeta::new_readonly(self.root, false),
+            AccountMeta::new_readonly(self.vault.pubkey(), false),
+        ];
+        for (token, hype_mint) in tokens {
+            accounts.push(AccountMeta::new_readonly(*token, false));
+            accounts.push(AccountMeta::new_readonly(*hype_mint, false));
+        }
+        let ix = Instruction::new_with_bytes(program_id(), &[10], accounts);
+        self.simulate(&[ix]).await
+    }
+
+    /// Opens the referrer account of `referrer`, which trades never do, by a claim of nothing.
+    pub async fn open_referrer_account(&mut self, referrer: &Keypair) {
+        self.claim_referral_fees(referrer).await.unwrap();
+        assert!(self
+            .account(&referrer_address(VERSION, &referrer.pubkey()))
+            .await
+            .is_some());
+    }
+
+    pub async fn claim_referral_fees(
+        &mut self,
+        referrer: &Keypair,
+    ) -> Result<Vec<String>, BanksClientError> {
+        let wallet = referrer.pubkey();
+        let ix = Instruction::new_with_bytes(
+            program_id(),
+            &[11],
+            vec![
+                AccountMeta::new(wallet, true),
+                AccountMeta::new(referrer_address(VERSION, &wallet), false),
+                AccountMeta::new(self.root, false),
+                AccountMeta::new_readonly(self.base_mint, false),
+                AccountMeta::new(self.vault.pubkey(), false),
+                AccountMeta::new(self.base_ata(&wallet), false),
+                AccountMeta::new_readonly(self.base_token_program, false),
+                AccountMeta::new_readonly(hype_auth(), false),
+                AccountMeta::new_readonly(system_program::ID, false),
+                AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+            ],
+        );
+        self.process(&[ix], &[referrer]).await
+    }
+
+    pub async fn claim_creator_fees(
+        &mut self,
+        creator: &Keypair,
+        token: &Pubkey,
+    ) -> Result<Vec<String>, BanksClientError> {
+        let wallet = creator.pubkey();
+        let ix = Instruction::new_with_bytes(
+            program_id(),
+            &[16],
+            vec![
+                AccountMeta::new(wallet, true),
+                AccountMeta::new(*token, false),
+                AccountMeta::new(self.root, false),
+                AccountMeta::new_readonly(self.base_mint, false),
+                AccountMeta::new(self.vault.pubkey(), false),
+                AccountMeta::new(self.base_ata(&wallet), false),
+                AccountMeta::new_readonly(self.base_token_program, false),
+                AccountMeta::new_readonly(hype_auth(), false),
+                AccountMeta::new_readonly(system_program::ID, false),
+                AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+            ],
+        );
+        self.process(&[ix], &[creator]).await
+    }
+
+    /// `change_client_data` releasing the current nickname of `wallet` and claiming `nickname`.
+    pub async fn change_nickname(
+        &mut self,
+        wallet: &Keypair,
+        nickname: &str,
+    ) -> Result<Vec<String>, BanksClientError> {
+        let client = self.client_account(&wallet.pubkey()).await;
+        let old_nickname_acc = client_nickname_address(VERSION, &client.nickname);
+        let new_nickname_acc =
+            client_nickname_address(VERSION, &fixed::<NICKNAME_STRING_LENGTH>(nickname));
+        let mut data = vec![0u8; 8];
+        data[0] = 6;
+        data.extend_from_slice(&fixed::<NICKNAME_STRING_LENGTH>(nickname));
+        let ix = Instruction::new_with_bytes(
+            program_id(),
+            &data,
+            vec![
+                AccountMeta::new(wallet.pubkey(), true),
+                AccountMeta::new(client_address(VERSION, &wallet.pubkey()), false),
+                AccountMeta::new(old_nickname_acc, false),
+                AccountMeta::new(new_nickname_acc, false),
+                AccountMeta::new_readonly(system_program::ID, false),
+                AccountMeta::new_readonly(
+                    client_ref_code_address(VERSION, &fixed::<NICKNAME_STRING_LENGTH>(nickname)),
+                    false,
+                ),
+            ],
+        );
+        self.process(&[ix], &[wallet]).await
+    }
+
+    pub async fn close_client(
+        &mut self,
+        wallet: &Keypair,
+    ) -> Result<Vec<String>, BanksClientError> {
+        let client = self.client_account(&wallet.pubkey()).await;
+        let ix = Instruction::new_with_bytes(
+            program_id(),
+            &[17],
+            vec![
+                AccountMeta::new(wallet.pubkey(), true),
+                AccountMeta::new(client_address(VERSION, &wallet.pubkey()), false),
+                AccountMeta::new_readonly(referrer_address(VERSION, &wallet.pubkey()), false),
+                AccountMeta::new(client_nickname_address(VERSION, &client.nickname), false),
+            ],
+        );
+        self.process(&[ix], &[wallet]).await
+    }
+
+    pub async fn retire_token(
+        &mut self,
+        token: &Pubkey,
+        hype_mint: &Pubkey,
+        hype_program_acc: &Pubkey,
+        creator: &Pubkey,
+    ) -> Result<Vec<String>, BanksClientError> {
+        let ix = Instruction::new_with_bytes(
+            program_id(),
+            &[18],
+            vec![
+                AccountMeta::new(self.root, false),
+                AccountMeta::new(*token, false),
+                AccountMeta::new(*hype_mint, false),
+                AccountMeta::new(*hype_program_acc, false),
+                AccountMeta::new(*creator, false),
+                AccountMeta::new_readonly(hype_auth(), false),
+                AccountMeta::new_readonly(spl_token_2022::id(), false),
+            ],
+        );
+        self.process(&[ix], &[]).await
+    }
+
+    pub async fn init_price_history(
+        &mut self,
+        payer: &Keypair,
+        token: &Pubkey,
+        hype_mint: &Pubkey,
+    ) -> Result<Vec<String>, BanksClientError> {
+        let ix = Instruction::new_with_bytes(
+            program_id(),
+            &[19],
+            vec![
+                AccountMeta::new(payer.pubkey(), true),
+                AccountMeta::new_readonly(self.root, false),
+                AccountMeta::new(*token, false),
+                AccountMeta::new(price_history_address(VERSION, hype_mint), false),
+                AccountMeta::new_readonly(system_program::ID, false),
+            ],
+        );
+        self.process(&[ix], &[payer]).await
+    }
+
+    /// `code` is sent as is; `address` is the code account it should land in.
+    pub async fn register_ref_code(
+        &mut self,
+        wallet: &Keypair,
+        code: &str,
+        address: &Pubkey,
+    ) -> Result<Vec<String>, BanksClientError> {
+        let name = match code {
+            "" => self.client_account(&wallet.pubkey()).await.nickname,
+            _ => fixed::<NICKNAME_STRING_LENGTH>(code),
+        };
+        let mut data = vec![0u8; 8];
+        data[0] = 12;
+        data.extend_from_slice(&fixed::<NICKNAME_STRING_LENGTH>(code));
+        let ix = Instruction::new_with_bytes(
+            program_id(),
+            &data,
+            vec![
+                AccountMeta::new(wallet.pubkey(), true),
+                AccountMeta::new_readonly(client_address(VERSION, &wallet.pubkey()), false),
+                AccountMeta::new(*address, false),
+                AccountMeta::new_readonly(system_program::ID, false),
+                AccountMeta::new(referrer_address(VERSION, &wallet.pubkey()), false),
+                AccountMeta::new_readonly(client_nickname_address(VERSION, &name), false),
+            ],
+        );
+        self.process(&[ix], &[wallet]).await
+    }
+
+    pub async fn release_ref_code(
+        &mut self,
+        wallet: &Keypair,
+        address: &Pubkey,
+    ) -> Result<Vec<String>, BanksClientError> {
+        let ix = Instruction::new_with_bytes(
+            program_id(),
+            &[13],
+            vec![
+                AccountMeta::new(wallet.pubkey(), true),
+                AccountMeta::new(*address, false),
+            ],
+        );
+        self.process(&[ix], &[wallet]).await
+    }
+
+    /// `ref_acc` is `referrer` itself or one of its referral code accounts.
+    pub async fn set_referrer(
+        &mut self,
+        wallet: &Keypair,
+        ref_acc: &Pubkey,
+        referrer: &Pubkey,
+    ) -> Result<Vec<String>, BanksClientError> {
+        let ix = Instruction::new_with_bytes(
+            program_id(),
+            &[14],
+            vec![
+                AccountMeta::new_readonly(wallet.pubkey(), true),
+                AccountMeta::new(client_address(VERSION, &wallet.pubkey()), false),
+                AccountMeta::new_readonly(self.root, false),
+                AccountMeta::new_readonly(*ref_acc, false),
+                AccountMeta::new_readonly(client_address(VERSION, referrer), false),
+            ],
+        );
+        self.process(&[ix], &[wallet]).await
+    }
+
+    /// `tiers` are (min volume, fee rate) pairs.
+    pub async fn set_fee_tiers(
+        &mut self,
+        admin: &Keypair,
+        tiers: &[(u64, f64)],
+    ) -> Result<Vec<String>, BanksClientError> {
+        let mut data = vec![0u8; 8];
+        data[0] = 15;
+        data.extend_from_slice(&(tiers.len() as u32).to_le_bytes());
+        data.extend_from_slice(&[0; 4]);
+        for (min_volume, fee_rate) in tiers {
+            data.extend_from_slice(&min_volume.to_le_bytes());
+            data.extend_from_slice(&fee_rate.to_le_bytes());
+        }
+        let ix = Instruction::new_with_bytes(
+            program_id(),
+            &data,
+            vec![
+                AccountMeta::new_readonly(admin.pubkey(), true),
+                AccountMeta::new(self.root, false),
+            ],
+        );
+        self.process(&[ix], &[admin]).await
+    }
+
+    /// `set_launch_allowance` letting `wallet` into restricted launch windows or not.
+    pub async fn set_launch_allowance(
+        &mut self,
+        admin: &Keypair,
+        wallet: &Pubkey,
+        allowed: bool,
+    ) -> Result<Vec<String>, BanksClientError> {
+        let mut data = vec![0u8; 8];
+        data[0] = 27;
+        data.extend_from_slice(&(allowed as u32).to_le_bytes());
+        let ix = Instruction::new_with_bytes(
+            program_id(),
+            &data,
+            vec![
+                AccountMeta::new(admin.pubkey(), true),
+                AccountMeta::new_readonly(self.root, false),
+                AccountMeta::new_readonly(*wallet, false),
+                AccountMeta::new(launch_allowance_address(VERSION, wallet), false),
+                AccountMeta::new_readonly(system_program::ID, false),
+            ],
+        );
+        self.process(&[ix], &[admin]).await
+    }
+
+    pub fn withdraw_operator_funds_ix(&self) -> Instruction {
+        Instruction::new_with_bytes(
+            program_id(),
+            &[8],
+            vec![
+                AccountMeta::new(self.operator.pubkey(), true),
+                AccountMeta::new(self.root, false),
+                AccountMeta::new_readonly(self.base_mint, false),
+                AccountMeta::new(self.vault.pubkey(), false),
+                AccountMeta::new_readonly(self.fee_wallet.pubkey(), false),
+                AccountMeta::new(self.base_ata(&self.fee_wallet.pubkey()), false),
+                AccountMeta::new_readonly(self.base_token_program, false),
+                AccountMeta::new_readonly(hype_auth(), false),
+                AccountMeta::new_readonly(system_program::ID, false),
+                AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+            ],
+        )
+    }
+
+    pub async fn withdraw_operator_funds(&mut self) -> Result<Vec<String>, BanksClientError> {
+        let ix = self.withdraw_operator_funds_ix();
+        let operator = self.operator.insecure_clone();
+        self.process(&[ix], &[&operator]).await
+    }
+
+    pub fn withdraw_holder_funds_ix(&self) -> Instruction {
+        Instruction::new_with_bytes(
+            program_id(),
+            &[9],
+            vec![
+                AccountMeta::new(self.holder_admin.pubkey(), true),
+                AccountMeta::new_readonly(self.holder, false),
+                AccountMeta::new(self.root, false),
+                AccountMeta::new_readonly(self.base_mint, false),
+                AccountMeta::new(self.vault.pubkey(), false),
+                AccountMeta::new_readonly(self.holder_wallet.pubkey(), false),
+                AccountMeta::new(self.base_ata(&self.holder_wallet.pubkey()), false),
+                AccountMeta::new_readonly(self.base_token_program, false),
+                AccountMeta::new_readonly(hype_auth(), false),
+                AccountMeta::new_readonly(system_program::ID, false),
+                AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+            ],
+        )
+    }
+
+    pub async fn withdraw_holder_funds(&mut self) -> Result<Vec<String>, BanksClientError> {
+        let ix = self.withdraw_holder_funds_ix();
+        let admin = self.holder_admin.insecure_clone();
+        self.process(&[ix], &[&admin]).await
+    }
+}
@@ -0,0 +1,101 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use common::*;
+use hype_watch::{program::*, state::TradeResult};
+use solana_sdk::signature::Signer;
+
+const TRADER_FUNDS: u64 = 1_000_000_000_000_000;
+
+#[tokio::test]
+async fn cpi_trade_returns_the_fill() {
+    let mut env = TestEnv::setup(spl_token::id()).await;
+    let params = env.params;
+    let creator = env.new_trader(TRADER_FUNDS).await;
+    let trader = env.new_trader(TRADER_FUNDS).await;
+    let wallet = trader.pubkey();
+    let supply = 1_000_000;
+    let (token, hype_mint, hype_program_acc, _) =
+        env.create_token(&creator, 0, "doge", supply).await.unwrap();
+    // The hype account has to exist beforehand
+    let ix = env.mint_ix(&wallet, 0, "doge", &hype_mint, &hype_program_acc, 1, 0, &[]);
+    env.process(&[ix], &[&trader]).await.unwrap();
+    let supply = supply + 1;
+
+    let amount = 5_000_000;
+    let ix = env.cpi_trade_ix(
+        &wallet,
+        &token,
+        &hype_mint,
+        &hype_program_acc,
+        BATCH_SIDE_BUY,
+        amount,
+        0,
+        &[],
+    );
+    let result =
+        TradeResult::from_bytes(&env.return_data(ix.clone(), &[&trader]).await.unwrap()).unwrap();
+    let quote = expected_mint(&params, supply, amount, false);
+    assert_eq!(
+        result,
+        TradeResult {
+            amount,
+            base_crncy_amount: quote.base_crncy_amount,
+            fees: quote.fees,
+            payment: quote.base_crncy_amount + quote.fees,
+            supply: supply + amount,
+        }
+    );
+    let base = env.base_ata(&wallet);
+    let before = env.balance(&base).await;
+    env.process(&[ix], &[&trader]).await.unwrap();
+    assert_eq!(before - env.balance(&base).await, result.payment);
+
+    let ix = env.cpi_trade_ix(
+        &wallet,
+        &token,
+        &hype_mint,
+        &hype_program_acc,
+        BATCH_SIDE_SELL,
+        amount,
+        0,
+        &[],
+    );
+    let result = TradeResult::from_bytes(&env.return_data(ix, &[&trader]).await.unwrap()).unwrap();
+    let quote = expected_burn(&params, supply + amount, amount);
+    assert_eq!(result.base_crncy_amount, quote.base_crncy_amount);
+    assert_eq!(result.fees, quote.fees);
+    assert_eq!(result.payment, quote.base_crncy_amount - quote.fees);
+    assert_eq!(result.supply, supply);
+    env.check_invariants(&[(token, hype_mint)]).await.unwrap();
+}
+
+#[tokio::test]
+async fn cpi_trade_needs_the_referral_accounts_only_while_active() {
+    let mut env = TestEnv::setup(spl_token::id()).await;
+    let referrer = env.new_trader(TRADER_FUNDS).await;
+    let trader = env.new_trader(TRADER_FUNDS).await;
+    let wallet = trader.pubkey();
+    let (token, hype_mint, hype_program_acc, _) = env
+        .create_referred_token(&trader, 0, "doge", 1_000_000, &[referrer.pubkey()])
+        .await
+        .unwrap();
+
+    let trade = |env: &TestEnv, referrers: &[_]| {
+        env.cpi_trade_ix(
+            &wallet,
+            &token,
+            &hype_mint,
+            &hype_program_acc,
+            BATCH_SIDE_BUY,
+            1_000,
+            0,
+            referrers,
+        )
+    };
+    assert!(env.process(&[trade(&env, &[])], &[&trader]).await.is_err());
+    let ix = trade(&env, &[referrer.pubkey()]);
+    env.process(&[ix], &[&trader]).await.unwrap();
+    assert_eq!(env.client_account(&wallet).await.all_time_trades_count, 2);
+}
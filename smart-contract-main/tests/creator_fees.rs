@@ -0,0 +1,125 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use common::*;
+use hype_watch::program::error::HypeError;
+use solana_sdk::signature::Signer;
+
+const TRADER_FUNDS: u64 = 1_000_000_000_000_000;
+
+#[tokio::test]
+async fn creator_fees_accrue_per_token() {
+    let mut env = TestEnv::new(spl_token::id()).await;
+    env.params.creator_fee_ratio = 0.2;
+    let params = env.params;
+    env.initialize_holder().await.unwrap();
+    env.add_operator(10).await.unwrap();
+    env.initialize_root().await.unwrap();
+    env.add_network("ethereum", "abcdefghijklmnopqrstuvwxyz0123456789", 24)
+        .await
+        .unwrap();
+    assert_hype_error(
+        env.add_capped_network(
+            "solana",
+            "abcdefghijklmnopqrstuvwxyz0123456789",
+            24,
+            Some(1.5),
+        )
+        .await,
+        HypeError::InvalidCreatorFeeRatio,
+    );
+    env.add_capped_network(
+        "solana",
+        "abcdefghijklmnopqrstuvwxyz0123456789",
+        24,
+        Some(0.05),
+    )
+    .await
+    .unwrap();
+    let creator = env.new_trader(TRADER_FUNDS).await;
+    let trader = env.new_trader(TRADER_FUNDS).await;
+    let amount = 1_000_000;
+
+    // Creators earn nothing on their own trades
+    let (token, hype_mint, hype_program_acc, _) =
+        env.create_token(&creator, 0, "doge", amount).await.unwrap();
+    assert_eq!(env.token_account(&token).await.creator_fees, 0);
+
+    let quote = expected_creator_mint(&params, amount, amount, 0.2);
+    assert!(quote.creator_fees > 0);
+    let ix = env.mint_ix(
+        &trader.pubkey(),
+        0,
+        "doge",
+        &hype_mint,
+        &hype_program_acc,
+        amount,
+        0,
+        &[],
+    );
+    env.process(&[ix], &[&trader]).await.unwrap();
+    let burn = expected_creator_burn(&params, 2 * amount, amount, 0.2);
+    let ix = env.burn_ix(
+        &trader.pubkey(),
+        &token,
+        &hype_mint,
+        &hype_program_acc,
+        amount,
+        0,
+        &[],
+    );
+    env.process(&[ix], &[&trader]).await.unwrap();
+    let creator_fees = quote.creator_fees + burn.creator_fees;
+    let token_account = env.token_account(&token).await;
+    assert_eq!(token_account.creator_fees, creator_fees);
+    assert_eq!(token_account.all_time_creator_fees, creator_fees);
+
+    // The network cap lowers the root share
+    let (capped_token, capped_mint, capped_program_acc, _) =
+        env.create_token(&creator, 1, "bonk", amount).await.unwrap();
+    let capped = expected_creator_mint(&params, amount, amount, 0.05);
+    let ix = env.mint_ix(
+        &trader.pubkey(),
+        1,
+        "bonk",
+        &capped_mint,
+        &capped_program_acc,
+        amount,
+        0,
+        &[],
+    );
+    env.process(&[ix], &[&trader]).await.unwrap();
+    assert_eq!(
+        env.token_account(&capped_token).await.creator_fees,
+        capped.creator_fees
+    );
+    assert_eq!(
+        env.root_account().await.creator_fees,
+        creator_fees + capped.creator_fees
+    );
+    env.check_invariants(&[(token, hype_mint), (capped_token, capped_mint)])
+        .await
+        .unwrap();
+
+    // Only the creator claims, once
+    assert_hype_error(
+        env.claim_creator_fees(&trader, &token).await,
+        HypeError::InvalidTokenCreator,
+    );
+    let creator_base = env.base_ata(&creator.pubkey());
+    let balance = env.balance(&creator_base).await;
+    let logs = env.claim_creator_fees(&creator, &token).await.unwrap();
+    assert_eq!(env.balance(&creator_base).await, balance + creator_fees);
+    let event = &events_with_tag(&logs, 10)[0];
+    assert_eq!(field_u64(event, 1), token_account.id);
+    assert_eq!(field_u64(event, 3), creator_fees);
+    assert_eq!(field_u64(event, 4), creator_fees);
+    assert_eq!(env.token_account(&token).await.creator_fees, 0);
+    assert_eq!(env.root_account().await.creator_fees, capped.creator_fees);
+    env.claim_creator_fees(&creator, &token).await.unwrap();
+    assert_eq!(env.balance(&creator_base).await, balance + creator_fees);
+    env.check_invariants(&[(token, hype_mint), (capped_token, capped_mint)])
+        .await
+        .unwrap();
+}
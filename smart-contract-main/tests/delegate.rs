@@ -0,0 +1,249 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use common::*;
+use hype_watch::program::error::HypeError;
+use solana_sdk::signature::Signer;
+
+const TRADER_FUNDS: u64 = 1_000_000_000_000_000;
+
+#[tokio::test]
+async fn delegate_trades_within_its_limits() {
+    let mut env = TestEnv::setup(spl_token::id()).await;
+    let params = env.params;
+    env.add_network("ethereum", "abcdefghijklmnopqrstuvwxyz0123456789", 24)
+        .await
+        .unwrap();
+    let owner = env.new_trader(TRADER_FUNDS).await;
+    let wallet = owner.pubkey();
+    let bot = env.new_trader(0).await;
+    let supply = 1_000_000;
+    let (token, hype_mint, hype_program_acc, _) =
+        env.create_token(&owner, 0, "doge", supply).await.unwrap();
+    let (other, other_mint, other_program_acc, _) =
+        env.create_token(&owner, 1, "shib", supply).await.unwrap();
+
+    // Network 0 only, a spend limit of two buys per day
+    let amount = 1_000_000;
+    let quote = expected_mint(&params, supply, amount, false);
+    let next_quote = expected_mint(&params, supply + amount, amount, false);
+    let spend_limit =
+        quote.base_crncy_amount + quote.fees + next_quote.base_crncy_amount + next_quote.fees;
+    let ix = env.set_delegate_ix(
+        &wallet,
+        &bot.pubkey(),
+        spend_limit,
+        1,
+        86_400,
+        0,
+        &[hype_mint, other_mint],
+    );
+    env.process(&[ix], &[&owner]).await.unwrap();
+
+    let buy = |env: &TestEnv| {
+        delegated(
+            env.mint_ix(
+                &wallet,
+                0,
+                "doge",
+                &hype_mint,
+                &hype_program_acc,
+                amount,
+                0,
+                &[],
+            ),
+            &wallet,
+            &bot.pubkey(),
+        )
+    };
+    let base = env.base_ata(&wallet);
+    let before = env.balance(&base).await;
+    env.process(&[buy(&env)], &[&bot]).await.unwrap();
+    assert_eq!(
+        before - env.balance(&base).await,
+        quote.base_crncy_amount + quote.fees
+    );
+    assert_eq!(
+        env.balance(&env.hype_ata(&wallet, &hype_mint)).await,
+        supply + amount
+    );
+    assert_eq!(env.client_account(&wallet).await.all_time_trades_count, 2);
+    env.process(&[buy(&env)], &[&bot]).await.unwrap();
+    assert_hype_error(
+        env.process(&[buy(&env)], &[&bot]).await,
+        HypeError::DelegateSpendLimitExceeded,
+    );
+
+    // Sells need no spend allowance, but the network has to be allowed
+    let sell = delegated(
+        env.burn_ix(
+            &wallet,
+            &token,
+            &hype_mint,
+            &hype_program_acc,
+            amount,
+            0,
+            &[],
+        ),
+        &wallet,
+        &bot.pubkey(),
+    );
+    env.process(&[sell], &[&bot]).await.unwrap();
+    let ix = delegated(
+        env.mint_ix(
+            &wallet,
+            1,
+            "shib",
+            &other_mint,
+            &other_program_acc,
+            amount,
+            0,
+            &[],
+        ),
+        &wallet,
+        &bot.pubkey(),
+    );
+    assert_hype_error(
+        env.process(&[ix], &[&bot]).await,
+        HypeError::DelegateNetworkNotAllowed,
+    );
+    env.check_invariants(&[(token, hype_mint), (other, other_mint)])
+        .await
+        .unwrap();
+
+    // Revoking ends the delegation at once
+    let ix = env.revoke_delegate_ix(&wallet, &bot.pubkey(), &[hype_mint]);
+    env.process(&[ix], &[&owner]).await.unwrap();
+    assert!(env
+        .account(&delegate_address(VERSION, &wallet, &bot.pubkey()))
+        .await
+        .is_none());
+    assert_hype_error(
+        env.process(&[buy(&env)], &[&bot]).await,
+        HypeError::InvalidDelegateAccount,
+    );
+}
+
+#[tokio::test]
+async fn delegate_trades_tokens_new_to_the_wallet() {
+    let mut env = TestEnv::setup(spl_token::id()).await;
+    let creator = env.new_trader(TRADER_FUNDS).await;
+    let owner = env.new_trader(TRADER_FUNDS).await;
+    let wallet = owner.pubkey();
+    let bot = env.new_trader(0).await;
+    let supply = 1_000_000;
+    let (token, hype_mint, hype_program_acc, _) =
+        env.create_token(&creator, 0, "doge", supply).await.unwrap();
+    let (other, other_mint, _, _) = env.create_token(&owner, 0, "shib", supply).await.unwrap();
+    let amount = 1_000_000;
+    let buy = |env: &TestEnv| {
+        delegated(
+            env.mint_ix(
+                &wallet,
+                0,
+                "doge",
+                &hype_mint,
+                &hype_program_acc,
+                amount,
+                0,
+                &[],
+            ),
+            &wallet,
+            &bot.pubkey(),
+        )
+    };
+
+    // Unless the wallet approved the token the delegate could not sell what it buys
+    let ix = env.set_delegate_ix(&wallet, &bot.pubkey(), 0, u64::MAX, 0, 0, &[]);
+    env.process(&[ix], &[&owner]).await.unwrap();
+    assert_hype_error(
+        env.process(&[buy(&env)], &[&bot]).await,
+        HypeError::DelegateNotApproved,
+    );
+
+    // Approving it opens the wallet's hype account ahead of the first buy
+    let hype_ata = env.hype_ata(&wallet, &hype_mint);
+    let ix = env.set_delegate_ix(&wallet, &bot.pubkey(), 0, u64::MAX, 0, 0, &[hype_mint]);
+    env.process(&[ix], &[&owner]).await.unwrap();
+    assert_eq!(env.balance(&hype_ata).await, 0);
+    env.process(&[buy(&env)], &[&bot]).await.unwrap();
+    assert_eq!(env.balance(&hype_ata).await, amount);
+    let sell = delegated(
+        env.burn_ix(
+            &wallet,
+            &token,
+            &hype_mint,
+            &hype_program_acc,
+            amount,
+            0,
+            &[],
+        ),
+        &wallet,
+        &bot.pubkey(),
+    );
+    env.process(&[sell], &[&bot]).await.unwrap();
+    assert_eq!(env.balance(&hype_ata).await, 0);
+    env.check_invariants(&[(token, hype_mint), (other, other_mint)])
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn delegate_cannot_act_beyond_trading() {
+    let mut env = TestEnv::setup(spl_token::id()).await;
+    let owner = env.new_trader(TRADER_FUNDS).await;
+    let wallet = owner.pubkey();
+    let bot = env.new_trader(0).await;
+    let stranger = env.new_trader(0).await;
+    let (token, hype_mint, hype_program_acc, _) = env
+        .create_token(&owner, 0, "doge", 1_000_000)
+        .await
+        .unwrap();
+    let ix = env.set_delegate_ix(&wallet, &bot.pubkey(), 0, u64::MAX, 0, 0, &[]);
+    env.process(&[ix], &[&owner]).await.unwrap();
+
+    // Another key cannot use the delegate account
+    let mut ix = delegated(
+        env.burn_ix(
+            &wallet,
+            &token,
+            &hype_mint,
+            &hype_program_acc,
+            1_000,
+            0,
+            &[],
+        ),
+        &wallet,
+        &bot.pubkey(),
+    );
+    ix.accounts[17].pubkey = stranger.pubkey();
+    assert_hype_error(
+        env.process(&[ix], &[&stranger]).await,
+        HypeError::InvalidDelegateAccount,
+    );
+
+    // nor can the delegate give the wallet's funds away
+    let ix = delegated(
+        env.mint_for_ix(
+            &wallet,
+            &bot.pubkey(),
+            &token,
+            &hype_mint,
+            &hype_program_acc,
+            1_000,
+            0,
+        ),
+        &wallet,
+        &bot.pubkey(),
+    );
+    assert!(env.process(&[ix], &[&bot]).await.is_err());
+
+    // and an expired delegate trades no more
+    let now = env.clock().await.unix_timestamp as u32;
+    let ix = env.set_delegate_ix(&wallet, &bot.pubkey(), 0, u64::MAX, 0, now, &[]);
+    assert_hype_error(
+        env.process(&[ix], &[&owner]).await,
+        HypeError::DelegateExpired,
+    );
+}
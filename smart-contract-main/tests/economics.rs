@@ -0,0 +1,462 @@
+use hype_watch::program::*;
+use hype_watch::state::{
+    DelegateAccount, FeeTier, PositionAccount, PriceHistoryAccount, TokenAccount,
+};
+use proptest::prelude::*;
+
+// Property tests of the pure curve module: random root parameters, referral
+// states and mint/burn sequences are replayed against a model of the vault.
+
+const USERS: usize = 3;
+
+fn curve_params() -> impl Strategy<Value = CurveParams> {
+    (
+        1e-9f64..1e3,
+        1_000u64..1_000_000_000_000_000_000,
+        0.0f64..0.2,
+        0.0f64..10.0,
+        0.0f64..=1.0,
+        0u32..=9,
+    )
+        .prop_map(
+            |(init_price, max_supply, fee_rate, min_fee, fee_ratio, decimals)| CurveParams {
+                init_price,
+                max_supply,
+                // trade_fees scales the rate by the decimals factor as well
+                fee_rate: fee_rate / 10f64.powi(decimals as i32),
+                min_fee,
+                fee_ratio,
+                base_crncy_decs_factor: 10u32.pow(decimals),
+            },
+        )
+}
+
+fn referral() -> impl Strategy<Value = Option<(f64, f64)>> {
+    prop::option::of((0.0f64..=1.0, 0.0f64..=1.0))
+}
+
+#[derive(Clone, Debug)]
+enum Op {
+    Mint { user: usize, amount: u64 },
+    // Burns this share of the user's tokens
+    Burn { user: usize, share: f64 },
+}
+
+fn op(max_supply: u64) -> impl Strategy<Value = Op> {
+    prop_oneof![
+        (0..USERS, 1..=max_supply).prop_map(|(user, amount)| Op::Mint { user, amount }),
+        (0..USERS, 1..=max_supply / 1000 + 1).prop_map(|(user, amount)| Op::Mint { user, amount }),
+        (0..USERS, 0.0f64..=1.0).prop_map(|(user, share)| Op::Burn { user, share }),
+    ]
+}
+
+/// Root parameters, each user's referral state and the trades.
+type Scenario = (CurveParams, Vec<Option<(f64, f64)>>, Vec<Op>);
+
+fn scenario() -> impl Strategy<Value = Scenario> {
+    curve_params().prop_flat_map(|params| {
+        (
+            Just(params),
+            prop::collection::vec(referral(), USERS),
+            prop::collection::vec(op(params.max_supply), 1..40),
+        )
+    })
+}
+
+#[derive(Default)]
+struct User {
+    tokens: u64,
+    paid_in: u128,
+    paid_out: u128,
+}
+
+/// Model of one token: the vault, the root counters and the traders.
+struct Market {
+    params: CurveParams,
+    referrals: Vec<Option<(f64, f64)>>,
+    supply: u64,
+    tvl: u64,
+    fees: u128,
+    vault: u128,
+    users: Vec<User>,
+}
+
+impl Market {
+    fn new(params: CurveParams, referrals: Vec<Option<(f64, f64)>>) -> Self {
+        Market {
+            params,
+            referrals,
+            supply: 0,
+            tvl: 0,
+            fees: 0,
+            vault: 0,
+            users: (0..USERS).map(|_| User::default()).collect(),
+        }
+    }
+
+    fn mint(&mut self, user: usize, amount: u64) -> Result<(), TestCaseError> {
+        let quote = match quote_mint(&self.params, self.supply, amount) {
+            Ok(quote) => quote,
+            Err(_) => return Ok(()),
+        };
+        // mint_tokens rejects a trade whose fees exceed its size
+        if quote.check_fees().is_err() {
+            return Ok(());
+        }
+        prop_assert!(quote.fees <= quote.base_crncy_amount);
+        let split = split_fees(quote.fees, self.params.fee_ratio, self.referrals[user]);
+        prop_assert!(split.total() <= quote.fees);
+        let user = &mut self.users[user];
+        user.tokens += amount;
+        user.paid_in += quote.base_crncy_amount as u128 + split.total() as u128;
+        // Referral fees stay in the vault until the referrer claims them
+        self.vault += quote.base_crncy_amount as u128 + split.total() as u128;
+        self.fees += split.total() as u128;
+        self.tvl += quote.base_crncy_amount;
+        self.supply += amount;
+        Ok(())
+    }
+
+    fn burn(&mut self, user: usize, amount: u64) -> Result<(), TestCaseError> {
+        if amount == 0 {
+            return Ok(());
+        }
+        let quote = quote_burn(&self.params, self.supply, amount)
+            .map_err(|e| TestCaseError::fail(format!("burn of held tokens failed: {}", e)))?;
+        prop_assert!(quote.base_crncy_amount <= self.tvl, "InvalidTVL");
+        // burn_tokens rejects a sale whose fees exceed its cash out
+        if quote.check_fees().is_err() {
+            return Ok(());
+        }
+        prop_assert!(quote.fees <= quote.base_crncy_amount);
+        let split = split_fees(quote.fees, self.params.fee_ratio, self.referrals[user]);
+        prop_assert!(split.total() <= quote.fees);
+        let payment = quote.base_crncy_amount - split.total();
+        let user = &mut self.users[user];
+        user.tokens -= amount;
+        user.paid_out += payment as u128;
+        self.vault -= payment as u128;
+        self.fees += split.total() as u128;
+        self.tvl -= quote.base_crncy_amount;
+        self.supply -= amount;
+        Ok(())
+    }
+
+    fn apply(&mut self, op: &Op) -> Result<(), TestCaseError> {
+        match *op {
+            Op::Mint { user, amount } => self.mint(user, amount)?,
+            Op::Burn { user, share } => {
+                let amount = (self.users[user].tokens as f64 * share) as u64;
+                self.burn(user, amount.min(self.users[user].tokens))?
+            }
+        }
+        self.check()
+    }
+
+    fn check(&self) -> Result<(), TestCaseError> {
+        prop_assert_eq!(self.vault, self.tvl as u128 + self.fees);
+        prop_assert_eq!(self.tvl, reserve_units(&self.params, self.supply).unwrap());
+        let tokens: u64 = self.users.iter().map(|u| u.tokens).sum();
+        prop_assert_eq!(tokens, self.supply);
+        Ok(())
+    }
+}
+
+proptest! {
+    #[test]
+    fn no_sequence_drains_the_vault((params, referrals, ops) in scenario()) {
+        let mut market = Market::new(params, referrals);
+        for op in &ops {
+            market.apply(op)?;
+        }
+        for user in 0..USERS {
+            let tokens = market.users[user].tokens;
+            market.burn(user, tokens)?;
+            market.check()?;
+        }
+        // A sale whose fees exceed its cash out is rejected, leaving its tokens unsold
+        let unsold: u64 = market.users.iter().map(|u| u.tokens).sum();
+        if unsold == 0 {
+            prop_assert_eq!(market.tvl, 0);
+        }
+        let paid_in: u128 = market.users.iter().map(|u| u.paid_in).sum();
+        let paid_out: u128 = market.users.iter().map(|u| u.paid_out).sum();
+        prop_assert!(paid_out <= paid_in);
+    }
+
+    #[test]
+    fn single_trader_never_profits(
+        (params, referrals, ops) in scenario(),
+    ) {
+        let ops: Vec<Op> = ops
+            .into_iter()
+            .map(|op| match op {
+                Op::Mint { amount, .. } => Op::Mint { user: 0, amount },
+                Op::Burn { share, .. } => Op::Burn { user: 0, share },
+            })
+            .collect();
+        let mut market = Market::new(params, referrals);
+        for op in &ops {
+            market.apply(op)?;
+        }
+        let tokens = market.users[0].tokens;
+        market.burn(0, tokens)?;
+        prop_assert!(market.users[0].paid_out <= market.users[0].paid_in);
+    }
+
+    #[test]
+    fn reserve_is_monotonic_near_max_supply(
+        params in curve_params(),
+        distance in 1u64..10_000,
+        amount in 1u64..10_000,
+    ) {
+        let supply = params.max_supply.saturating_sub(distance);
+        if let (Ok(low), Ok(high)) = (
+            reserve_units(&params, supply),
+            reserve_units(&params, supply.saturating_add(amount)),
+        ) {
+            prop_assert!(low <= high);
+        }
+        let min_fee = (params.min_fee * params.base_crncy_decs_factor as f64) as u64;
+        if let Ok(quote) = quote_mint(&params, supply, amount) {
+            prop_assert!(quote.fees >= min_fee);
+            prop_assert_eq!(quote.check_fees().is_ok(), quote.fees <= quote.base_crncy_amount);
+            let back = quote_burn(&params, supply + amount, amount).unwrap();
+            prop_assert_eq!(back.base_crncy_amount, quote.base_crncy_amount);
+        }
+        let burn = amount.min(supply);
+        if burn > 0 {
+            if let Ok(quote) = quote_burn(&params, supply, burn) {
+                prop_assert!(quote.fees >= min_fee);
+                prop_assert_eq!(quote.check_fees().is_ok(), quote.fees <= quote.base_crncy_amount);
+            }
+        }
+    }
+
+    #[test]
+    fn fee_split_never_exceeds_fees(
+        fees in any::<u64>(),
+        fee_ratio in 0.0f64..=1.0,
+        referral in referral(),
+    ) {
+        let split = split_fees(fees, fee_ratio, referral);
+        prop_assert!(split.holder_fees as u128 + split.operator_fees as u128 + split.ref_fees as u128 <= fees as u128);
+        if referral.is_none() {
+            prop_assert_eq!(split.total(), fees);
+        }
+    }
+
+    #[test]
+    fn upline_fees_come_out_of_operator_fees(
+        fees in any::<u64>(),
+        fee_ratio in 0.0f64..=1.0,
+        referral in (0.0f64..=1.0, 0.0f64..=1.0),
+        upline_ratios in prop::array::uniform2(-1.0f64..=2.0),
+    ) {
+        let split = split_fees(fees, fee_ratio, Some(referral));
+        let with_uplines = split.with_upline_fees(&upline_ratios);
+        prop_assert_eq!(with_uplines.total(), split.total());
+        prop_assert_eq!(with_uplines.holder_fees, split.holder_fees);
+        prop_assert_eq!(with_uplines.ref_fees, split.ref_fees);
+        prop_assert!(with_uplines.operator_fees <= split.operator_fees);
+    }
+
+    #[test]
+    fn creator_fees_come_out_of_operator_fees(
+        fees in any::<u64>(),
+        fee_ratio in 0.0f64..=1.0,
+        referral in referral(),
+        creator_fee_ratio in 0.0f64..=1.0,
+    ) {
+        let split = split_fees(fees, fee_ratio, referral);
+        let with_creator = split.with_creator_fees(fees, creator_fee_ratio);
+        prop_assert_eq!(with_creator.total(), split.total());
+        prop_assert_eq!(with_creator.holder_fees, split.holder_fees);
+        prop_assert_eq!(with_creator.ref_fees, split.ref_fees);
+        prop_assert!(with_creator.creator_fees <= (fees as f64 * creator_fee_ratio) as u64);
+    }
+
+    #[test]
+    fn closed_position_realizes_proceeds_minus_cost(
+        buys in prop::collection::vec((1u64..1_000_000_000, 0u64..1_000_000_000_000), 1..10),
+        sells in prop::collection::vec((1u64..1_000_000_000, 0u64..1_000_000_000_000), 0..10),
+    ) {
+        let mut position: PositionAccount = unsafe { std::mem::zeroed() };
+        let mut cost = 0i128;
+        for (amount, paid) in buys.iter() {
+            position.add_buy(*amount, *paid, 0, 0).unwrap();
+            cost += *paid as i128;
+        }
+        let mut proceeds = 0i128;
+        for (amount, received) in sells.iter() {
+            let quantity = position.quantity;
+            let basis = position.cost_basis;
+            position.add_sell((*amount).min(quantity).max(1), *received, 0, 0).unwrap();
+            prop_assert!(position.cost_basis <= basis);
+            if quantity > 0 {
+                proceeds += *received as i128;
+            }
+        }
+        let rest = position.quantity;
+        position.add_sell(rest.max(1), 0, 0, 0).unwrap();
+        prop_assert_eq!(position.quantity, 0);
+        prop_assert_eq!(position.cost_basis, 0);
+        prop_assert_eq!(position.realized_pnl as i128, proceeds - cost);
+    }
+
+    #[test]
+    fn fee_tier_grows_with_volume(
+        mut min_volumes in prop::collection::vec(any::<u64>(), 0..=MAX_FEE_TIERS),
+        volume in any::<u64>(),
+        more in any::<u64>(),
+    ) {
+        min_volumes.sort_unstable();
+        let tiers: Vec<FeeTier> = min_volumes
+            .iter()
+            .map(|min_volume| FeeTier { min_volume: *min_volume, fee_rate: 0.0 })
+            .collect();
+        let tier = fee_tier(&tiers, volume);
+        prop_assert!(tier as usize <= tiers.len());
+        prop_assert!(fee_tier(&tiers, volume.saturating_add(more)) >= tier);
+        if tier > 0 {
+            prop_assert!(volume >= tiers[tier as usize - 1].min_volume);
+        }
+    }
+
+    #[test]
+    fn spot_price_rises_with_supply(params in curve_params(), supply in any::<u64>(), more in any::<u64>()) {
+        let supply = supply % params.max_supply;
+        let next = supply.saturating_add(more % (params.max_supply - supply));
+        let price = spot_price(&params, supply).unwrap();
+        prop_assert!(price >= params.init_price);
+        prop_assert!(spot_price(&params, next).unwrap() >= price);
+        prop_assert!(spot_price(&params, params.max_supply).is_err());
+    }
+
+    #[test]
+    fn funds_buy_at_most_their_worth(params in curve_params(), supply in any::<u64>(), funds in 0u64..1_000_000_000_000) {
+        let supply = supply % params.max_supply;
+        if let Ok(amount) = mint_amount_for_funds(&params, supply, funds) {
+            prop_assert!(supply + amount < params.max_supply);
+            if amount > 0 {
+                let cost = quote_mint(&params, supply, amount).unwrap().base_crncy_amount;
+                prop_assert!(cost <= funds);
+            }
+        }
+    }
+
+    #[test]
+    fn buys_stop_at_the_graduation_reserve(params in curve_params(), supply in any::<u64>(), graduation_reserve in 1u64..1_000_000_000_000) {
+        let supply = supply % params.max_supply;
+        if let Ok(cap) = graduation_cap(&params, supply, graduation_reserve) {
+            let reserve = reserve_units(&params, supply).unwrap();
+            prop_assert!(supply + cap < params.max_supply);
+            if reserve >= graduation_reserve {
+                prop_assert_eq!(cap, 0);
+            } else if cap > 0 {
+                prop_assert!(reserve_units(&params, supply + cap - 1).unwrap() <= graduation_reserve);
+            }
+        }
+    }
+
+    #[test]
+    fn delegate_spends_within_each_period(
+        spend_limit in 1u64..1_000_000,
+        period in 1u32..86_400,
+        spends in prop::collection::vec((0u32..20_000, 0u64..500_000), 1..100),
+    ) {
+        let mut delegate: DelegateAccount = unsafe { std::mem::zeroed() };
+        delegate.spend_limit = spend_limit;
+        delegate.period = period;
+        delegate.period_start = 1_700_000_000;
+        let mut time = delegate.period_start;
+        for (delay, amount) in spends.iter() {
+            time += delay;
+            match delegate.spend(*amount, time) {
+                Ok(()) => prop_assert!(delegate.spent <= spend_limit),
+                Err(_) => prop_assert!(delegate.spent + amount > spend_limit),
+            }
+            prop_assert!(delegate.period_start <= time);
+            prop_assert!(time - delegate.period_start < period);
+        }
+    }
+
+    #[test]
+    fn candles_keep_the_latest_intervals(
+        interval in 1u32..3600,
+        trades in prop::collection::vec((0u32..7200, 0.0f64..1e6, 0u64..1_000_000), 1..200),
+    ) {
+        let mut history: Box<PriceHistoryAccount> = Box::new(unsafe { std::mem::zeroed() });
+        history.interval = interval;
+        let mut time = 1_700_000_000u32;
+        let mut price = 1.0;
+        let mut starts = Vec::new();
+        for (delay, next_price, volume) in trades.iter() {
+            time += delay;
+            history.record(price, *next_price, *volume, *volume, 0, time);
+            price = *next_price;
+            let start = time - time % interval;
+            if starts.last() != Some(&start) {
+                starts.push(start);
+            }
+        }
+        let count = starts.len().min(PRICE_HISTORY_CANDLES);
+        prop_assert_eq!(history.candles_count as usize, count);
+        for (i, start) in starts.iter().rev().take(count).enumerate() {
+            let index = (history.head as usize + PRICE_HISTORY_CANDLES - i) % PRICE_HISTORY_CANDLES;
+            let candle = history.candles[index];
+            prop_assert_eq!(candle.start_time, *start);
+            prop_assert!(candle.trades_count > 0);
+            prop_assert!(candle.low <= candle.open.min(candle.close));
+            prop_assert!(candle.high >= candle.open.max(candle.close));
+        }
+        prop_assert_eq!(history.candles[history.head as usize].close, price);
+    }
+
+    #[test]
+    fn twap_stays_within_traded_prices(
+        trades in prop::collection::vec((0u32..2 * TWAP_OBSERVATION_PERIOD, 1e-3f64..1e6), 1..50),
+        window in 0u32..4 * TWAP_OBSERVATION_PERIOD,
+        wait in 0u32..TWAP_OBSERVATION_PERIOD,
+    ) {
+        let mut token: Box<TokenAccount> = Box::new(unsafe { std::mem::zeroed() });
+        let mut time = 1_700_000_000u32;
+        let mut low = f64::MAX;
+        let mut high = 0.0f64;
+        for (delay, price) in trades.iter() {
+            time += delay;
+            token.update_twap(*price, time);
+            low = low.min(*price);
+            high = high.max(*price);
+        }
+        prop_assert!(token.observations_count as usize <= TWAP_OBSERVATIONS);
+        if let Ok((price, elapsed)) = token.twap(window, time + wait) {
+            prop_assert!(elapsed >= window.max(1));
+            prop_assert!(price >= low * (1.0 - 1e-9) && price <= high * (1.0 + 1e-9));
+        }
+    }
+
+    #[test]
+    fn pool_swaps_never_shrink_the_product(
+        base_crncy_reserve in 1u64..1_000_000_000_000_000,
+        token_reserve in 1u64..1_000_000_000_000_000,
+        swaps in prop::collection::vec((any::<bool>(), 1u64..1_000_000_000_000), 1..50),
+    ) {
+        let (mut base, mut tokens) = (base_crncy_reserve, token_reserve);
+        for (buy, amount) in swaps {
+            let product = base as u128 * tokens as u128;
+            if buy {
+                let out = swap_amount_out(base, tokens, amount)?;
+                prop_assert!(out < tokens);
+                base += amount;
+                tokens -= out;
+            } else {
+                let out = swap_amount_out(tokens, base, amount)?;
+                prop_assert!(out < base);
+                tokens += amount;
+                base -= out;
+            }
+            prop_assert!(base as u128 * tokens as u128 >= product);
+        }
+    }
+}
@@ -0,0 +1,115 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use common::*;
+use hype_watch::program::error::HypeError;
+use solana_sdk::signature::Signer;
+
+const TRADER_FUNDS: u64 = 1_000_000_000_000_000;
+
+#[tokio::test]
+async fn fee_tiers_follow_client_volume() {
+    let mut env = TestEnv::setup(spl_token::id()).await;
+    let params = env.params;
+    let admin = env.operator.insecure_clone();
+    let validator = env.validator.insecure_clone();
+    let trader = env.new_trader(TRADER_FUNDS).await;
+    let amount = 1_000_000;
+
+    let (token, hype_mint, hype_program_acc, logs) =
+        env.create_token(&trader, 0, "doge", amount).await.unwrap();
+    assert_eq!(field_u32(&events_with_tag(&logs, 4)[0], 18), 0);
+    let volume = env
+        .client_account(&trader.pubkey())
+        .await
+        .all_time_base_crncy_volume;
+    assert!(volume > 0);
+
+    // Tiers must raise the volume and may only lower the fee rate
+    let tiers = [(volume, 0.000_000_000_5), (volume * 1_000, 0.000_000_000_2)];
+    assert_hype_error(
+        env.set_fee_tiers(&validator, &tiers).await,
+        HypeError::InvalidAdmin,
+    );
+    assert_hype_error(
+        env.set_fee_tiers(&admin, &[tiers[1], tiers[0]]).await,
+        HypeError::InvalidFeeTiers,
+    );
+    assert_hype_error(
+        env.set_fee_tiers(&admin, &[(volume, params.fee_rate * 2.0)])
+            .await,
+        HypeError::InvalidFeeTiers,
+    );
+    assert_hype_error(
+        env.set_fee_tiers(
+            &admin,
+            &[(volume, 0.000_000_000_2), (volume * 1_000, 0.000_000_000_5)],
+        )
+        .await,
+        HypeError::InvalidFeeTiers,
+    );
+    env.set_fee_tiers(&admin, &tiers).await.unwrap();
+    let root = env.root_account().await;
+    assert_eq!(root.fee_tiers_count, 2);
+    assert_eq!(root.fee_tiers[1].min_volume, volume * 1_000);
+
+    // The client's volume now reaches the first tier
+    let tier_params = RootParams {
+        fee_rate: 0.000_000_000_5,
+        ..params
+    };
+    let trader_base = env.base_ata(&trader.pubkey());
+    let balance = env.balance(&trader_base).await;
+    let quote = expected_mint(&tier_params, amount, amount, false);
+    let ix = env.mint_ix(
+        &trader.pubkey(),
+        0,
+        "doge",
+        &hype_mint,
+        &hype_program_acc,
+        amount,
+        0,
+        &[],
+    );
+    let logs = env.process(&[ix], &[&trader]).await.unwrap();
+    assert_eq!(field_u32(&events_with_tag(&logs, 4)[0], 18), 1);
+    assert_eq!(
+        env.balance(&trader_base).await,
+        balance - quote.base_crncy_amount - quote.fees
+    );
+
+    let balance = env.balance(&trader_base).await;
+    let quote = expected_burn(&tier_params, 2 * amount, amount);
+    let ix = env.burn_ix(
+        &trader.pubkey(),
+        &token,
+        &hype_mint,
+        &hype_program_acc,
+        amount,
+        0,
+        &[],
+    );
+    let logs = env.process(&[ix], &[&trader]).await.unwrap();
+    assert_eq!(field_u32(&events_with_tag(&logs, 5)[0], 18), 1);
+    assert_eq!(
+        env.balance(&trader_base).await,
+        balance + quote.base_crncy_amount - quote.fees
+    );
+    env.check_invariants(&[(token, hype_mint)]).await.unwrap();
+
+    // An empty table restores the flat fee rate
+    env.set_fee_tiers(&admin, &[]).await.unwrap();
+    let ix = env.mint_ix(
+        &trader.pubkey(),
+        0,
+        "doge",
+        &hype_mint,
+        &hype_program_acc,
+        amount,
+        0,
+        &[],
+    );
+    let logs = env.process(&[ix], &[&trader]).await.unwrap();
+    assert_eq!(field_u32(&events_with_tag(&logs, 4)[0], 18), 0);
+}
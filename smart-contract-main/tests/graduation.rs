@@ -0,0 +1,216 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use common::*;
+use hype_watch::{program::error::HypeError, program::*};
+use solana_sdk::{rent::Rent, signature::Signer};
+
+const TRADER_FUNDS: u64 = 1_000_000_000_000_000;
+
+#[tokio::test]
+async fn graduated_token_trades_in_its_pool() {
+    let mut env = TestEnv::new(spl_token::id()).await;
+    env.params.graduation_reserve = 3_000_000;
+    env.initialize_holder().await.unwrap();
+    env.add_operator(10).await.unwrap();
+    env.initialize_root().await.unwrap();
+    env.add_network("ethereum", "abcdefghijklmnopqrstuvwxyz0123456789", 24)
+        .await
+        .unwrap();
+    let params = env.params;
+    let creator = env.new_trader(TRADER_FUNDS).await;
+    let trader = env.new_trader(TRADER_FUNDS).await;
+    let wallet = trader.pubkey();
+    let (token, hype_mint, hype_program_acc, _) = env
+        .create_token(&creator, 0, "doge", 1_000_000)
+        .await
+        .unwrap();
+    let mint = |env: &TestEnv, amount| {
+        env.mint_ix(
+            &wallet,
+            0,
+            "doge",
+            &hype_mint,
+            &hype_program_acc,
+            amount,
+            0,
+            &[],
+        )
+    };
+
+    // Below the graduation reserve the curve keeps trading
+    assert_hype_error(
+        env.graduate(&token, &hype_mint, &hype_program_acc).await,
+        HypeError::GraduationReserveNotReached,
+    );
+    // A buy past the graduation reserve fails instead of filling partly
+    let ix = mint(&env, 2_500_000);
+    assert_hype_error(
+        env.process(&[ix], &[&trader]).await,
+        HypeError::GraduationReserveExceeded,
+    );
+    let cap = graduation_cap(&params.curve(), 1_000_000, params.graduation_reserve).unwrap();
+    assert_eq!(cap, 2_000_000);
+    let ix = mint(&env, cap);
+    env.process(&[ix], &[&trader]).await.unwrap();
+    let supply = 3_000_000;
+    assert_eq!(env.token_account(&token).await.supply, supply);
+    assert_eq!(
+        env.balance(&env.hype_ata(&wallet, &hype_mint)).await,
+        2_000_000
+    );
+    assert_eq!(
+        reserve_units(&params.curve(), supply).unwrap(),
+        env.params.graduation_reserve
+    );
+    let ix = mint(&env, 1);
+    assert_hype_error(
+        env.process(&[ix], &[&trader]).await,
+        HypeError::TokenGraduated,
+    );
+
+    // Lamports sent to the pool address ahead of graduation do not block it
+    env.fund(&pool_address(VERSION, &hype_mint), 1).await;
+    let tvl = env.root_account().await.tvl;
+    let (reserve, tokens) = graduation_amounts(&params.curve(), supply).unwrap();
+    env.graduate(&token, &hype_mint, &hype_program_acc)
+        .await
+        .unwrap();
+    let pool_acc = env
+        .account(&pool_address(VERSION, &hype_mint))
+        .await
+        .unwrap();
+    assert_eq!(pool_acc.owner, program_id());
+    assert_eq!(
+        pool_acc.lamports,
+        Rent::default().minimum_balance(POOL_ACCOUNT_SIZE)
+    );
+    let pool = env.pool_account(&hype_mint).await;
+    assert_eq!(
+        (pool.base_crncy_reserve, pool.token_reserve),
+        (reserve, tokens)
+    );
+    assert_eq!(pool.token, token);
+    assert_eq!(env.token_account(&token).await.supply, supply + tokens);
+    assert_eq!(env.balance(&hype_program_acc).await, tokens);
+    let root = env.root_account().await;
+    assert_eq!(root.tvl, tvl - reserve);
+    assert_eq!(root.pool_reserves, reserve);
+    env.check_invariants(&[(token, hype_mint)]).await.unwrap();
+    assert_hype_error(
+        env.graduate(&token, &hype_mint, &hype_program_acc).await,
+        HypeError::TokenGraduated,
+    );
+    let ix = env.burn_ix(
+        &wallet,
+        &token,
+        &hype_mint,
+        &hype_program_acc,
+        1_000,
+        0,
+        &[],
+    );
+    assert_hype_error(
+        env.process(&[ix], &[&trader]).await,
+        HypeError::TokenGraduated,
+    );
+
+    // Buys pay the pool fee out of the amount paid in
+    let base = env.base_ata(&wallet);
+    let hype = env.hype_ata(&wallet, &hype_mint);
+    let amount = 100_000;
+    let base_crncy_in = amount - pool_fees(params.pool_fee_rate, amount);
+    let tokens_out = swap_amount_out(reserve, tokens, base_crncy_in).unwrap();
+    let ix = env.swap_ix(
+        &wallet,
+        &token,
+        &hype_mint,
+        &hype_program_acc,
+        BATCH_SIDE_BUY,
+        amount,
+        tokens_out + 1,
+    );
+    assert_hype_error(
+        env.process(&[ix], &[&trader]).await,
+        HypeError::MaxTradeCostExceeded,
+    );
+    let base_before = env.balance(&base).await;
+    let hype_before = env.balance(&hype).await;
+    let ix = env.swap_ix(
+        &wallet,
+        &token,
+        &hype_mint,
+        &hype_program_acc,
+        BATCH_SIDE_BUY,
+        amount,
+        tokens_out,
+    );
+    env.process(&[ix], &[&trader]).await.unwrap();
+    assert_eq!(base_before - env.balance(&base).await, amount);
+    assert_eq!(env.balance(&hype).await - hype_before, tokens_out);
+    let pool = env.pool_account(&hype_mint).await;
+    assert_eq!(pool.base_crncy_reserve, reserve + base_crncy_in);
+    assert_eq!(pool.token_reserve, tokens - tokens_out);
+
+    // Sells pay it out of the cash out
+    let base_crncy_out =
+        swap_amount_out(pool.token_reserve, pool.base_crncy_reserve, tokens_out).unwrap();
+    let payout = base_crncy_out - pool_fees(params.pool_fee_rate, base_crncy_out);
+    let ix = env.swap_ix(
+        &wallet,
+        &token,
+        &hype_mint,
+        &hype_program_acc,
+        BATCH_SIDE_SELL,
+        tokens_out,
+        payout,
+    );
+    env.process(&[ix], &[&trader]).await.unwrap();
+    assert_eq!(env.balance(&hype).await, hype_before);
+    assert_eq!(env.balance(&base).await, base_before - amount + payout);
+    let after = env.pool_account(&hype_mint).await;
+    assert_eq!(after.swaps_count, 2);
+    assert!(
+        after.base_crncy_reserve as u128 * after.token_reserve as u128
+            >= reserve as u128 * tokens as u128
+    );
+    assert_eq!(
+        env.root_account().await.pool_reserves,
+        after.base_crncy_reserve
+    );
+    env.check_invariants(&[(token, hype_mint)]).await.unwrap();
+}
+
+#[tokio::test]
+async fn switch_into_graduation_fails() {
+    let mut env = TestEnv::new(spl_token::id()).await;
+    env.params.graduation_reserve = 3_000_000;
+    env.initialize_holder().await.unwrap();
+    env.add_operator(10).await.unwrap();
+    env.initialize_root().await.unwrap();
+    env.add_network("ethereum", "abcdefghijklmnopqrstuvwxyz0123456789", 24)
+        .await
+        .unwrap();
+    let trader = env.new_trader(TRADER_FUNDS).await;
+    let wallet = trader.pubkey();
+    let (doge, doge_mint, doge_program_acc, _) = env
+        .create_token(&trader, 0, "doge", 2_500_000)
+        .await
+        .unwrap();
+    let (shib, shib_mint, shib_program_acc, _) = env
+        .create_token(&trader, 0, "shib", 1_000_000)
+        .await
+        .unwrap();
+    let from = (doge, doge_mint, doge_program_acc);
+    let to = (shib, shib_mint, shib_program_acc);
+
+    // A switch spends all it cashes out, so it can not stop at the graduation reserve
+    let ix = env.switch_ix(&wallet, from, to, 2_500_000, 0, &[]);
+    assert_hype_error(
+        env.process(&[ix], &[&trader]).await,
+        HypeError::GraduationReserveExceeded,
+    );
+    let ix = env.switch_ix(&wallet, from, to, 1_000_000, 0, &[]);
+    env.process(&[ix], &[&trader]).await.unwrap();
+}
@@ -0,0 +1,456 @@
+use hype_watch::inspector::inspect_account;
+use hype_watch::program::*;
+use hype_watch::state::*;
+use solana_program::pubkey::Pubkey;
+
+// Host tests of the account inspector. Accounts are written field by field at the
+// offsets of `state::data_structure`, as the program lays them out on chain.
+
+struct Account(Vec<u8>);
+
+impl Account {
+    fn new(tag: u32, size: usize) -> Self {
+        Account(vec![0; size]).u32(0, tag).u32(4, 1)
+    }
+
+    fn put(mut self, offset: usize, bytes: &[u8]) -> Self {
+        self.0[offset..offset + bytes.len()].copy_from_slice(bytes);
+        self
+    }
+
+    fn u32(self, offset: usize, value: u32) -> Self {
+        self.put(offset, &value.to_le_bytes())
+    }
+
+    fn u64(self, offset: usize, value: u64) -> Self {
+        self.put(offset, &value.to_le_bytes())
+    }
+
+    fn i64(self, offset: usize, value: i64) -> Self {
+        self.put(offset, &value.to_le_bytes())
+    }
+
+    fn f64(self, offset: usize, value: f64) -> Self {
+        self.put(offset, &value.to_le_bytes())
+    }
+
+    fn pubkey(self, offset: usize, value: &Pubkey) -> Self {
+        self.put(offset, value.as_ref())
+    }
+
+    fn str(self, offset: usize, value: &str) -> Self {
+        self.put(offset, value.as_bytes())
+    }
+}
+
+fn inspect(data: &[u8]) -> String {
+    inspect_account(data).unwrap()
+}
+
+fn assert_fields(json: &str, fields: &[&str]) {
+    for field in fields {
+        assert!(json.contains(field), "{} not in {}", field, json);
+    }
+}
+
+fn assert_no_warnings(json: &str) {
+    assert!(json.ends_with("\"warnings\":[]}"), "{}", json);
+}
+
+fn assert_warning(json: &str, warning: &str) {
+    let warnings = &json[json.find("\"warnings\":").unwrap()..];
+    assert!(
+        warnings.contains(warning),
+        "{} not in {}",
+        warning,
+        warnings
+    );
+}
+
+fn root(networks_count: u32) -> Account {
+    use root_account_offsets::*;
+    let size = ROOT_ACCOUNT_SIZE + networks_count as usize * NETWORK_RECORD_SIZE;
+    let mut root = Account::new(ROOT_TAG, size)
+        .pubkey(ADMIN, &Pubkey::new_from_array([1; 32]))
+        .u32(NETWORKS_COUNT, networks_count)
+        .u32(MAX_NETWORKS_COUNT, 4)
+        .u32(DECIMALS, 6)
+        .u32(BASE_CRNCY_DECS_FACTOR, 1_000_000)
+        .u64(TVL, 123_456)
+        .f64(FEE_RATE, 0.01)
+        .str(OPERATOR_NANE, "hype")
+        .u32(REF_DEPTH, 2)
+        .u32(FEE_TIERS_COUNT, 1)
+        .u64(FEE_TIERS + fee_tier_offsets::MIN_VOLUME, 1_000)
+        .f64(FEE_TIERS + fee_tier_offsets::FEE_RATE, 0.005);
+    for i in 0..networks_count as usize {
+        let offset = NETWORK_RECORDS + i * NETWORK_RECORD_SIZE;
+        root = root
+            .u64(offset + network_record_offsets::MAX_LENGTH, 24)
+            .pubkey(
+                offset + network_record_offsets::VALIDATOR,
+                &Pubkey::new_from_array([2; 32]),
+            )
+            .str(offset + network_record_offsets::DESCRIPTOR, "ethereum")
+            .str(offset + network_record_offsets::MASK, "abcdef0123456789")
+            .f64(offset + network_record_offsets::CREATOR_FEE_CAP, 0.5);
+    }
+    root
+}
+
+fn token() -> Account {
+    use token_account_offsets::*;
+    Account::new(TOKEN_TAG, TOKEN_ACCOUNT_SIZE)
+        .u64(ID, 7)
+        .pubkey(MINT, &Pubkey::new_from_array([3; 32]))
+        .u64(SUPPLY, 1_000_000)
+        .str(ADDRESS, "doge")
+        .u32(NETWORK, 2)
+        .u64(VALIDATION, token_status::VERIFIED as u64)
+        .u32(OBSERVATIONS_COUNT, 1)
+        .u32(PRICE_HISTORY, 1)
+        .u32(OBSERVATIONS + observation_offsets::TIME, 900)
+        .f64(OBSERVATIONS + observation_offsets::PRICE_CUMULATIVE, 1.5)
+}
+
+#[test]
+fn decodes_holder_with_its_operators() {
+    use holder_account_offsets::*;
+    let holder = Account::new(HOLDER_TAG, HOLDER_ACCOUNT_SIZE + OPERATOR_RECORD_SIZE)
+        .pubkey(WALLET, &Pubkey::new_from_array([4; 32]))
+        .u32(OPERATORS_COUNT, 1)
+        .pubkey(
+            OPERATORS_OFFSET + operator_record_offsets::OPERATOR_ADDRESS,
+            &Pubkey::new_from_array([5; 32]),
+        )
+        .u32(OPERATORS_OFFSET + operator_record_offsets::VERSION, 1)
+        .u32(
+            OPERATORS_OFFSET + operator_record_offsets::MAX_NETWORKS_COUNT,
+            10,
+        )
+        .str(
+            OPERATORS_OFFSET + operator_record_offsets::OPERATOR_NAME,
+            "hype",
+        );
+    let json = inspect(&holder.0);
+    assert_fields(
+        &json,
+        &[
+            "\"type\":\"holder\"",
+            "\"operators_count\":1",
+            "\"max_networks_count\":10",
+            "\"operator_name\":\"hype\"",
+            &format!("\"wallet\":\"{}\"", Pubkey::new_from_array([4; 32])),
+        ],
+    );
+    assert_no_warnings(&json);
+
+    // A count the account has no room for
+    let json = inspect(&holder.0[..HOLDER_ACCOUNT_SIZE]);
+    assert_fields(&json, &["\"operators\":[]"]);
+    assert_warning(
+        &json,
+        "operators_count 1 requires 128 bytes, account has 56",
+    );
+}
+
+#[test]
+fn decodes_root_with_its_networks() {
+    let json = inspect(&root(1).0);
+    assert_fields(
+        &json,
+        &[
+            "\"type\":\"root\"",
+            "\"tvl\":123456",
+            "\"fee_rate\":0.01",
+            "\"operator_name\":\"hype\"",
+            "\"fee_tiers\":[{\"min_volume\":1000,\"fee_rate\":0.005}]",
+            "\"max_length\":24",
+            "\"descriptor\":\"ethereum\"",
+            "\"mask\":\"abcdef0123456789\"",
+            "\"creator_fee_cap\":0.5",
+        ],
+    );
+    assert_no_warnings(&json);
+}
+
+#[test]
+fn flags_inconsistent_root() {
+    use root_account_offsets::*;
+    let root = root(1)
+        .u32(BASE_CRNCY_DECS_FACTOR, 1_000)
+        .u32(REF_DEPTH, 0)
+        .u32(FEE_TIERS_COUNT, MAX_FEE_TIERS as u32 + 1)
+        .u32(NETWORKS_COUNT, 2)
+        .f64(INIT_PRICE, f64::NAN)
+        .str(
+            NETWORK_RECORDS + network_record_offsets::DESCRIPTOR + 10,
+            "x",
+        );
+    let json = inspect(&root.0);
+    assert_fields(&json, &["\"init_price\":null"]);
+    assert_warning(
+        &json,
+        "base_crncy_decs_factor 1000 does not match decimals 6",
+    );
+    assert_warning(&json, "ref_depth 0 is outside [1, 3]");
+    assert_warning(&json, "fee_tiers_count 5 exceeds 4");
+    assert_warning(
+        &json,
+        "networks[0].descriptor: non-zero bytes after nul terminator",
+    );
+    assert_warning(
+        &json,
+        &format!(
+            "networks_count 2 requires {} bytes, account has {}",
+            ROOT_ACCOUNT_SIZE + 2 * NETWORK_RECORD_SIZE,
+            ROOT_ACCOUNT_SIZE + NETWORK_RECORD_SIZE
+        ),
+    );
+}
+
+#[test]
+fn rejects_truncated_and_unknown_accounts() {
+    assert!(inspect_account(&root(0).0[..ROOT_ACCOUNT_SIZE - 1]).is_err());
+    let token = token();
+    assert!(inspect_account(&token.0[..TOKEN_ACCOUNT_SIZE - 1]).is_err());
+    assert!(inspect_account(&token.0[..3]).is_err());
+    assert!(inspect_account(&token.u32(0, 99).0).is_err());
+}
+
+#[test]
+fn decodes_token() {
+    let json = inspect(&token().0);
+    assert_fields(
+        &json,
+        &[
+            "\"type\":\"token\"",
+            "\"id\":7",
+            "\"supply\":1000000",
+            "\"address\":\"doge\"",
+            "\"network\":2",
+            "\"status\":\"verified\"",
+            "\"price_history\":1",
+            "\"observations\":[{\"time\":900,\"price_cumulative\":1.5}]",
+        ],
+    );
+    assert_no_warnings(&json);
+
+    // Trailing bytes, an unknown status and a broken address
+    let mut data = token()
+        .u64(token_account_offsets::VALIDATION, 9)
+        .put(token_account_offsets::ADDRESS, &[0xff])
+        .0;
+    data.push(0);
+    let json = inspect(&data);
+    assert_fields(&json, &["\"status\":9", "\"address\":\"\u{fffd}oge\""]);
+    assert_warning(
+        &json,
+        &format!(
+            "token account has {} bytes, expected {}",
+            TOKEN_ACCOUNT_SIZE + 1,
+            TOKEN_ACCOUNT_SIZE
+        ),
+    );
+    assert_warning(&json, "unknown token status 9");
+    assert_warning(&json, "address: not valid utf-8");
+}
+
+#[test]
+fn decodes_client() {
+    use client_account_offsets::*;
+    let client = Account::new(CLIENT_TAG, CLIENT_ACCOUNT_SIZE)
+        .u64(ID, 3)
+        .str(NICKNAME, "Alice")
+        .u64(REF_PAID, 42)
+        .f64(REF_DISCOUNT, 0.1)
+        .f64(REF_RATIO, 0.5);
+    let json = inspect(&client.0);
+    assert_fields(
+        &json,
+        &[
+            "\"type\":\"client\"",
+            "\"id\":3",
+            "\"nickname\":\"Alice\"",
+            "\"ref_paid\":42",
+            "\"ref_discount\":0.1",
+        ],
+    );
+    assert_no_warnings(&json);
+
+    let json = inspect(&client.f64(REF_RATIO, 1.5).0);
+    assert_warning(&json, "ref_discount and ref_ratio must be within [0, 1]");
+}
+
+#[test]
+fn decodes_referrer() {
+    use referrer_account_offsets::*;
+    let referrer = Account::new(REFERRER_TAG, REFERRER_ACCOUNT_SIZE)
+        .u64(FEES, 30)
+        .u64(ALL_TIME_FEES, 100)
+        .u64(ALL_TIME_CLAIMED, 70);
+    let json = inspect(&referrer.0);
+    assert_fields(&json, &["\"type\":\"referrer\"", "\"fees\":30"]);
+    assert_no_warnings(&json);
+
+    let json = inspect(&referrer.u64(ALL_TIME_CLAIMED, 80).0);
+    assert_warning(
+        &json,
+        "fees 30 and all_time_claimed 80 do not add up to all_time_fees 100",
+    );
+}
+
+#[test]
+fn flags_denormalized_names() {
+    let ref_code = Account::new(REF_CODE_TAG, REF_CODE_ACCOUNT_SIZE)
+        .str(ref_code_account_offsets::CODE, "moon");
+    let json = inspect(&ref_code.0);
+    assert_fields(&json, &["\"type\":\"ref_code\"", "\"code\":\"moon\""]);
+    assert_no_warnings(&json);
+    let json = inspect(&ref_code.str(ref_code_account_offsets::CODE, "M").0);
+    assert_warning(&json, "code is not a normalized referral code");
+
+    let nickname = Account::new(NICKNAME_TAG, NICKNAME_ACCOUNT_SIZE)
+        .str(nickname_account_offsets::NICKNAME, "alice");
+    let json = inspect(&nickname.0);
+    assert_fields(&json, &["\"type\":\"nickname\"", "\"nickname\":\"alice\""]);
+    assert_no_warnings(&json);
+    let json = inspect(&nickname.str(nickname_account_offsets::NICKNAME + 2, " ").0);
+    assert_warning(&json, "nickname is not normalized");
+}
+
+#[test]
+fn decodes_position() {
+    use position_account_offsets::*;
+    let position = Account::new(POSITION_TAG, POSITION_ACCOUNT_SIZE)
+        .u64(QUANTITY, 10)
+        .u64(COST_BASIS, 500)
+        .i64(REALIZED_PNL, -25);
+    let json = inspect(&position.0);
+    assert_fields(
+        &json,
+        &[
+            "\"type\":\"position\"",
+            "\"quantity\":10",
+            "\"realized_pnl\":-25",
+        ],
+    );
+    assert_no_warnings(&json);
+
+    let json = inspect(&position.u64(QUANTITY, 0).0);
+    assert_warning(&json, "cost_basis 500 without quantity");
+}
+
+#[test]
+fn decodes_delegate() {
+    use delegate_account_offsets::*;
+    let delegate = Account::new(DELEGATE_TAG, DELEGATE_ACCOUNT_SIZE)
+        .u64(SPEND_LIMIT, 1_000)
+        .u64(SPENT, 400)
+        .u64(NETWORKS, u64::MAX);
+    let json = inspect(&delegate.0);
+    assert_fields(
+        &json,
+        &[
+            "\"type\":\"delegate\"",
+            "\"spent\":400",
+            &format!("\"networks\":{}", u64::MAX),
+        ],
+    );
+    assert_no_warnings(&json);
+
+    let json = inspect(&delegate.u64(SPENT, 1_001).0);
+    assert_warning(&json, "spent 1001 exceeds spend_limit 1000");
+}
+
+#[test]
+fn decodes_launch_allowance() {
+    let wallet = Pubkey::new_from_array([6; 32]);
+    let launch_allowance = Account::new(LAUNCH_ALLOWANCE_TAG, LAUNCH_ALLOWANCE_ACCOUNT_SIZE)
+        .pubkey(launch_allowance_account_offsets::WALLET, &wallet);
+    let json = inspect(&launch_allowance.0);
+    assert_fields(
+        &json,
+        &[
+            "\"type\":\"launch_allowance\"",
+            &format!("\"wallet\":\"{}\"", wallet),
+        ],
+    );
+    assert_no_warnings(&json);
+}
+
+#[test]
+fn decodes_launch_buy() {
+    use launch_buy_account_offsets::*;
+    let wallet = Pubkey::new_from_array([6; 32]);
+    let mint = Pubkey::new_from_array([7; 32]);
+    let launch_buy = Account::new(LAUNCH_BUY_TAG, LAUNCH_BUY_ACCOUNT_SIZE)
+        .pubkey(WALLET, &wallet)
+        .pubkey(MINT, &mint)
+        .u64(BOUGHT, 750)
+        .u32(LAST_BUY_TIME, 1_700_000_000)
+        .u32(BUYS_COUNT, 2);
+    let json = inspect(&launch_buy.0);
+    assert_fields(
+        &json,
+        &[
+            "\"type\":\"launch_buy\"",
+            &format!("\"wallet\":\"{}\"", wallet),
+            &format!("\"mint\":\"{}\"", mint),
+            "\"bought\":750",
+            "\"last_buy_time\":1700000000",
+            "\"buys_count\":2",
+        ],
+    );
+    assert_no_warnings(&json);
+}
+
+#[test]
+fn decodes_pool() {
+    use pool_account_offsets::*;
+    let pool = Account::new(POOL_TAG, POOL_ACCOUNT_SIZE)
+        .u64(BASE_CRNCY_RESERVE, 1_000)
+        .u64(TOKEN_RESERVE, 2_000)
+        .u32(SWAPS_COUNT, 4);
+    let json = inspect(&pool.0);
+    assert_fields(
+        &json,
+        &[
+            "\"type\":\"pool\"",
+            "\"base_crncy_reserve\":1000",
+            "\"swaps_count\":4",
+        ],
+    );
+    assert_no_warnings(&json);
+
+    let json = inspect(&pool.u64(TOKEN_RESERVE, 0).0);
+    assert_warning(&json, "pool with an empty reserve");
+}
+
+#[test]
+fn decodes_price_history_oldest_candle_first() {
+    use price_history_account_offsets::*;
+    let last = CANDLES + (PRICE_HISTORY_CANDLES - 1) * CANDLE_SIZE;
+    let price_history = Account::new(PRICE_HISTORY_TAG, PRICE_HISTORY_ACCOUNT_SIZE)
+        .u32(INTERVAL, 60)
+        .u32(CANDLES_COUNT, 2)
+        .u32(last + candle_offsets::START_TIME, 60)
+        .f64(last + candle_offsets::CLOSE, 2.5)
+        .u32(CANDLES + candle_offsets::START_TIME, 120);
+    let json = inspect(&price_history.0);
+    let first = json.find("\"start_time\":60").unwrap();
+    let second = json.find("\"start_time\":120").unwrap();
+    assert!(first < second);
+    assert_fields(&json, &["\"close\":2.5"]);
+    assert_no_warnings(&json);
+
+    let json = inspect(
+        &price_history
+            .u32(INTERVAL, 0)
+            .u32(HEAD, PRICE_HISTORY_CANDLES as u32)
+            .0,
+    );
+    assert_fields(&json, &["\"candles\":[]"]);
+    assert_warning(&json, "interval is 0");
+    assert_warning(&json, "head 64 exceeds 63");
+}
@@ -0,0 +1,57 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use common::*;
+use hype_watch::program::error::HypeError;
+use solana_sdk::signature::Signer;
+
+#[tokio::test]
+async fn detects_vault_and_token_discrepancies() {
+    let mut env = TestEnv::setup(spl_token::id()).await;
+    env.check_invariants(&[]).await.unwrap();
+
+    let trader = env.new_trader(1_000_000_000_000).await;
+    let (token, hype_mint, _, _) = env
+        .create_token(&trader, 0, "doge", 1_000_000)
+        .await
+        .unwrap();
+    let (other_token, other_mint, _, _) = env
+        .create_token(&trader, 0, "pepe", 2_000_000)
+        .await
+        .unwrap();
+    env.check_invariants(&[(token, hype_mint), (other_token, other_mint)])
+        .await
+        .unwrap();
+    // A subset of the tokens only bounds the totals
+    env.check_invariants(&[(other_token, other_mint)])
+        .await
+        .unwrap();
+    assert_hype_error(
+        env.check_invariants(&[(other_token, other_mint), (token, hype_mint)])
+            .await,
+        HypeError::InvalidTokenAccount,
+    );
+    assert_hype_error(
+        env.check_invariants(&[(token, other_mint)]).await,
+        HypeError::InvalidTokenMint,
+    );
+
+    // Base currency sent straight to the vault is not accounted anywhere
+    let payer = env.context.payer.pubkey();
+    let ix = spl_token::instruction::mint_to(
+        &spl_token::id(),
+        &env.base_mint,
+        &env.vault.pubkey(),
+        &payer,
+        &[],
+        1,
+    )
+    .unwrap();
+    env.process(&[ix], &[]).await.unwrap();
+    assert_hype_error(
+        env.check_invariants(&[(token, hype_mint), (other_token, other_mint)])
+            .await,
+        HypeError::VaultBalanceMismatch,
+    );
+}
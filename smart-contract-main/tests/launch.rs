@@ -0,0 +1,239 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use common::*;
+use hype_watch::program::{error::HypeError, TRADE_EXTRA_LAUNCH_ALLOWANCE, TRADE_EXTRA_LAUNCH_BUY};
+use solana_sdk::signature::Signer;
+
+const TRADER_FUNDS: u64 = 1_000_000_000_000_000;
+
+async fn launch_env(params: RootParams) -> TestEnv {
+    let mut env = TestEnv::new(spl_token::id()).await;
+    env.params = params;
+    env.initialize_holder().await.unwrap();
+    env.add_operator(10).await.unwrap();
+    env.initialize_root().await.unwrap();
+    env.add_network("ethereum", "abcdefghijklmnopqrstuvwxyz0123456789", 24)
+        .await
+        .unwrap();
+    env
+}
+
+#[tokio::test]
+async fn launch_window_caps_and_spaces_buys() {
+    let max_buy = 1_000_000;
+    let mut env = launch_env(RootParams {
+        launch_window: 600,
+        launch_max_buy: max_buy,
+        buy_cooldown: 30,
+        ..RootParams::default()
+    })
+    .await;
+    let creator = env.new_trader(TRADER_FUNDS).await;
+    let trader = env.new_trader(TRADER_FUNDS).await;
+    let wallet = trader.pubkey();
+
+    // The creator's own first buy is capped too, and counted in its launch buy account
+    assert_hype_error(
+        env.create_token(&creator, 0, "doge", max_buy)
+            .await
+            .map(|(_, _, _, logs)| logs),
+        HypeError::LaunchBuyAccountRequired,
+    );
+    assert_hype_error(
+        env.create_launch_token(&creator, 0, "doge", max_buy + 1)
+            .await
+            .map(|(_, _, _, logs)| logs),
+        HypeError::LaunchMaxBuyExceeded,
+    );
+    let (token, hype_mint, hype_program_acc, _) = env
+        .create_launch_token(&creator, 0, "doge", max_buy)
+        .await
+        .unwrap();
+    assert_eq!(
+        env.launch_buy_account(&creator.pubkey(), &hype_mint)
+            .await
+            .bought,
+        max_buy
+    );
+    let buy = |env: &TestEnv, amount| {
+        let ix = env.mint_ix(
+            &wallet,
+            0,
+            "doge",
+            &hype_mint,
+            &hype_program_acc,
+            amount,
+            0,
+            &[],
+        );
+        with_trade_extras(
+            ix,
+            TRADE_EXTRA_LAUNCH_BUY,
+            &[launch_buy_address(VERSION, &wallet, &hype_mint)],
+        )
+    };
+
+    let ix = env.mint_ix(
+        &wallet,
+        0,
+        "doge",
+        &hype_mint,
+        &hype_program_acc,
+        max_buy,
+        0,
+        &[],
+    );
+    assert_hype_error(
+        env.process(&[ix], &[&trader]).await,
+        HypeError::LaunchBuyAccountRequired,
+    );
+    let ix = buy(&env, max_buy + 1);
+    assert_hype_error(
+        env.process(&[ix], &[&trader]).await,
+        HypeError::LaunchMaxBuyExceeded,
+    );
+    let ix = buy(&env, max_buy / 2);
+    env.process(&[ix], &[&trader]).await.unwrap();
+    let time = env.clock().await.unix_timestamp as u32;
+    let launch_buy = env.launch_buy_account(&wallet, &hype_mint).await;
+    assert_eq!(launch_buy.bought, max_buy / 2);
+    assert_eq!(launch_buy.last_buy_time, time);
+    assert_eq!(launch_buy.buys_count, 1);
+
+    // Each buy in the window waits for the cooldown of the last one, closed client or not
+    let ix = buy(&env, 1);
+    assert_hype_error(
+        env.process(&[ix], &[&trader]).await,
+        HypeError::BuyCooldownActive,
+    );
+    env.close_client(&trader).await.unwrap();
+    let ix = buy(&env, 1);
+    assert_hype_error(
+        env.process(&[ix], &[&trader]).await,
+        HypeError::BuyCooldownActive,
+    );
+
+    // Buys in the window add up to the cap
+    env.advance_clock(30).await;
+    let ix = buy(&env, max_buy / 2 + 1);
+    assert_hype_error(
+        env.process(&[ix], &[&trader]).await,
+        HypeError::LaunchMaxBuyExceeded,
+    );
+    let ix = buy(&env, max_buy / 2);
+    env.process(&[ix], &[&trader]).await.unwrap();
+    assert_eq!(
+        env.launch_buy_account(&wallet, &hype_mint).await.bought,
+        max_buy
+    );
+
+    // Past the window buys are free again and need no launch buy account
+    env.advance_clock(600).await;
+    let ix = buy(&env, max_buy * 5);
+    env.process(&[ix], &[&trader]).await.unwrap();
+    let ix = env.mint_ix(
+        &wallet,
+        0,
+        "doge",
+        &hype_mint,
+        &hype_program_acc,
+        max_buy * 3,
+        0,
+        &[],
+    );
+    env.process(&[ix], &[&trader]).await.unwrap();
+    assert_eq!(env.token_account(&token).await.supply, max_buy * 10);
+}
+
+#[tokio::test]
+async fn restricted_window_admits_creator_and_allow_listed() {
+    let mut env = launch_env(RootParams {
+        restricted_window: 600,
+        ..RootParams::default()
+    })
+    .await;
+    let admin = env.operator.insecure_clone();
+    let validator = env.validator.insecure_clone();
+    let creator = env.new_trader(TRADER_FUNDS).await;
+    let trader = env.new_trader(TRADER_FUNDS).await;
+    let wallet = trader.pubkey();
+    let amount = 1_000_000;
+    let (_, hype_mint, hype_program_acc, _) =
+        env.create_token(&creator, 0, "doge", amount).await.unwrap();
+    let buy = |env: &TestEnv, amount, allowance: bool| {
+        let ix = env.mint_ix(
+            &wallet,
+            0,
+            "doge",
+            &hype_mint,
+            &hype_program_acc,
+            amount,
+            0,
+            &[],
+        );
+        if allowance {
+            return with_trade_extras(
+                ix,
+                TRADE_EXTRA_LAUNCH_ALLOWANCE,
+                &[launch_allowance_address(VERSION, &wallet)],
+            );
+        }
+        ix
+    };
+
+    let ix = buy(&env, amount, false);
+    assert_hype_error(
+        env.process(&[ix], &[&trader]).await,
+        HypeError::LaunchRestricted,
+    );
+    let ix = env.mint_ix(
+        &creator.pubkey(),
+        0,
+        "doge",
+        &hype_mint,
+        &hype_program_acc,
+        amount,
+        0,
+        &[],
+    );
+    env.process(&[ix], &[&creator]).await.unwrap();
+
+    // Only the admin allow-lists wallets
+    assert_hype_error(
+        env.set_launch_allowance(&validator, &wallet, true).await,
+        HypeError::InvalidAdmin,
+    );
+    env.set_launch_allowance(&admin, &wallet, true)
+        .await
+        .unwrap();
+    let ix = buy(&env, amount, false);
+    assert_hype_error(
+        env.process(&[ix], &[&trader]).await,
+        HypeError::LaunchRestricted,
+    );
+    let ix = buy(&env, amount, true);
+    env.process(&[ix], &[&trader]).await.unwrap();
+
+    // A removed allowance is closed and no longer admits the wallet
+    env.set_launch_allowance(&admin, &wallet, false)
+        .await
+        .unwrap();
+    assert!(env
+        .account(&launch_allowance_address(VERSION, &wallet))
+        .await
+        .is_none());
+    let ix = buy(&env, amount - 1, false);
+    assert_hype_error(
+        env.process(&[ix], &[&trader]).await,
+        HypeError::LaunchRestricted,
+    );
+    env.advance_clock(600).await;
+    let ix = buy(&env, amount - 1, false);
+    env.process(&[ix], &[&trader]).await.unwrap();
+    assert_eq!(
+        env.balance(&env.hype_ata(&wallet, &hype_mint)).await,
+        amount * 2 - 1
+    );
+}
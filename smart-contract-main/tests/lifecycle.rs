@@ -0,0 +1,336 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use common::*;
+use hype_watch::{program::error::HypeError, program::*, state::*};
+use solana_sdk::{pubkey::Pubkey, signature::Signer};
+
+const TRADER_FUNDS: u64 = 1_000_000_000_000_000;
+
+struct Expected {
+    supply: u64,
+    tvl: u64,
+    fees: u64,
+    holder_fees: u64,
+    counter: u64,
+    trades: u64,
+    trader_base: u64,
+    vault: u64,
+}
+
+async fn check_state(
+    env: &mut TestEnv,
+    expected: &Expected,
+    trader: &Pubkey,
+    token: &Pubkey,
+    hype_mint: &Pubkey,
+) {
+    let root = env.root_account().await;
+    assert_eq!(root.supply, expected.supply);
+    assert_eq!(root.tvl, expected.tvl);
+    assert_eq!(root.fees, expected.fees);
+    assert_eq!(root.holder_fees, expected.holder_fees);
+    assert_eq!(root.counter, expected.counter);
+    let token_account = env.token_account(token).await;
+    assert_eq!(token_account.supply, expected.supply);
+    assert_eq!(token_account.all_time_trades_count, expected.trades);
+    assert_eq!(env.mint_supply(hype_mint).await, expected.supply);
+    let client = env.client_account(trader).await;
+    assert_eq!(client.all_time_trades_count as u64, expected.trades);
+    let trader_base = env.base_ata(trader);
+    assert_eq!(env.balance(&trader_base).await, expected.trader_base);
+    let trader_hype = env.hype_ata(trader, hype_mint);
+    assert_eq!(env.balance(&trader_hype).await, expected.supply);
+    let vault = env.vault.pubkey();
+    assert_eq!(env.balance(&vault).await, expected.vault);
+    env.check_invariants(&[(*token, *hype_mint)]).await.unwrap();
+}
+
+fn check_trade_event(logs: &[String], tag: u8, supply: u64, amount: u64, base_crncy_amount: u64) {
+    let trades = events_with_tag(logs, tag);
+    assert_eq!(trades.len(), 1);
+    assert_eq!(field_u64(&trades[0], 8), supply);
+    assert_eq!(field_u64(&trades[0], 13), amount);
+    assert_eq!(field_u64(&trades[0], 14), base_crncy_amount);
+}
+
+async fn lifecycle(base_token_program: Pubkey) {
+    let mut env = TestEnv::new(base_token_program).await;
+    let params = env.params;
+
+    // initialize_holder
+    env.initialize_holder().await.unwrap();
+    let holder_address = env.holder;
+    let data = env.account(&holder_address).await.unwrap().data;
+    assert_eq!(data.len(), HOLDER_ACCOUNT_SIZE);
+    let holder: HolderAccount = unsafe { read_struct(&data) };
+    assert_eq!(holder.tag, HOLDER_TAG);
+    assert_eq!(holder.wallet, env.holder_wallet.pubkey());
+    assert_eq!(holder.operators_count, 0);
+
+    // add_operator
+    env.add_operator(10).await.unwrap();
+    let data = env.account(&holder_address).await.unwrap().data;
+    assert_eq!(data.len(), HOLDER_ACCOUNT_SIZE + OPERATOR_RECORD_SIZE);
+    let holder: HolderAccount = unsafe { read_struct(&data) };
+    assert_eq!(holder.operators_count, 1);
+    let operator: OperatorRecord = unsafe { read_struct(&data[HOLDER_ACCOUNT_SIZE..]) };
+    assert_eq!(operator.operator_address, env.operator.pubkey());
+    assert_eq!(operator.version, VERSION);
+    assert_eq!(operator.max_networks_count, 10);
+    assert_hype_error(
+        env.add_operator(10).await,
+        HypeError::InvalidNewOperatorAccount,
+    );
+
+    // initialize_root
+    env.initialize_root().await.unwrap();
+    let root = env.root_account().await;
+    assert_eq!(root.tag, ROOT_TAG);
+    assert_eq!(root.version, VERSION);
+    assert_eq!(root.admin, env.operator.pubkey());
+    assert_eq!(root.fee_wallet, env.fee_wallet.pubkey());
+    assert_eq!(root.base_crncy_mint, env.base_mint);
+    assert_eq!(root.base_crncy_program_address, env.vault.pubkey());
+    assert_eq!(root.decimals, BASE_DECIMALS as u32);
+    assert_eq!(root.base_crncy_decs_factor, 10u32.pow(BASE_DECIMALS as u32));
+    assert_eq!(root.max_networks_count, 10);
+    assert_eq!(root.max_supply, params.max_supply);
+    assert_eq!(root.clients_count, 0);
+    assert_eq!(root.tokens_count, 0);
+    assert_eq!(root.counter, 0);
+    let vault = env.vault.pubkey();
+    assert_eq!(env.account(&vault).await.unwrap().owner, base_token_program);
+    assert_eq!(env.balance(&vault).await, 0);
+
+    // add_network
+    let logs = env
+        .add_network("ethereum", "abcdefghijklmnopqrstuvwxyz0123456789", 24)
+        .await
+        .unwrap();
+    let root = env.root_account().await;
+    assert_eq!(root.networks_count, 1);
+    let root_address = env.root;
+    let data = env.account(&root_address).await.unwrap().data;
+    assert_eq!(data.len(), ROOT_ACCOUNT_SIZE + NETWORK_RECORD_SIZE);
+    let network: NetworkRecord = unsafe { read_struct(&data[ROOT_ACCOUNT_SIZE..]) };
+    assert_eq!(network.validator, env.validator.pubkey());
+    assert_eq!(network.max_length, 24);
+    let network_events = events_with_tag(&logs, 2);
+    assert_eq!(network_events.len(), 1);
+    assert_eq!(network_events[0][1], 0u32.to_le_bytes());
+
+    // First mint creates the client, the token and its Token-2022 mint
+    let trader = env.new_trader(TRADER_FUNDS).await;
+    let amount = 1_000_000;
+    let quote = expected_mint(&params, 0, amount, true);
+    let (token, hype_mint, hype_program_acc, logs) =
+        env.create_token(&trader, 0, "doge", amount).await.unwrap();
+    let mut expected = Expected {
+        supply: amount,
+        tvl: quote.base_crncy_amount,
+        fees: quote.operator_fees,
+        holder_fees: quote.holder_fees,
+        counter: 2,
+        trades: 1,
+        trader_base: TRADER_FUNDS - quote.base_crncy_amount - quote.fees,
+        vault: quote.base_crncy_amount + quote.fees,
+    };
+    check_state(&mut env, &expected, &trader.pubkey(), &token, &hype_mint).await;
+    let root = env.root_account().await;
+    assert_eq!(root.clients_count, 1);
+    assert_eq!(root.tokens_count, 1);
+    let token_account = env.token_account(&token).await;
+    assert_eq!(token_account.tag, TOKEN_TAG);
+    assert_eq!(token_account.id, 0);
+    assert_eq!(token_account.mint, hype_mint);
+    assert_eq!(token_account.program_address, hype_program_acc);
+    assert_eq!(token_account.creator, trader.pubkey());
+    assert_eq!(token_account.status, token_status::NOT_CHECKED as u64);
+    let client = env.client_account(&trader.pubkey()).await;
+    assert_eq!(client.id, 0);
+    assert_eq!(client.wallet, trader.pubkey());
+    assert_eq!(client.tokens_created, 1);
+    assert_eq!(client.all_time_base_crncy_volume, quote.base_crncy_amount);
+    let new_clients = events_with_tag(&logs, 1);
+    assert_eq!(new_clients.len(), 1);
+    assert_eq!(field_u64(&new_clients[0], 1), 0);
+    let new_tokens = events_with_tag(&logs, 3);
+    assert_eq!(new_tokens.len(), 1);
+    assert_eq!(field_u64(&new_tokens[0], 3), 0);
+    assert_eq!(new_tokens[0][5], hype_mint.to_bytes());
+    check_trade_event(&logs, 4, amount, amount, quote.base_crncy_amount);
+
+    // Repeated mint/burn
+    for (is_mint, amount) in [
+        (true, 5_000_000),
+        (false, 2_000_000),
+        (true, 300_000),
+        (false, 3_300_000),
+        (true, 42),
+    ] {
+        let logs = if is_mint {
+            let quote = expected_mint(&params, expected.supply, amount, false);
+            let ix = env.mint_ix(
+                &trader.pubkey(),
+                0,
+                "doge",
+                &hype_mint,
+                &hype_program_acc,
+                amount,
+                quote.base_crncy_amount,
+                &[],
+            );
+            let logs = env.process(&[ix], &[&trader]).await.unwrap();
+            expected.supply += amount;
+            expected.tvl += quote.base_crncy_amount;
+            expected.trader_base -= quote.base_crncy_amount + quote.fees;
+            expected.vault += quote.base_crncy_amount + quote.fees;
+            expected.fees += quote.operator_fees;
+            expected.holder_fees += quote.holder_fees;
+            check_trade_event(&logs, 4, expected.supply, amount, quote.base_crncy_amount);
+            logs
+        } else {
+            let quote = expected_burn(&params, expected.supply, amount);
+            let ix = env.burn_ix(
+                &trader.pubkey(),
+                &token,
+                &hype_mint,
+                &hype_program_acc,
+                amount,
+                quote.base_crncy_amount,
+                &[],
+            );
+            let logs = env.process(&[ix], &[&trader]).await.unwrap();
+            expected.supply -= amount;
+            expected.tvl -= quote.base_crncy_amount;
+            expected.trader_base += quote.base_crncy_amount - quote.fees;
+            expected.vault -= quote.base_crncy_amount - quote.fees;
+            expected.fees += quote.operator_fees;
+            expected.holder_fees += quote.holder_fees;
+            check_trade_event(&logs, 5, expected.supply, amount, quote.base_crncy_amount);
+            logs
+        };
+        assert!(events_with_tag(&logs, 1).is_empty());
+        expected.counter += 1;
+        expected.trades += 1;
+        check_state(&mut env, &expected, &trader.pubkey(), &token, &hype_mint).await;
+    }
+
+    // Slippage bounds
+    let quote = expected_mint(&params, expected.supply, 1_000, false);
+    let ix = env.mint_ix(
+        &trader.pubkey(),
+        0,
+        "doge",
+        &hype_mint,
+        &hype_program_acc,
+        1_000,
+        quote.base_crncy_amount - 1,
+        &[],
+    );
+    assert_hype_error(
+        env.process(&[ix], &[&trader]).await,
+        HypeError::MaxTradeCostExceeded,
+    );
+    let ix = env.burn_ix(
+        &trader.pubkey(),
+        &token,
+        &hype_mint,
+        &hype_program_acc,
+        expected.supply + 1,
+        0,
+        &[],
+    );
+    assert_hype_error(
+        env.process(&[ix], &[&trader]).await,
+        HypeError::TooBigQuantity,
+    );
+
+    // change_token_status
+    env.change_token_status(&token, true).await.unwrap();
+    let token_account = env.token_account(&token).await;
+    assert_eq!(token_account.status, token_status::VERIFIED as u64);
+    assert_hype_error(
+        env.change_token_status(&token, false).await,
+        HypeError::TokenAlreadyVerified,
+    );
+
+    // Both withdrawals leave exactly the TVL in the vault
+    env.withdraw_operator_funds().await.unwrap();
+    let fee_wallet_ata = env.base_ata(&env.fee_wallet.pubkey());
+    assert_eq!(env.balance(&fee_wallet_ata).await, expected.fees);
+    assert_eq!(env.root_account().await.fees, 0);
+    expected.vault -= expected.fees;
+    assert_eq!(env.balance(&vault).await, expected.vault);
+
+    env.withdraw_holder_funds().await.unwrap();
+    let holder_wallet_ata = env.base_ata(&env.holder_wallet.pubkey());
+    assert_eq!(env.balance(&holder_wallet_ata).await, expected.holder_fees);
+    assert_eq!(env.root_account().await.holder_fees, 0);
+    expected.vault -= expected.holder_fees;
+    assert_eq!(env.balance(&vault).await, expected.vault);
+    assert_eq!(expected.vault, expected.tvl);
+    env.check_invariants(&[(token, hype_mint)]).await.unwrap();
+}
+
+#[tokio::test]
+async fn lifecycle_spl_token() {
+    lifecycle(spl_token::id()).await;
+}
+
+#[tokio::test]
+async fn lifecycle_token_2022() {
+    lifecycle(spl_token_2022::id()).await;
+}
+
+#[tokio::test]
+async fn trades_paying_more_fees_than_their_size_are_rejected() {
+    let mut env = TestEnv::new(spl_token::id()).await;
+    env.params.min_fee = 1.0;
+    env.initialize_holder().await.unwrap();
+    env.add_operator(10).await.unwrap();
+    env.initialize_root().await.unwrap();
+    env.add_network("ethereum", "abcdefghijklmnopqrstuvwxyz0123456789", 24)
+        .await
+        .unwrap();
+    let trader = env.new_trader(TRADER_FUNDS).await;
+    let supply = 1_000_000_000;
+    let (token, hype_mint, hype_program_acc, _) =
+        env.create_token(&trader, 0, "doge", supply).await.unwrap();
+
+    let curve = env.params.curve();
+    let dust_mint = quote_mint(&curve, supply, 1).unwrap();
+    assert!(dust_mint.fees > dust_mint.base_crncy_amount);
+    let ix = env.mint_ix(
+        &trader.pubkey(),
+        0,
+        "doge",
+        &hype_mint,
+        &hype_program_acc,
+        1,
+        0,
+        &[],
+    );
+    assert_hype_error(
+        env.process(&[ix], &[&trader]).await,
+        HypeError::TooSmallQuantity,
+    );
+    let dust_burn = quote_burn(&curve, supply, 1).unwrap();
+    assert!(dust_burn.fees > dust_burn.base_crncy_amount);
+    let ix = env.burn_ix(
+        &trader.pubkey(),
+        &token,
+        &hype_mint,
+        &hype_program_acc,
+        1,
+        0,
+        &[],
+    );
+    assert_hype_error(
+        env.process(&[ix], &[&trader]).await,
+        HypeError::TooSmallQuantity,
+    );
+    assert_eq!(env.token_account(&token).await.supply, supply);
+}
@@ -0,0 +1,160 @@
+mod common;
+
+use common::input::{process, RawAccount};
+use common::*;
+use hype_watch::program::error::HypeError;
+use solana_program::{program_error::ProgramError, pubkey::Pubkey, system_program};
+
+fn assert_error(result: Result<(), ProgramError>, error: HypeError) {
+    assert_eq!(result, Err(ProgramError::Custom(error as u32)));
+}
+
+fn account(key: Pubkey, owner: Pubkey, is_signer: bool, data: Vec<u8>) -> RawAccount {
+    RawAccount {
+        key: key,
+        owner: owner,
+        is_signer: is_signer,
+        is_writable: true,
+        lamports: 1_000_000_000,
+        data: data,
+    }
+}
+
+#[test]
+fn empty_and_unknown_instructions() {
+    assert_error(
+        process(&program_id(), &[], &[]),
+        HypeError::InvalidDataLength,
+    );
+    for tag in 31..=255u8 {
+        assert_error(
+            process(&program_id(), &[], &[tag]),
+            HypeError::InvalidInstruction,
+        );
+    }
+}
+
+#[test]
+fn truncated_instruction_data() {
+    // Every payload shorter than the documented layout is rejected
+    let layouts: [(u8, usize); 16] = [
+        (2, 112),
+        (3, 112),
+        (4, 48),
+        (5, 24),
+        (6, 40),
+        (7, 2),
+        (12, 40),
+        (15, 16),
+        (20, 12),
+        (21, 16),
+        (22, 24),
+        (23, 24),
+        (24, 32),
+        (26, 32),
+        (27, 12),
+        (29, 32),
+    ];
+    for &(tag, len) in layouts.iter() {
+        for short in 1..len {
+            let mut data = vec![0u8; short];
+            data[0] = tag;
+            assert_error(
+                process(&program_id(), &[], &data),
+                HypeError::InvalidDataLength,
+            );
+        }
+    }
+}
+
+#[test]
+fn tag_only_payloads_never_panic() {
+    let signer = Pubkey::new_unique();
+    for tag in 0..=30u8 {
+        let accounts: Vec<RawAccount> = (0..17)
+            .map(|_| account(Pubkey::new_unique(), system_program::ID, true, vec![]))
+            .collect();
+        let mut data = vec![0u8; 128];
+        data[0] = tag;
+        assert!(process(&program_id(), &accounts, &data).is_err());
+        assert!(process(&program_id(), &accounts[..2], &data).is_err());
+    }
+    let client = account(
+        client_address(VERSION, &signer),
+        program_id(),
+        false,
+        vec![0; 8],
+    );
+    let mut data = vec![0u8; 40];
+    data[0] = 6;
+    assert_error(
+        process(
+            &program_id(),
+            &[account(signer, system_program::ID, true, vec![]), client],
+            &data,
+        ),
+        HypeError::InvalidAccountSize,
+    );
+}
+
+#[test]
+fn short_program_accounts() {
+    let validator = Pubkey::new_unique();
+    let root = account(root_address(VERSION), program_id(), false, vec![0; 16]);
+    let token = account(Pubkey::new_unique(), program_id(), false, vec![]);
+    assert_error(
+        process(
+            &program_id(),
+            &[
+                account(validator, system_program::ID, true, vec![]),
+                root.clone(),
+                token,
+            ],
+            &[7, 1],
+        ),
+        HypeError::InvalidAccountSize,
+    );
+    let mut data = vec![0u8; 112];
+    data[0] = 3;
+    assert_error(
+        process(
+            &program_id(),
+            &[
+                account(validator, system_program::ID, true, vec![]),
+                root.clone(),
+                account(validator, system_program::ID, false, vec![]),
+                account(system_program::ID, system_program::ID, false, vec![]),
+            ],
+            &data,
+        ),
+        HypeError::InvalidAccountSize,
+    );
+    assert_error(
+        process(
+            &program_id(),
+            &[root, account(validator, system_program::ID, false, vec![])],
+            &[10],
+        ),
+        HypeError::InvalidAccountSize,
+    );
+}
+
+#[test]
+fn check_name_stays_inside_a_full_mask() {
+    use hype_watch::{program::*, state::check_name};
+    let mut mask = [b'b'; MASK_STRING_LENGTH];
+    mask[0] = b'a';
+    let name = fixed::<ADDRESS_STRING_LENGTH>("bb");
+    assert_eq!(check_name(&name, &mask, ADDRESS_STRING_LENGTH), Ok(()));
+    // The mask is read from its second byte on, as it always was
+    let name = fixed::<ADDRESS_STRING_LENGTH>("ab");
+    assert_error(
+        check_name(&name, &mask, ADDRESS_STRING_LENGTH),
+        HypeError::InvalidAddress,
+    );
+    let name = fixed::<ADDRESS_STRING_LENGTH>("c");
+    assert_error(
+        check_name(&name, &mask, ADDRESS_STRING_LENGTH),
+        HypeError::InvalidAddress,
+    );
+}
@@ -0,0 +1,166 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use common::*;
+use hype_watch::program::error::HypeError;
+use hype_watch::program::*;
+use hype_watch::state::*;
+use solana_sdk::{pubkey::Pubkey, signature::Signer};
+
+const TRADER_FUNDS: u64 = 1_000_000_000_000_000;
+
+/// Replaces the data of `address`, keeping its lamports, owner and the rest.
+async fn set_data(env: &mut TestEnv, address: &Pubkey, data: Vec<u8>) {
+    let mut account = env.account(address).await.unwrap();
+    account.data = data;
+    env.context.set_account(address, &account.into());
+}
+
+#[tokio::test]
+async fn migrate_original_layouts() {
+    let mut env = TestEnv::setup(spl_token::id()).await;
+    let trader = env.new_trader(TRADER_FUNDS).await;
+    let amount = 1_000_000;
+    let (token, hype_mint, hype_program_acc, _) =
+        env.create_token(&trader, 0, "doge", amount).await.unwrap();
+    let root = env.root;
+    let client = client_address(VERSION, &trader.pubkey());
+    let root_data = env.account(&root).await.unwrap().data;
+    let client_data = env.account(&client).await.unwrap().data;
+    let token_data = env.account(&token).await.unwrap().data;
+
+    // Store the accounts as the original program version did, without the appended
+    // fields and with the network records right after the original root fields
+    let records = root_account_offsets::NETWORK_RECORDS;
+    let mut legacy_root = root_data[..LEGACY_ROOT_ACCOUNT_SIZE].to_vec();
+    legacy_root.extend_from_slice(&root_data[records..records + LEGACY_NETWORK_RECORD_SIZE]);
+    set_data(&mut env, &root, legacy_root).await;
+    set_data(
+        &mut env,
+        &client,
+        client_data[..LEGACY_CLIENT_ACCOUNT_SIZE].to_vec(),
+    )
+    .await;
+    set_data(
+        &mut env,
+        &token,
+        token_data[..LEGACY_TOKEN_ACCOUNT_SIZE].to_vec(),
+    )
+    .await;
+
+    let ix = env.mint_ix(
+        &trader.pubkey(),
+        0,
+        "doge",
+        &hype_mint,
+        &hype_program_acc,
+        amount,
+        0,
+        &[],
+    );
+    assert_hype_error(
+        env.process(std::slice::from_ref(&ix), &[&trader]).await,
+        HypeError::InvalidAccountSize,
+    );
+
+    for address in [root, client, token] {
+        env.migrate_account(&address).await.unwrap();
+        assert_hype_error(
+            env.migrate_account(&address).await,
+            HypeError::AccountAlreadyMigrated,
+        );
+    }
+
+    // The original fields and network records are kept; the appended fields hold
+    // the defaults of an `initialize_root` that omitted them
+    let mut expected_root = root_data.clone();
+    expected_root[LEGACY_ROOT_ACCOUNT_SIZE..records].fill(0);
+    expected_root[root_account_offsets::REF_DEPTH..root_account_offsets::REF_DEPTH + 4]
+        .copy_from_slice(&1u32.to_le_bytes());
+    expected_root
+        [root_account_offsets::REF_GRACE_PERIOD..root_account_offsets::REF_GRACE_PERIOD + 4]
+        .copy_from_slice(&DEFAULT_REF_GRACE_PERIOD.to_le_bytes());
+    expected_root[root_account_offsets::CANDLE_INTERVAL..root_account_offsets::CANDLE_INTERVAL + 4]
+        .copy_from_slice(&DEFAULT_CANDLE_INTERVAL.to_le_bytes());
+    for offset in [
+        root_account_offsets::SWITCH_FEE_RATE,
+        root_account_offsets::POOL_FEE_RATE,
+    ] {
+        expected_root[offset..offset + 8].copy_from_slice(&env.params.fee_rate.to_le_bytes());
+    }
+    let cap = records + network_record_offsets::CREATOR_FEE_CAP;
+    expected_root[cap..cap + 8].copy_from_slice(&1.0f64.to_le_bytes());
+    assert_eq!(env.account(&root).await.unwrap().data, expected_root);
+    let mut expected_client = client_data.clone();
+    expected_client[LEGACY_CLIENT_ACCOUNT_SIZE..].fill(0);
+    assert_eq!(env.account(&client).await.unwrap().data, expected_client);
+    let mut expected_token = token_data.clone();
+    expected_token[LEGACY_TOKEN_ACCOUNT_SIZE..].fill(0);
+    assert_eq!(env.account(&token).await.unwrap().data, expected_token);
+
+    // Migrated accounts trade and keep the vault consistent
+    env.process(&[ix], &[&trader]).await.unwrap();
+    let ix = env.burn_ix(
+        &trader.pubkey(),
+        &token,
+        &hype_mint,
+        &hype_program_acc,
+        amount,
+        0,
+        &[],
+    );
+    env.process(&[ix], &[&trader]).await.unwrap();
+    env.check_invariants(&[(token, hype_mint)]).await.unwrap();
+}
+
+#[tokio::test]
+async fn unmigrated_root_with_networks_is_rejected() {
+    let mut env = TestEnv::setup(spl_token::id()).await;
+    env.add_network("solana", "abcdefghijklmnopqrstuvwxyz0123456789", 24)
+        .await
+        .unwrap();
+    let trader = env.new_trader(TRADER_FUNDS).await;
+    let amount = 1_000_000;
+    let (_, hype_mint, hype_program_acc, _) =
+        env.create_token(&trader, 0, "doge", amount).await.unwrap();
+    let root = env.root;
+    let root_data = env.account(&root).await.unwrap().data;
+
+    // With two networks the original layout is longer than ROOT_ACCOUNT_SIZE
+    let records = root_account_offsets::NETWORK_RECORDS;
+    let mut legacy_root = root_data[..LEGACY_ROOT_ACCOUNT_SIZE].to_vec();
+    for i in 0..2 {
+        let from = records + i * NETWORK_RECORD_SIZE;
+        legacy_root.extend_from_slice(&root_data[from..from + LEGACY_NETWORK_RECORD_SIZE]);
+    }
+    assert!(legacy_root.len() >= ROOT_ACCOUNT_SIZE);
+    set_data(&mut env, &root, legacy_root).await;
+
+    let ix = env.mint_ix(
+        &trader.pubkey(),
+        0,
+        "doge",
+        &hype_mint,
+        &hype_program_acc,
+        amount,
+        0,
+        &[],
+    );
+    assert_hype_error(
+        env.process(std::slice::from_ref(&ix), &[&trader]).await,
+        HypeError::AccountNotMigrated,
+    );
+    let admin = env.operator.insecure_clone();
+    assert_hype_error(
+        env.set_fee_tiers(&admin, &[(1_000, 0.000_000_000_5)]).await,
+        HypeError::AccountNotMigrated,
+    );
+
+    env.migrate_account(&root).await.unwrap();
+    assert_eq!(
+        env.account(&root).await.unwrap().data.len(),
+        root_data.len()
+    );
+    env.process(&[ix], &[&trader]).await.unwrap();
+}
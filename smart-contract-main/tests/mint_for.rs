@@ -0,0 +1,77 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use common::*;
+use hype_watch::program::error::HypeError;
+use solana_sdk::{signature::Keypair, signature::Signer};
+
+const TRADER_FUNDS: u64 = 1_000_000_000_000_000;
+
+#[tokio::test]
+async fn payer_buys_for_recipient() {
+    let mut env = TestEnv::setup(spl_token::id()).await;
+    let params = env.params;
+    let payer = env.new_trader(TRADER_FUNDS).await;
+    let wallet = payer.pubkey();
+    let recipient = Keypair::new().pubkey();
+    let supply = 1_000_000;
+    let (token, hype_mint, hype_program_acc, _) =
+        env.create_token(&payer, 0, "doge", supply).await.unwrap();
+    let recipient_ata = env.hype_ata(&recipient, &hype_mint);
+    assert!(env.account(&recipient_ata).await.is_none());
+
+    let amount = 2_000_000;
+    let base = env.base_ata(&wallet);
+    let before = env.balance(&base).await;
+    let ix = env.mint_for_ix(
+        &wallet,
+        &recipient,
+        &token,
+        &hype_mint,
+        &hype_program_acc,
+        amount,
+        0,
+    );
+    let logs = env.process(&[ix], &[&payer]).await.unwrap();
+    let quote = expected_mint(&params, supply, amount, false);
+    assert_eq!(
+        before - env.balance(&base).await,
+        quote.base_crncy_amount + quote.fees
+    );
+    assert_eq!(env.balance(&recipient_ata).await, amount);
+    assert_eq!(
+        env.balance(&env.hype_ata(&wallet, &hype_mint)).await,
+        supply
+    );
+    let client = env.client_account(&wallet).await;
+    assert_eq!(client.all_time_trades_count, 2);
+    assert_eq!(client.all_time_tokens_volume, supply + amount);
+    assert!(env
+        .account(&client_address(VERSION, &recipient))
+        .await
+        .is_none());
+    let event = &events_with_tag(&logs, 14)[0];
+    assert_eq!(event[4], wallet.to_bytes());
+    assert_eq!(event[5], recipient.to_bytes());
+    assert_eq!(field_u64(event, 6), amount);
+    assert_eq!(field_u64(event, 7), quote.base_crncy_amount);
+
+    // The recipient's account has to be its associated hype account
+    let mut ix = env.mint_for_ix(
+        &wallet,
+        &recipient,
+        &token,
+        &hype_mint,
+        &hype_program_acc,
+        amount,
+        0,
+    );
+    let last = ix.accounts.len() - 1;
+    ix.accounts[last].pubkey = env.hype_ata(&wallet, &hype_mint);
+    assert_hype_error(
+        env.process(&[ix], &[&payer]).await,
+        HypeError::InvalidAssociatedTokenAddress,
+    );
+    env.check_invariants(&[(token, hype_mint)]).await.unwrap();
+}
@@ -0,0 +1,172 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use common::*;
+use hype_watch::{program::error::HypeError, program::*, state::*};
+use solana_program_test::BanksClientError;
+use solana_sdk::{
+    instruction::AccountMeta,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+const TRADER_FUNDS: u64 = 1_000_000_000_000_000;
+
+/// Mint creating the client of `trader` with `nickname`.
+async fn named_mint(
+    env: &mut TestEnv,
+    trader: &Keypair,
+    hype_mint: &Pubkey,
+    hype_program_acc: &Pubkey,
+    nickname: &str,
+) -> Result<Vec<String>, BanksClientError> {
+    let mut ix = env.mint_ix(
+        &trader.pubkey(),
+        0,
+        "doge",
+        hype_mint,
+        hype_program_acc,
+        1_000,
+        0,
+        &[],
+    );
+    ix.data[48..80].copy_from_slice(&fixed::<NICKNAME_STRING_LENGTH>(nickname));
+    ix.accounts
+        .push(AccountMeta::new(nickname_address(VERSION, nickname), false));
+    ix.accounts.push(AccountMeta::new_readonly(
+        client_ref_code_address(VERSION, &fixed::<NICKNAME_STRING_LENGTH>(nickname)),
+        false,
+    ));
+    env.process(&[ix], &[trader]).await
+}
+
+#[tokio::test]
+async fn nicknames_are_unique() {
+    let mut env = TestEnv::setup(spl_token::id()).await;
+    let alice = env.new_trader(TRADER_FUNDS).await;
+    let bob = env.new_trader(TRADER_FUNDS).await;
+    let (_, hype_mint, hype_program_acc, _) =
+        env.create_token(&alice, 0, "doge", 1_000).await.unwrap();
+    assert_eq!(
+        env.client_account(&alice.pubkey()).await.nickname,
+        [0; NICKNAME_STRING_LENGTH]
+    );
+
+    // The registry holds the case-folded name, the client keeps it as typed
+    env.change_nickname(&alice, "Alice").await.unwrap();
+    assert_eq!(
+        env.client_account(&alice.pubkey()).await.nickname,
+        fixed::<NICKNAME_STRING_LENGTH>("Alice")
+    );
+    let alice_acc = nickname_address(VERSION, "alice");
+    let data = env.account(&alice_acc).await.unwrap().data;
+    assert_eq!(data.len(), NICKNAME_ACCOUNT_SIZE);
+    let registry: NicknameAccount = unsafe { read_struct(&data) };
+    assert_eq!(registry.tag, NICKNAME_TAG);
+    assert_eq!(registry.wallet, alice.pubkey());
+    assert_eq!(registry.nickname, fixed::<NICKNAME_STRING_LENGTH>("alice"));
+
+    // A new client can not take a registered name in any case
+    assert_hype_error(
+        named_mint(&mut env, &bob, &hype_mint, &hype_program_acc, "ALICE").await,
+        HypeError::NicknameAlreadyTaken,
+    );
+    assert_hype_error(
+        named_mint(&mut env, &bob, &hype_mint, &hype_program_acc, "bob!").await,
+        HypeError::InvalidNickname,
+    );
+    named_mint(&mut env, &bob, &hype_mint, &hype_program_acc, "Bob")
+        .await
+        .unwrap();
+    assert_eq!(
+        env.client_account(&bob.pubkey()).await.nickname,
+        fixed::<NICKNAME_STRING_LENGTH>("Bob")
+    );
+    assert!(env
+        .account(&nickname_address(VERSION, "bob"))
+        .await
+        .is_some());
+    assert_hype_error(
+        env.change_nickname(&bob, "aLiCe").await,
+        HypeError::NicknameAlreadyTaken,
+    );
+    assert_hype_error(
+        env.change_nickname(&bob, "bob bob").await,
+        HypeError::InvalidNickname,
+    );
+
+    // Another spelling keeps the entry, another name moves it
+    env.change_nickname(&alice, "ALICE").await.unwrap();
+    assert!(env.account(&alice_acc).await.is_some());
+    env.change_nickname(&alice, "alice-2").await.unwrap();
+    assert!(env.account(&alice_acc).await.is_none());
+    let registry: NicknameAccount = unsafe {
+        read_struct(
+            &env.account(&nickname_address(VERSION, "alice-2"))
+                .await
+                .unwrap()
+                .data,
+        )
+    };
+    assert_eq!(registry.wallet, alice.pubkey());
+
+    // The released name is free again, and dropping a name releases it
+    env.change_nickname(&bob, "Alice").await.unwrap();
+    assert!(env
+        .account(&nickname_address(VERSION, "bob"))
+        .await
+        .is_none());
+    assert_eq!(
+        unsafe { read_struct::<NicknameAccount>(&env.account(&alice_acc).await.unwrap().data) }
+            .wallet,
+        bob.pubkey()
+    );
+    env.change_nickname(&alice, "").await.unwrap();
+    assert!(env
+        .account(&nickname_address(VERSION, "alice-2"))
+        .await
+        .is_none());
+    assert_eq!(
+        env.client_account(&alice.pubkey()).await.nickname,
+        [0; NICKNAME_STRING_LENGTH]
+    );
+}
+
+#[tokio::test]
+async fn nicknames_and_ref_codes_share_one_namespace() {
+    let mut env = TestEnv::setup(spl_token::id()).await;
+    let alice = env.new_trader(TRADER_FUNDS).await;
+    let bob = env.new_trader(TRADER_FUNDS).await;
+    let (_, hype_mint, hype_program_acc, _) =
+        env.create_token(&alice, 0, "doge", 1_000).await.unwrap();
+    named_mint(&mut env, &bob, &hype_mint, &hype_program_acc, "Bob")
+        .await
+        .unwrap();
+    env.change_nickname(&alice, "Alice").await.unwrap();
+
+    // Another wallet's nickname can not be registered as a code, one's own can
+    assert_hype_error(
+        env.register_ref_code(&bob, "alice", &ref_code_address(VERSION, "alice"))
+            .await,
+        HypeError::RefCodeAlreadyRegistered,
+    );
+    env.register_ref_code(&alice, "alice", &ref_code_address(VERSION, "alice"))
+        .await
+        .unwrap();
+
+    // Another wallet's code can not be taken as a nickname, at creation or later
+    env.register_ref_code(&bob, "promo", &ref_code_address(VERSION, "promo"))
+        .await
+        .unwrap();
+    assert_hype_error(
+        env.change_nickname(&alice, "Promo").await,
+        HypeError::NicknameAlreadyTaken,
+    );
+    let carol = env.new_trader(TRADER_FUNDS).await;
+    assert_hype_error(
+        named_mint(&mut env, &carol, &hype_mint, &hype_program_acc, "PROMO").await,
+        HypeError::NicknameAlreadyTaken,
+    );
+    env.change_nickname(&bob, "promo").await.unwrap();
+}
@@ -0,0 +1,260 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use common::*;
+use hype_watch::{program::error::HypeError, program::*};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    rent::Rent,
+    signature::{Keypair, Signer},
+};
+
+const TRADER_FUNDS: u64 = 1_000_000_000_000_000;
+
+fn with_position(ix: Instruction, wallet: &Pubkey, hype_mint: &Pubkey) -> Instruction {
+    with_trade_extras(
+        ix,
+        TRADE_EXTRA_POSITION,
+        &[position_address(VERSION, wallet, hype_mint)],
+    )
+}
+
+/// `ix`, a `switch`, passing the position accounts of the token received and of the
+/// token given up.
+fn with_switch_positions(
+    ix: Instruction,
+    wallet: &Pubkey,
+    from_mint: &Pubkey,
+    to_mint: &Pubkey,
+) -> Instruction {
+    let mut ix = with_position(ix, wallet, to_mint);
+    ix.data.resize(44, 0);
+    ix.data[40..44].copy_from_slice(&TRADE_EXTRA_POSITION.to_le_bytes());
+    ix.accounts.push(AccountMeta::new(
+        position_address(VERSION, wallet, from_mint),
+        false,
+    ));
+    ix
+}
+
+/// Base currency that `wallet` spends (negative) or receives in `ix`.
+async fn traded(env: &mut TestEnv, wallet: &Keypair, ix: Instruction) -> i128 {
+    let base = env.base_ata(&wallet.pubkey());
+    let before = env.balance(&base).await;
+    env.process(&[ix], &[wallet]).await.unwrap();
+    env.balance(&base).await as i128 - before as i128
+}
+
+#[tokio::test]
+async fn positions_track_cost_and_pnl() {
+    let mut env = TestEnv::setup(spl_token::id()).await;
+    let creator = env.new_trader(TRADER_FUNDS).await;
+    let trader = env.new_trader(TRADER_FUNDS).await;
+    let wallet = trader.pubkey();
+    let amount = 1_000_000_000;
+    let (token, hype_mint, hype_program_acc, _) =
+        env.create_token(&creator, 0, "doge", amount).await.unwrap();
+    let mint_ix = |env: &TestEnv, amount: u64| {
+        env.mint_ix(
+            &wallet,
+            0,
+            "doge",
+            &hype_mint,
+            &hype_program_acc,
+            amount,
+            0,
+            &[],
+        )
+    };
+    let burn_ix = |env: &TestEnv, amount: u64| {
+        env.burn_ix(
+            &wallet,
+            &token,
+            &hype_mint,
+            &hype_program_acc,
+            amount,
+            0,
+            &[],
+        )
+    };
+
+    // Two buys at rising prices
+    let ix = with_position(mint_ix(&env, amount), &wallet, &hype_mint);
+    let first_cost = -traded(&mut env, &trader, ix).await as u64;
+    let ix = with_position(mint_ix(&env, amount), &wallet, &hype_mint);
+    let second_cost = -traded(&mut env, &trader, ix).await as u64;
+    assert!(second_cost > first_cost);
+    let position = env.position_account(&wallet, &hype_mint).await;
+    assert_eq!(position.tag, POSITION_TAG);
+    assert_eq!(position.wallet, wallet);
+    assert_eq!(position.mint, hype_mint);
+    assert_eq!(position.quantity, 2 * amount);
+    assert_eq!(position.cost_basis, first_cost + second_cost);
+    assert_eq!(position.total_cost, first_cost + second_cost);
+    assert_eq!(position.trades_count, 2);
+
+    // A sell realizes against the average cost
+    let ix = with_position(burn_ix(&env, amount), &wallet, &hype_mint);
+    let proceeds = traded(&mut env, &trader, ix).await as u64;
+    let basis = (first_cost + second_cost) / 2;
+    let position = env.position_account(&wallet, &hype_mint).await;
+    assert_eq!(position.quantity, amount);
+    assert_eq!(position.cost_basis, first_cost + second_cost - basis);
+    assert_eq!(position.total_proceeds, proceeds);
+    assert_eq!(position.realized_pnl, proceeds as i64 - basis as i64);
+
+    // Once tracked, trades have to pass the account; gifts received are not tracked
+    let ix = mint_ix(&env, amount);
+    assert_hype_error(
+        env.process(&[ix], &[&trader]).await,
+        HypeError::PositionRequired,
+    );
+    let ix = env.mint_for_ix(
+        &creator.pubkey(),
+        &wallet,
+        &token,
+        &hype_mint,
+        &hype_program_acc,
+        amount,
+        0,
+    );
+    env.process(&[ix], &[&creator]).await.unwrap();
+    assert_eq!(
+        env.position_account(&wallet, &hype_mint).await.quantity,
+        amount
+    );
+
+    // Selling more than the tracked quantity only realizes the tracked part
+    let ix = with_position(burn_ix(&env, 2 * amount), &wallet, &hype_mint);
+    let last_proceeds = traded(&mut env, &trader, ix).await as u64;
+    let position = env.position_account(&wallet, &hype_mint).await;
+    assert_eq!(position.quantity, 0);
+    assert_eq!(position.cost_basis, 0);
+    assert_eq!(position.total_proceeds, proceeds + last_proceeds);
+    assert_eq!(
+        position.realized_pnl,
+        proceeds as i64 + (last_proceeds / 2) as i64 - (first_cost + second_cost) as i64
+    );
+
+    // The account is bound to the signer and the mint
+    let ix = with_position(burn_ix(&env, 1), &creator.pubkey(), &hype_mint);
+    assert_hype_error(
+        env.process(&[ix], &[&trader]).await,
+        HypeError::InvalidAccountKey,
+    );
+}
+
+#[tokio::test]
+async fn positions_follow_switches() {
+    let mut env = TestEnv::setup(spl_token::id()).await;
+    let params = env.params;
+    let creator = env.new_trader(TRADER_FUNDS).await;
+    let trader = env.new_trader(TRADER_FUNDS).await;
+    let wallet = trader.pubkey();
+    let supply = 1_000_000_000;
+    let (doge, doge_mint, doge_program_acc, _) =
+        env.create_token(&creator, 0, "doge", supply).await.unwrap();
+    let (shib, shib_mint, shib_program_acc, _) =
+        env.create_token(&creator, 0, "shib", supply).await.unwrap();
+    let ix = env.mint_ix(
+        &wallet,
+        0,
+        "doge",
+        &doge_mint,
+        &doge_program_acc,
+        supply,
+        0,
+        &[],
+    );
+    let ix = with_position(ix, &wallet, &doge_mint);
+    let cost = -traded(&mut env, &trader, ix).await as u64;
+
+    let from = (doge, doge_mint, doge_program_acc);
+    let to = (shib, shib_mint, shib_program_acc);
+    let amount = supply / 2;
+    let curve = params.curve();
+    let cashout = quote_burn(&curve, 2 * supply, amount)
+        .unwrap()
+        .base_crncy_amount;
+    let budget = cashout - (cashout as f64 * params.switch_fee_rate) as u64;
+    let amount_out = mint_amount_for_funds(&curve, supply, budget).unwrap();
+
+    // A client tracking positions passes those of both tokens
+    let ix = env.switch_ix(&wallet, from, to, amount, 0, &[]);
+    assert_hype_error(
+        env.process(&[ix], &[&trader]).await,
+        HypeError::PositionRequired,
+    );
+    let ix = with_position(
+        env.switch_ix(&wallet, from, to, amount, 0, &[]),
+        &wallet,
+        &shib_mint,
+    );
+    assert_hype_error(
+        env.process(&[ix], &[&trader]).await,
+        HypeError::PositionRequired,
+    );
+    let ix = with_switch_positions(
+        env.switch_ix(&wallet, from, to, amount, 0, &[]),
+        &wallet,
+        &doge_mint,
+        &shib_mint,
+    );
+    env.process(&[ix], &[&trader]).await.unwrap();
+
+    // The sale yields the budget left after the switch fee, which the purchase costs
+    let doge_position = env.position_account(&wallet, &doge_mint).await;
+    assert_eq!(doge_position.quantity, supply - amount);
+    assert_eq!(doge_position.cost_basis, cost - cost / 2);
+    assert_eq!(doge_position.total_proceeds, budget);
+    assert_eq!(
+        doge_position.realized_pnl,
+        budget as i64 - (cost / 2) as i64
+    );
+    let shib_position = env.position_account(&wallet, &shib_mint).await;
+    assert_eq!(shib_position.quantity, amount_out);
+    assert_eq!(shib_position.cost_basis, budget);
+    assert_eq!(shib_position.total_cost, budget);
+}
+
+#[tokio::test]
+async fn prefunded_positions_still_open() {
+    let mut env = TestEnv::setup(spl_token::id()).await;
+    let creator = env.new_trader(TRADER_FUNDS).await;
+    let trader = env.new_trader(TRADER_FUNDS).await;
+    let wallet = trader.pubkey();
+    let (_, hype_mint, hype_program_acc, _) = env
+        .create_token(&creator, 0, "doge", 1_000_000_000)
+        .await
+        .unwrap();
+
+    // A lamport sent to the position address ahead of the first trade does not block it
+    let position = position_address(VERSION, &wallet, &hype_mint);
+    env.fund(&position, 1).await;
+    let ix = env.mint_ix(
+        &wallet,
+        0,
+        "doge",
+        &hype_mint,
+        &hype_program_acc,
+        1_000_000_000,
+        0,
+        &[],
+    );
+    env.process(&[with_position(ix, &wallet, &hype_mint)], &[&trader])
+        .await
+        .unwrap();
+    let account = env.account(&position).await.unwrap();
+    assert_eq!(account.owner, program_id());
+    assert_eq!(account.data.len(), POSITION_ACCOUNT_SIZE);
+    assert_eq!(
+        account.lamports,
+        Rent::default().minimum_balance(POSITION_ACCOUNT_SIZE)
+    );
+    assert_eq!(
+        env.position_account(&wallet, &hype_mint).await.quantity,
+        1_000_000_000
+    );
+}
@@ -0,0 +1,268 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use common::*;
+use hype_watch::{program::error::HypeError, program::*};
+use solana_sdk::{instruction::AccountMeta, signature::Signer};
+
+const TRADER_FUNDS: u64 = 1_000_000_000_000_000;
+
+#[tokio::test]
+async fn trades_fill_candles() {
+    let mut env = TestEnv::setup(spl_token::id()).await;
+    let params = env.params;
+    let curve = params.curve();
+    let creator = env.new_trader(TRADER_FUNDS).await;
+    let trader = env.new_trader(TRADER_FUNDS).await;
+    let wallet = trader.pubkey();
+    let amount = 1_000_000_000;
+    let (token, hype_mint, hype_program_acc, _) =
+        env.create_token(&creator, 0, "doge", amount).await.unwrap();
+    env.init_price_history(&trader, &token, &hype_mint)
+        .await
+        .unwrap();
+    let history_address = price_history_address(VERSION, &hype_mint);
+    let history = env.price_history_account(&hype_mint).await;
+    assert_eq!(history.tag, PRICE_HISTORY_TAG);
+    assert_eq!(history.mint, hype_mint);
+    assert_eq!(history.interval, params.candle_interval);
+    assert_eq!(history.candles_count, 0);
+
+    // A buy opens the first candle
+    let ix = env.mint_ix(
+        &wallet,
+        0,
+        "doge",
+        &hype_mint,
+        &hype_program_acc,
+        2 * amount,
+        0,
+        &[],
+    );
+    env.process(
+        &[with_trade_extras(
+            ix,
+            TRADE_EXTRA_PRICE_HISTORY,
+            &[history_address],
+        )],
+        &[&trader],
+    )
+    .await
+    .unwrap();
+    let history = env.price_history_account(&hype_mint).await;
+    let candle = history.candles[0];
+    assert_eq!(history.candles_count, 1);
+    assert_eq!(history.head, 0);
+    assert_eq!(candle.start_time % params.candle_interval, 0);
+    assert_eq!(candle.trades_count, 1);
+    assert_eq!(candle.open, spot_price(&curve, amount).unwrap());
+    assert_eq!(candle.close, spot_price(&curve, 3 * amount).unwrap());
+    assert_eq!(candle.low, candle.open);
+    assert_eq!(candle.high, candle.close);
+    assert_eq!(
+        candle.base_crncy_volume,
+        expected_mint(&params, amount, 2 * amount, false).base_crncy_amount
+    );
+    assert_eq!(candle.tokens_volume, 2 * amount);
+
+    // A sell in the same interval, passed after a position account
+    let ix = env.burn_ix(
+        &wallet,
+        &token,
+        &hype_mint,
+        &hype_program_acc,
+        amount,
+        0,
+        &[],
+    );
+    let ix = with_trade_extras(
+        ix,
+        TRADE_EXTRA_POSITION | TRADE_EXTRA_PRICE_HISTORY,
+        &[
+            position_address(VERSION, &wallet, &hype_mint),
+            history_address,
+        ],
+    );
+    env.process(&[ix], &[&trader]).await.unwrap();
+    let history = env.price_history_account(&hype_mint).await;
+    let updated = history.candles[0];
+    assert_eq!(history.candles_count, 1);
+    assert_eq!(updated.trades_count, 2);
+    assert_eq!(updated.open, candle.open);
+    assert_eq!(updated.high, candle.high);
+    assert_eq!(updated.close, spot_price(&curve, 2 * amount).unwrap());
+    assert_eq!(updated.tokens_volume, 3 * amount);
+    assert_eq!(
+        env.position_account(&wallet, &hype_mint).await.trades_count,
+        1
+    );
+
+    // The next interval starts a new candle
+    env.advance_clock(params.candle_interval as i64).await;
+    let ix = env.burn_ix(
+        &wallet,
+        &token,
+        &hype_mint,
+        &hype_program_acc,
+        amount,
+        0,
+        &[],
+    );
+    env.process(
+        &[with_trade_extras(
+            ix,
+            TRADE_EXTRA_PRICE_HISTORY,
+            &[history_address],
+        )],
+        &[&trader],
+    )
+    .await
+    .unwrap();
+    let history = env.price_history_account(&hype_mint).await;
+    let candle = history.candles[1];
+    assert_eq!(history.candles_count, 2);
+    assert_eq!(history.head, 1);
+    assert_eq!(
+        candle.start_time,
+        updated.start_time + params.candle_interval
+    );
+    assert_eq!(candle.open, updated.close);
+    assert_eq!(candle.close, spot_price(&curve, amount).unwrap());
+    assert_eq!(candle.low, candle.close);
+    assert_eq!(candle.trades_count, 1);
+}
+
+#[tokio::test]
+async fn price_history_is_bound_to_its_mint() {
+    let mut env = TestEnv::setup(spl_token::id()).await;
+    let creator = env.new_trader(TRADER_FUNDS).await;
+    let (token, hype_mint, hype_program_acc, _) =
+        env.create_token(&creator, 0, "doge", 1_000).await.unwrap();
+    let (other_token, other_mint, _, _) =
+        env.create_token(&creator, 0, "shib", 1_000).await.unwrap();
+    env.init_price_history(&creator, &token, &hype_mint)
+        .await
+        .unwrap();
+    assert_hype_error(
+        env.init_price_history(&creator, &token, &hype_mint).await,
+        HypeError::InvalidPriceHistoryAccount,
+    );
+    assert_hype_error(
+        env.init_price_history(&creator, &other_token, &hype_mint)
+            .await,
+        HypeError::InvalidAccountKey,
+    );
+    let ix = env.burn_ix(
+        &creator.pubkey(),
+        &token,
+        &hype_mint,
+        &hype_program_acc,
+        1,
+        0,
+        &[],
+    );
+    let ix = with_trade_extras(
+        ix,
+        TRADE_EXTRA_PRICE_HISTORY,
+        &[price_history_address(VERSION, &other_mint)],
+    );
+    env.init_price_history(&creator, &other_token, &other_mint)
+        .await
+        .unwrap();
+    assert_hype_error(
+        env.process(&[ix], &[&creator]).await,
+        HypeError::InvalidPriceHistoryAccount,
+    );
+}
+
+#[tokio::test]
+async fn trades_pass_the_price_history_once_it_exists() {
+    let mut env = TestEnv::setup(spl_token::id()).await;
+    let creator = env.new_trader(TRADER_FUNDS).await;
+    let wallet = creator.pubkey();
+    let (token, hype_mint, hype_program_acc, _) = env
+        .create_token(&creator, 0, "doge", 1_000_000_000)
+        .await
+        .unwrap();
+    let burn_ix = |env: &TestEnv| {
+        env.burn_ix(
+            &wallet,
+            &token,
+            &hype_mint,
+            &hype_program_acc,
+            100_000_000,
+            0,
+            &[],
+        )
+    };
+    env.process(&[burn_ix(&env)], &[&creator]).await.unwrap();
+    env.init_price_history(&creator, &token, &hype_mint)
+        .await
+        .unwrap();
+    assert_eq!(env.token_account(&token).await.price_history, 1);
+
+    // Left out or passed undeclared, the history is missing
+    assert_hype_error(
+        env.process(&[burn_ix(&env)], &[&creator]).await,
+        HypeError::PriceHistoryRequired,
+    );
+    let mut ix = burn_ix(&env);
+    ix.accounts.push(AccountMeta::new(
+        price_history_address(VERSION, &hype_mint),
+        false,
+    ));
+    assert_hype_error(
+        env.process(&[ix], &[&creator]).await,
+        HypeError::PriceHistoryRequired,
+    );
+    // Undefined flags are rejected
+    let ix = with_trade_extras(
+        burn_ix(&env),
+        TRADE_EXTRA_PRICE_HISTORY | 8,
+        &[price_history_address(VERSION, &hype_mint)],
+    );
+    assert_hype_error(
+        env.process(&[ix], &[&creator]).await,
+        HypeError::InvalidTradeExtras,
+    );
+    let ix = with_trade_extras(
+        burn_ix(&env),
+        TRADE_EXTRA_PRICE_HISTORY,
+        &[price_history_address(VERSION, &hype_mint)],
+    );
+    env.process(&[ix], &[&creator]).await.unwrap();
+    assert_eq!(env.price_history_account(&hype_mint).await.candles_count, 1);
+
+    // Switches keep the history too
+    let (shib, shib_mint, shib_program_acc, _) = env
+        .create_token(&creator, 0, "shib", 1_000_000_000)
+        .await
+        .unwrap();
+    let switch_ix = |env: &TestEnv| {
+        env.switch_ix(
+            &wallet,
+            (shib, shib_mint, shib_program_acc),
+            (token, hype_mint, hype_program_acc),
+            100_000_000,
+            0,
+            &[],
+        )
+    };
+    assert_hype_error(
+        env.process(&[switch_ix(&env)], &[&creator]).await,
+        HypeError::PriceHistoryRequired,
+    );
+    let ix = with_trade_extras(
+        switch_ix(&env),
+        TRADE_EXTRA_PRICE_HISTORY,
+        &[price_history_address(VERSION, &hype_mint)],
+    );
+    env.process(&[ix], &[&creator]).await.unwrap();
+    let history = env.price_history_account(&hype_mint).await;
+    let trades: u32 = history.candles[..history.candles_count as usize]
+        .iter()
+        .map(|candle| candle.trades_count)
+        .sum();
+    assert_eq!(trades, 2);
+}
@@ -0,0 +1,532 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use common::*;
+use hype_watch::{program::error::HypeError, program::*, state::*};
+use solana_sdk::{
+    instruction::AccountMeta,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+const TRADER_FUNDS: u64 = 1_000_000_000_000_000;
+
+async fn check_ref_fees(env: &mut TestEnv, referrer: &Pubkey, trader: &Pubkey, ref_fees: u64) {
+    let referrer_account = env.referrer_account(referrer).await;
+    assert_eq!(referrer_account.tag, REFERRER_TAG);
+    assert_eq!(referrer_account.wallet, *referrer);
+    assert_eq!(referrer_account.fees, ref_fees);
+    assert_eq!(referrer_account.all_time_fees, ref_fees);
+    assert_eq!(env.root_account().await.ref_fees, ref_fees);
+    assert_eq!(env.client_account(trader).await.ref_paid, ref_fees);
+}
+
+async fn referral(base_token_program: Pubkey) {
+    let mut env = TestEnv::setup(base_token_program).await;
+    let params = env.params;
+    let referrer = env.new_trader(0).await;
+    let trader = env.new_trader(TRADER_FUNDS).await;
+    let vault = env.vault.pubkey();
+    env.open_referrer_account(&referrer).await;
+
+    // The client is created with an active referral
+    let amount = 1_000_000;
+    let mint = expected_referred_mint(&params, 0, amount, true, 0);
+    assert!(mint.ref_fees > 0);
+    let (token, hype_mint, hype_program_acc, _) = env
+        .create_referred_token(&trader, 0, "doge", amount, &[referrer.pubkey()])
+        .await
+        .unwrap();
+    check_ref_fees(
+        &mut env,
+        &referrer.pubkey(),
+        &trader.pubkey(),
+        mint.ref_fees,
+    )
+    .await;
+    let trader_base = env.base_ata(&trader.pubkey());
+    assert_eq!(
+        env.balance(&trader_base).await,
+        TRADER_FUNDS - mint.base_crncy_amount - mint.fees
+    );
+    let mut vault_balance = mint.base_crncy_amount + mint.fees;
+    assert_eq!(env.balance(&vault).await, vault_balance);
+    env.check_invariants(&[(token, hype_mint)]).await.unwrap();
+
+    // Burns accrue to the same referrer
+    let burn = expected_referred_burn(&params, amount, amount / 2, 0);
+    let ix = env.burn_ix(
+        &trader.pubkey(),
+        &token,
+        &hype_mint,
+        &hype_program_acc,
+        amount / 2,
+        0,
+        &[referrer.pubkey()],
+    );
+    env.process(&[ix], &[&trader]).await.unwrap();
+    let ref_fees = mint.ref_fees + burn.ref_fees;
+    check_ref_fees(&mut env, &referrer.pubkey(), &trader.pubkey(), ref_fees).await;
+    vault_balance -= burn.base_crncy_amount - burn.fees;
+    assert_eq!(env.balance(&vault).await, vault_balance);
+    env.check_invariants(&[(token, hype_mint)]).await.unwrap();
+
+    // While the referral is active the trade has to name the referrer
+    let ix = env.burn_ix(
+        &trader.pubkey(),
+        &token,
+        &hype_mint,
+        &hype_program_acc,
+        1,
+        0,
+        &[],
+    );
+    assert_hype_error(
+        env.process(&[ix], &[&trader]).await,
+        HypeError::InvalidRefAddress,
+    );
+
+    // Only the referrer wallet can claim
+    assert_hype_error(
+        env.claim_referral_fees(&trader).await,
+        HypeError::InvalidReferrerAccount,
+    );
+
+    let logs = env.claim_referral_fees(&referrer).await.unwrap();
+    let referrer_base = env.base_ata(&referrer.pubkey());
+    assert_eq!(env.balance(&referrer_base).await, ref_fees);
+    let referrer_account = env.referrer_account(&referrer.pubkey()).await;
+    assert_eq!(referrer_account.fees, 0);
+    assert_eq!(referrer_account.all_time_fees, ref_fees);
+    assert_eq!(referrer_account.all_time_claimed, ref_fees);
+    assert_eq!(env.root_account().await.ref_fees, 0);
+    let claims = events_with_tag(&logs, 6);
+    assert_eq!(claims.len(), 1);
+    assert_eq!(claims[0][1], referrer.pubkey().to_bytes());
+    assert_eq!(field_u64(&claims[0], 2), ref_fees);
+    assert_eq!(field_u64(&claims[0], 3), ref_fees);
+    vault_balance -= ref_fees;
+    assert_eq!(env.balance(&vault).await, vault_balance);
+    env.check_invariants(&[(token, hype_mint)]).await.unwrap();
+
+    // Nothing left to claim
+    let logs = env.claim_referral_fees(&referrer).await.unwrap();
+    assert!(events_with_tag(&logs, 6).is_empty());
+    assert_eq!(env.balance(&referrer_base).await, ref_fees);
+}
+
+/// First trade of `trader`, signing up with the referral chain `referrers`.
+async fn referred_mint(
+    env: &mut TestEnv,
+    trader: &Keypair,
+    hype_mint: &Pubkey,
+    hype_program_acc: &Pubkey,
+    amount: u64,
+    referrers: &[Pubkey],
+) {
+    let mut ix = env.mint_ix(
+        &trader.pubkey(),
+        0,
+        "doge",
+        hype_mint,
+        hype_program_acc,
+        amount,
+        0,
+        referrers,
+    );
+    if let Some(referrer) = referrers.first() {
+        ix = TestEnv::signing_up(ix, referrer);
+    }
+    env.process(&[ix], &[trader]).await.unwrap();
+}
+
+#[tokio::test]
+async fn referral_chain() {
+    let mut env = TestEnv::setup(spl_token::id()).await;
+    let params = env.params;
+    let a = env.new_trader(TRADER_FUNDS).await;
+    let b = env.new_trader(TRADER_FUNDS).await;
+    let c = env.new_trader(TRADER_FUNDS).await;
+    let d = env.new_trader(TRADER_FUNDS).await;
+    let (a_key, b_key, c_key) = (a.pubkey(), b.pubkey(), c.pubkey());
+    let amount = 1_000_000;
+    for referrer in [&a, &b, &c] {
+        env.open_referrer_account(referrer).await;
+    }
+
+    // a has never traded, so b gets no uplines
+    let (token, hype_mint, hype_program_acc, _) = env
+        .create_referred_token(&b, 0, "doge", amount, &[a_key])
+        .await
+        .unwrap();
+    assert_eq!(
+        env.client_account(&b_key).await.ref_uplines,
+        [Pubkey::default(); 2]
+    );
+
+    // c inherits b's referrer as its second level
+    referred_mint(
+        &mut env,
+        &c,
+        &hype_mint,
+        &hype_program_acc,
+        amount,
+        &[b_key, a_key],
+    )
+    .await;
+    let client = env.client_account(&c_key).await;
+    assert_eq!(client.ref_uplines, [a_key, Pubkey::default()]);
+    assert_eq!(client.ref_upline_ratios, params.ref_upline_ratios);
+
+    // d reaches all three levels and pays each of them
+    let supply = env.token_account(&token).await.supply;
+    let quote = expected_referred_mint(&params, supply, amount, false, 2);
+    assert!(quote.upline_fees.iter().all(|fees| *fees > 0));
+    let before = [
+        env.referrer_account(&c_key).await.fees,
+        env.referrer_account(&b_key).await.fees,
+        env.referrer_account(&a_key).await.fees,
+    ];
+    let ref_fees_before = env.root_account().await.ref_fees;
+    referred_mint(
+        &mut env,
+        &d,
+        &hype_mint,
+        &hype_program_acc,
+        amount,
+        &[c_key, b_key, a_key],
+    )
+    .await;
+    let client = env.client_account(&d.pubkey()).await;
+    assert_eq!(client.ref_uplines, [b_key, a_key]);
+    let ref_fees = quote.ref_fees + quote.upline_fees[0] + quote.upline_fees[1];
+    assert_eq!(client.ref_paid, ref_fees);
+    assert_eq!(
+        env.referrer_account(&c_key).await.fees,
+        before[0] + quote.ref_fees
+    );
+    assert_eq!(
+        env.referrer_account(&b_key).await.fees,
+        before[1] + quote.upline_fees[0]
+    );
+    assert_eq!(
+        env.referrer_account(&a_key).await.fees,
+        before[2] + quote.upline_fees[1]
+    );
+    assert_eq!(
+        env.root_account().await.ref_fees,
+        ref_fees_before + ref_fees
+    );
+    env.check_invariants(&[(token, hype_mint)]).await.unwrap();
+
+    // Later trades still have to pass every upline
+    let ix = env.mint_ix(
+        &d.pubkey(),
+        0,
+        "doge",
+        &hype_mint,
+        &hype_program_acc,
+        amount,
+        0,
+        &[c_key, b_key],
+    );
+    assert!(env.process(&[ix], &[&d]).await.is_err());
+
+    // A chain leading back to the new client ends there
+    referred_mint(
+        &mut env,
+        &a,
+        &hype_mint,
+        &hype_program_acc,
+        amount,
+        &[b_key],
+    )
+    .await;
+    assert_eq!(
+        env.client_account(&a_key).await.ref_uplines,
+        [Pubkey::default(); 2]
+    );
+}
+
+#[tokio::test]
+async fn referral_codes() {
+    let mut env = TestEnv::setup(spl_token::id()).await;
+    let params = env.params;
+    let referrer = env.new_trader(TRADER_FUNDS).await;
+    let trader = env.new_trader(TRADER_FUNDS).await;
+    let amount = 1_000_000;
+    let (token, hype_mint, hype_program_acc, _) = env
+        .create_token(&referrer, 0, "doge", amount)
+        .await
+        .unwrap();
+
+    // Codes are case-insensitive and need a client account
+    let code = ref_code_address(VERSION, "alice-1");
+    assert_hype_error(
+        env.register_ref_code(&trader, "alice-1", &code).await,
+        HypeError::InvalidClientAccount,
+    );
+    let logs = env
+        .register_ref_code(&referrer, "Alice-1", &code)
+        .await
+        .unwrap();
+    let registered = events_with_tag(&logs, 7);
+    assert_eq!(registered.len(), 1);
+    assert_eq!(registered[0][1], referrer.pubkey().to_bytes());
+    assert_eq!(registered[0][2], fixed::<32>("alice-1"));
+    let data = env.account(&code).await.unwrap().data;
+    assert_eq!(data.len(), REF_CODE_ACCOUNT_SIZE);
+    let code_account: RefCodeAccount = unsafe { read_struct(&data) };
+    assert_eq!(code_account.tag, REF_CODE_TAG);
+    assert_eq!(code_account.wallet, referrer.pubkey());
+    assert_eq!(code_account.code, fixed::<32>("alice-1"));
+    assert_hype_error(
+        env.register_ref_code(&referrer, "ALICE-1", &code).await,
+        HypeError::RefCodeAlreadyRegistered,
+    );
+    assert_hype_error(
+        env.register_ref_code(&referrer, "alice 1", &ref_code_address(VERSION, "alice 1"))
+            .await,
+        HypeError::InvalidRefCode,
+    );
+    // An empty code registers the client nickname
+    env.change_nickname(&referrer, "Trader").await.unwrap();
+    let nickname_code = ref_code_address(VERSION, "trader");
+    env.register_ref_code(&referrer, "", &nickname_code)
+        .await
+        .unwrap();
+
+    // A code account in place of the referrer wallet resolves to the wallet
+    let supply = env.token_account(&token).await.supply;
+    let quote = expected_referred_mint(&params, supply, amount, false, 0);
+    let mut ix = env.mint_ix(
+        &trader.pubkey(),
+        0,
+        "doge",
+        &hype_mint,
+        &hype_program_acc,
+        amount,
+        0,
+        &[referrer.pubkey()],
+    );
+    ix.accounts[15].pubkey = code;
+    let ix = TestEnv::signing_up(ix, &referrer.pubkey());
+    env.process(&[ix], &[&trader]).await.unwrap();
+    let client = env.client_account(&trader.pubkey()).await;
+    assert_eq!(client.ref_address, referrer.pubkey());
+    assert_eq!(client.ref_paid, quote.ref_fees);
+    let referrer_account = env.referrer_account(&referrer.pubkey()).await;
+    assert_eq!(referrer_account.fees, quote.ref_fees);
+
+    // Either the wallet or any of its codes names the referrer later on
+    let ix = env.burn_ix(
+        &trader.pubkey(),
+        &token,
+        &hype_mint,
+        &hype_program_acc,
+        1,
+        0,
+        &[referrer.pubkey()],
+    );
+    env.process(&[ix], &[&trader]).await.unwrap();
+    let mut ix = env.burn_ix(
+        &trader.pubkey(),
+        &token,
+        &hype_mint,
+        &hype_program_acc,
+        1,
+        0,
+        &[referrer.pubkey()],
+    );
+    ix.accounts[15].pubkey = nickname_code;
+    env.process(&[ix], &[&trader]).await.unwrap();
+
+    // Only the owner releases a code, and gets its rent back
+    assert_hype_error(
+        env.release_ref_code(&trader, &code).await,
+        HypeError::InvalidRefCode,
+    );
+    let rent = env.account(&code).await.unwrap().lamports;
+    let balance = env.account(&referrer.pubkey()).await.unwrap().lamports;
+    let logs = env.release_ref_code(&referrer, &code).await.unwrap();
+    assert_eq!(events_with_tag(&logs, 8).len(), 1);
+    assert!(env.account(&code).await.is_none());
+    assert_eq!(
+        env.account(&referrer.pubkey()).await.unwrap().lamports,
+        balance + rent
+    );
+    // A released code can be taken again
+    env.register_ref_code(&trader, "alice-1", &code)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn set_referrer_after_sign_up() {
+    let mut env = TestEnv::setup(spl_token::id()).await;
+    let params = env.params;
+    let a = env.new_trader(TRADER_FUNDS).await;
+    let b = env.new_trader(TRADER_FUNDS).await;
+    let c = env.new_trader(0).await;
+    let d = env.new_trader(TRADER_FUNDS).await;
+    let (a_key, b_key, c_key) = (a.pubkey(), b.pubkey(), c.pubkey());
+    let amount = 1_000_000;
+
+    // Self-referral on sign-up does not activate a referral
+    let (token, hype_mint, hype_program_acc, _) = env
+        .create_referred_token(&a, 0, "doge", amount, &[a_key])
+        .await
+        .unwrap();
+    let client = env.client_account(&a_key).await;
+    assert_eq!(client.ref_stop, client.creation_time);
+    referred_mint(
+        &mut env,
+        &b,
+        &hype_mint,
+        &hype_program_acc,
+        amount,
+        &[a_key],
+    )
+    .await;
+
+    assert_hype_error(
+        env.set_referrer(&a, &a_key, &a_key).await,
+        HypeError::SelfReferral,
+    );
+    assert_hype_error(
+        env.set_referrer(&a, &b_key, &b_key).await,
+        HypeError::ReferralCycle,
+    );
+    let logs = env.set_referrer(&a, &c_key, &c_key).await.unwrap();
+    let events = events_with_tag(&logs, 9);
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0][3], c_key.to_bytes());
+    let client = env.client_account(&a_key).await;
+    assert_eq!(client.ref_address, c_key);
+    assert_eq!(client.ref_stop, client.time + params.ref_duration);
+    assert_hype_error(
+        env.set_referrer(&a, &d.pubkey(), &d.pubkey()).await,
+        HypeError::ReferralAlreadyActive,
+    );
+
+    // From now on a's trades pay c
+    env.open_referrer_account(&c).await;
+    let supply = env.token_account(&token).await.supply;
+    let quote = expected_referred_mint(&params, supply, amount, false, 0);
+    let ix = env.mint_ix(
+        &a_key,
+        0,
+        "doge",
+        &hype_mint,
+        &hype_program_acc,
+        amount,
+        0,
+        &[c_key],
+    );
+    env.process(&[ix], &[&a]).await.unwrap();
+    assert_eq!(env.referrer_account(&c_key).await.fees, quote.ref_fees);
+
+    // Too late once the grace period is over
+    referred_mint(&mut env, &d, &hype_mint, &hype_program_acc, amount, &[]).await;
+    env.advance_clock(params.ref_grace_period as i64 + 1).await;
+    assert_hype_error(
+        env.set_referrer(&d, &c_key, &c_key).await,
+        HypeError::RefGracePeriodExpired,
+    );
+}
+
+#[tokio::test]
+async fn trades_open_missing_referrer_accounts() {
+    let mut env = TestEnv::setup(spl_token::id()).await;
+    let params = env.params;
+    let referrer = env.new_trader(0).await;
+    let trader = env.new_trader(TRADER_FUNDS).await;
+    let referrer_acc = referrer_address(VERSION, &referrer.pubkey());
+    let amount = 1_000_000;
+
+    // The first referred trade opens the referrer account and books the referral fees
+    assert!(env.account(&referrer_acc).await.is_none());
+    let mint = expected_referred_mint(&params, 0, amount, true, 0);
+    assert!(mint.ref_fees > 0);
+    let (token, hype_mint, hype_program_acc, _) = env
+        .create_referred_token(&trader, 0, "doge", amount, &[referrer.pubkey()])
+        .await
+        .unwrap();
+    assert_eq!(
+        env.account(&referrer_acc).await.unwrap().owner,
+        program_id()
+    );
+    check_ref_fees(
+        &mut env,
+        &referrer.pubkey(),
+        &trader.pubkey(),
+        mint.ref_fees,
+    )
+    .await;
+    let root = env.root_account().await;
+    assert_eq!(root.fees, mint.operator_fees);
+    env.check_invariants(&[(token, hype_mint)]).await.unwrap();
+
+    // A referrer account that is not the referrer's PDA is still rejected
+    let mut ix = env.burn_ix(
+        &trader.pubkey(),
+        &token,
+        &hype_mint,
+        &hype_program_acc,
+        amount / 2,
+        0,
+        &[referrer.pubkey()],
+    );
+    ix.accounts[16] = AccountMeta::new(referrer_address(VERSION, &trader.pubkey()), false);
+    assert_hype_error(
+        env.process(&[ix], &[&trader]).await,
+        HypeError::InvalidAccountKey,
+    );
+}
+
+#[tokio::test]
+async fn referral_spl_token() {
+    referral(spl_token::id()).await;
+}
+
+#[tokio::test]
+async fn referral_token_2022() {
+    referral(spl_token_2022::id()).await;
+}
+
+#[tokio::test]
+async fn referred_sellers_keep_the_referral_discount() {
+    let mut env = TestEnv::setup(spl_token::id()).await;
+    let params = env.params;
+    let referrer = env.new_trader(0).await;
+    let trader = env.new_trader(TRADER_FUNDS).await;
+    env.open_referrer_account(&referrer).await;
+    let amount = 1_000_000;
+    let (token, hype_mint, hype_program_acc, _) = env
+        .create_referred_token(&trader, 0, "doge", amount, &[referrer.pubkey()])
+        .await
+        .unwrap();
+
+    // The sale pays the discounted fees, not the full trading fee of the quote
+    let full = expected_burn(&params, amount, amount / 2);
+    let burn = expected_referred_burn(&params, amount, amount / 2, 0);
+    assert!(burn.fees < full.fees);
+    let trader_base = env.base_ata(&trader.pubkey());
+    let balance = env.balance(&trader_base).await;
+    let ix = env.burn_ix(
+        &trader.pubkey(),
+        &token,
+        &hype_mint,
+        &hype_program_acc,
+        amount / 2,
+        0,
+        &[referrer.pubkey()],
+    );
+    env.process(&[ix], &[&trader]).await.unwrap();
+    assert_eq!(
+        env.balance(&trader_base).await,
+        balance + burn.base_crncy_amount - burn.fees
+    );
+    env.check_invariants(&[(token, hype_mint)]).await.unwrap();
+}
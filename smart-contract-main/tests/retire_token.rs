@@ -0,0 +1,92 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use common::*;
+use hype_watch::program::error::HypeError;
+use solana_sdk::signature::Signer;
+
+const TRADER_FUNDS: u64 = 1_000_000_000_000_000;
+
+#[tokio::test]
+async fn retire_idle_token() {
+    let mut env = TestEnv::new(spl_token::id()).await;
+    env.params.token_idle_period = 3600;
+    env.initialize_holder().await.unwrap();
+    env.add_operator(10).await.unwrap();
+    env.initialize_root().await.unwrap();
+    env.add_network("ethereum", "abcdefghijklmnopqrstuvwxyz0123456789", 24)
+        .await
+        .unwrap();
+    let creator = env.new_trader(TRADER_FUNDS).await;
+    let amount = 1_000_000;
+    let (token, hype_mint, hype_program_acc, _) =
+        env.create_token(&creator, 0, "doge", amount).await.unwrap();
+
+    // Only an idle token without supply retires
+    assert_hype_error(
+        env.retire_token(&token, &hype_mint, &hype_program_acc, &creator.pubkey())
+            .await,
+        HypeError::InvalidTokenSupply,
+    );
+    let ix = env.burn_ix(
+        &creator.pubkey(),
+        &token,
+        &hype_mint,
+        &hype_program_acc,
+        amount,
+        0,
+        &[],
+    );
+    env.process(&[ix], &[&creator]).await.unwrap();
+    assert_hype_error(
+        env.retire_token(&token, &hype_mint, &hype_program_acc, &creator.pubkey())
+            .await,
+        HypeError::TokenNotIdle,
+    );
+    env.advance_clock(3600).await;
+    assert_hype_error(
+        env.retire_token(
+            &token,
+            &hype_mint,
+            &hype_program_acc,
+            &env.operator.pubkey(),
+        )
+        .await,
+        HypeError::InvalidTokenCreator,
+    );
+
+    let mut rent = 0;
+    for address in [token, hype_mint, hype_program_acc] {
+        rent += env.account(&address).await.unwrap().lamports;
+    }
+    let balance = env.account(&creator.pubkey()).await.unwrap().lamports;
+    let logs = env
+        .retire_token(&token, &hype_mint, &hype_program_acc, &creator.pubkey())
+        .await
+        .unwrap();
+    let event = &events_with_tag(&logs, 12)[0];
+    assert_eq!(field_u64(event, 1), 0);
+    assert_eq!(event[3], hype_mint.to_bytes());
+    for address in [token, hype_mint, hype_program_acc] {
+        assert!(env.account(&address).await.is_none());
+    }
+    assert_eq!(
+        env.account(&creator.pubkey()).await.unwrap().lamports,
+        balance + rent
+    );
+    let root = env.root_account().await;
+    assert_eq!(root.retired_tokens_count, 1);
+    env.check_invariants(&[]).await.unwrap();
+
+    // The next mint on the address starts a new token
+    let (new_token, new_mint, _, logs) =
+        env.create_token(&creator, 0, "doge", amount).await.unwrap();
+    assert_eq!(new_token, token);
+    assert_eq!(field_u64(&events_with_tag(&logs, 3)[0], 3), 1);
+    let token_account = env.token_account(&token).await;
+    assert_eq!(token_account.id, 1);
+    assert_eq!(token_account.mint, new_mint);
+    assert_eq!(token_account.supply, amount);
+    env.check_invariants(&[(token, new_mint)]).await.unwrap();
+}
@@ -0,0 +1,133 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use common::*;
+use hype_watch::{program::error::HypeError, program::*};
+use solana_sdk::signature::Signer;
+
+const TRADER_FUNDS: u64 = 1_000_000_000_000_000;
+
+#[tokio::test]
+async fn switch_moves_value_between_tokens() {
+    let mut env = TestEnv::setup(spl_token::id()).await;
+    let params = env.params;
+    let trader = env.new_trader(TRADER_FUNDS).await;
+    let wallet = trader.pubkey();
+    let supply = 1_000_000_000;
+    let (doge, doge_mint, doge_program_acc, _) =
+        env.create_token(&trader, 0, "doge", supply).await.unwrap();
+    let (shib, shib_mint, shib_program_acc, _) =
+        env.create_token(&trader, 0, "shib", supply).await.unwrap();
+    let from = (doge, doge_mint, doge_program_acc);
+    let to = (shib, shib_mint, shib_program_acc);
+    let base = env.base_ata(&wallet);
+    let base_before = env.balance(&base).await;
+    let tvl_before = env.root_account().await.tvl;
+
+    let amount = supply / 2;
+    let curve = params.curve();
+    let cashout = quote_burn(&curve, supply, amount)
+        .unwrap()
+        .base_crncy_amount;
+    let fee = (cashout as f64 * params.switch_fee_rate) as u64;
+    let amount_out = mint_amount_for_funds(&curve, supply, cashout - fee).unwrap();
+    let cost = quote_mint(&curve, supply, amount_out)
+        .unwrap()
+        .base_crncy_amount;
+
+    // The min-out bound covers the whole switch
+    let ix = env.switch_ix(&wallet, from, to, amount, amount_out + 1, &[]);
+    assert_hype_error(
+        env.process(&[ix], &[&trader]).await,
+        HypeError::MaxTradeCostExceeded,
+    );
+    let ix = env.switch_ix(&wallet, from, to, amount, amount_out, &[]);
+    env.process(&[ix], &[&trader]).await.unwrap();
+
+    assert_eq!(env.balance(&base).await, base_before);
+    assert_eq!(
+        env.balance(&env.hype_ata(&wallet, &doge_mint)).await,
+        supply - amount
+    );
+    assert_eq!(
+        env.balance(&env.hype_ata(&wallet, &shib_mint)).await,
+        supply + amount_out
+    );
+    assert_eq!(env.token_account(&doge).await.supply, supply - amount);
+    assert_eq!(env.token_account(&shib).await.supply, supply + amount_out);
+    assert!(cost <= cashout - fee);
+    assert_eq!(env.root_account().await.tvl, tvl_before - cashout + cost);
+    env.check_invariants(&[(doge, doge_mint), (shib, shib_mint)])
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn switch_into_the_same_token_fails() {
+    let mut env = TestEnv::setup(spl_token::id()).await;
+    let trader = env.new_trader(TRADER_FUNDS).await;
+    let wallet = trader.pubkey();
+    let (doge, doge_mint, doge_program_acc, _) = env
+        .create_token(&trader, 0, "doge", 1_000_000)
+        .await
+        .unwrap();
+    let from = (doge, doge_mint, doge_program_acc);
+
+    let ix = env.switch_ix(&wallet, from, from, 1_000, 0, &[]);
+    assert_hype_error(
+        env.process(&[ix], &[&trader]).await,
+        HypeError::InvalidTokenAccount,
+    );
+}
+
+#[tokio::test]
+async fn referred_switch_keeps_the_vault_balanced() {
+    let mut env = TestEnv::setup(spl_token::id()).await;
+    let params = env.params;
+    let curve = params.curve();
+    let referrer = env.new_trader(0).await;
+    let creator = env.new_trader(TRADER_FUNDS).await;
+    let trader = env.new_trader(TRADER_FUNDS).await;
+    let wallet = trader.pubkey();
+    let referrers = [referrer.pubkey()];
+    let supply = 1_000_000_000;
+    env.open_referrer_account(&referrer).await;
+    let (doge, doge_mint, doge_program_acc, _) = env
+        .create_referred_token(&trader, 0, "doge", supply, &referrers)
+        .await
+        .unwrap();
+    let (shib, shib_mint, shib_program_acc, _) =
+        env.create_token(&creator, 0, "shib", supply).await.unwrap();
+    let from = (doge, doge_mint, doge_program_acc);
+    let to = (shib, shib_mint, shib_program_acc);
+
+    // The discounted fee leaves more to buy with than the full one
+    let amount = supply / 2;
+    let cashout = quote_burn(&curve, supply, amount)
+        .unwrap()
+        .base_crncy_amount;
+    let fee = (cashout as f64 * params.switch_fee_rate) as u64;
+    let split = split_fees(
+        fee,
+        params.fee_ratio,
+        Some((params.ref_discount, params.ref_ratio)),
+    );
+    let amount_out = mint_amount_for_funds(&curve, supply, cashout - split.total()).unwrap();
+    assert!(amount_out > mint_amount_for_funds(&curve, supply, cashout - fee).unwrap());
+    let ref_fees = env.referrer_account(&referrer.pubkey()).await.fees;
+    let ix = env.switch_ix(&wallet, from, to, amount, amount_out, &referrers);
+    env.process(&[ix], &[&trader]).await.unwrap();
+
+    assert_eq!(
+        env.balance(&env.hype_ata(&wallet, &shib_mint)).await,
+        amount_out
+    );
+    assert_eq!(
+        env.referrer_account(&referrer.pubkey()).await.fees,
+        ref_fees + split.ref_fees
+    );
+    env.check_invariants(&[(doge, doge_mint), (shib, shib_mint)])
+        .await
+        .unwrap();
+}
@@ -0,0 +1,69 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use common::*;
+use hype_watch::{program::error::HypeError, program::*};
+use solana_sdk::signature::Signer;
+
+const TRADER_FUNDS: u64 = 1_000_000_000_000_000;
+
+fn assert_close(actual: f64, expected: f64) {
+    assert!(
+        (actual - expected).abs() <= expected.abs() * 1e-12,
+        "{} != {}",
+        actual,
+        expected
+    );
+}
+
+#[tokio::test]
+async fn twap_weighs_prices_by_time() {
+    let mut env = TestEnv::setup(spl_token::id()).await;
+    let curve = env.params.curve();
+    let trader = env.new_trader(TRADER_FUNDS).await;
+    let amount = 1_000_000_000;
+    let (token, hype_mint, hype_program_acc, _) =
+        env.create_token(&trader, 0, "doge", amount).await.unwrap();
+    let first_price = spot_price(&curve, amount).unwrap();
+    assert_hype_error(
+        env.twap(&token, 0).await.map(|_| vec![]),
+        HypeError::TwapUnavailable,
+    );
+
+    env.advance_clock(100).await;
+    let (price, elapsed, spot) = env.twap(&token, 60).await.unwrap();
+    assert_close(price, first_price);
+    assert_eq!(elapsed, 100);
+    assert_eq!(spot, first_price);
+
+    // A large buy moves the spot price but not the average of the past
+    let ix = env.mint_ix(
+        &trader.pubkey(),
+        0,
+        "doge",
+        &hype_mint,
+        &hype_program_acc,
+        100 * amount,
+        0,
+        &[],
+    );
+    env.process(&[ix], &[&trader]).await.unwrap();
+    let second_price = spot_price(&curve, 101 * amount).unwrap();
+    let (price, elapsed, spot) = env.twap(&token, 60).await.unwrap();
+    assert_close(price, first_price);
+    assert_eq!(elapsed, 100);
+    assert_eq!(spot, second_price);
+
+    env.advance_clock(100).await;
+    let (price, elapsed, _) = env.twap(&token, 150).await.unwrap();
+    assert_close(price, (first_price + second_price) / 2.0);
+    assert_eq!(elapsed, 200);
+    assert_hype_error(
+        env.twap(&token, 1000).await.map(|_| vec![]),
+        HypeError::TwapUnavailable,
+    );
+    let token_account = env.token_account(&token).await;
+    assert_eq!(token_account.observations_count, 1);
+    assert_eq!(token_account.twap_price, second_price);
+}
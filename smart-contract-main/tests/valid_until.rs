@@ -0,0 +1,80 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use common::*;
+use hype_watch::program::error::HypeError;
+use solana_sdk::signature::Signer;
+
+const TRADER_FUNDS: u64 = 1_000_000_000_000_000;
+
+#[tokio::test]
+async fn trades_past_their_deadline_fail() {
+    let mut env = TestEnv::setup(spl_token::id()).await;
+    let trader = env.new_trader(TRADER_FUNDS).await;
+    let wallet = trader.pubkey();
+    let amount = 1_000_000;
+    let (token, hype_mint, hype_program_acc, _) =
+        env.create_token(&trader, 0, "doge", amount).await.unwrap();
+    let mint_ix = |env: &TestEnv, amount: u64| {
+        env.mint_ix(
+            &wallet,
+            0,
+            "doge",
+            &hype_mint,
+            &hype_program_acc,
+            amount,
+            0,
+            &[],
+        )
+    };
+    let burn_ix = |env: &TestEnv, amount: u64| {
+        env.burn_ix(
+            &wallet,
+            &token,
+            &hype_mint,
+            &hype_program_acc,
+            amount,
+            0,
+            &[],
+        )
+    };
+    let clock = env.clock().await;
+    let slot = clock.slot;
+    let time = clock.unix_timestamp as u32;
+
+    // Deadlines still ahead, or left at 0, pass
+    let ix = with_valid_until(mint_ix(&env, amount), slot + 100, time + 60);
+    env.process(&[ix], &[&trader]).await.unwrap();
+    let ix = with_valid_until(burn_ix(&env, amount), 0, time + 60);
+    env.process(&[ix], &[&trader]).await.unwrap();
+    let ix = with_valid_until(mint_ix(&env, amount), slot + 100, 0);
+    env.process(&[ix], &[&trader]).await.unwrap();
+
+    // Either deadline alone rejects a late trade
+    env.advance_clock(120).await;
+    let ix = with_valid_until(mint_ix(&env, amount), 0, time + 60);
+    assert_hype_error(
+        env.process(&[ix], &[&trader]).await,
+        HypeError::TradeExpired,
+    );
+    let ix = with_valid_until(burn_ix(&env, amount), 0, time + 60);
+    assert_hype_error(
+        env.process(&[ix], &[&trader]).await,
+        HypeError::TradeExpired,
+    );
+    let slot = env.clock().await.slot;
+    let ix = with_valid_until(burn_ix(&env, amount), slot.saturating_sub(1), 0);
+    assert_hype_error(
+        env.process(&[ix], &[&trader]).await,
+        HypeError::TradeExpired,
+    );
+
+    // A truncated deadline is malformed
+    let mut ix = burn_ix(&env, amount);
+    ix.data.extend_from_slice(&[1, 0, 0, 0]);
+    assert_hype_error(
+        env.process(&[ix], &[&trader]).await,
+        HypeError::InvalidDataLength,
+    );
+}
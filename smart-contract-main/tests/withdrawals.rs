@@ -0,0 +1,105 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use common::*;
+use hype_watch::program::error::HypeError;
+use solana_sdk::{instruction::AccountMeta, pubkey::Pubkey, signature::Signer};
+
+const TRADER_FUNDS: u64 = 1_000_000_000_000_000;
+
+async fn traded_env(base_token_program: Pubkey) -> TestEnv {
+    let mut env = TestEnv::setup(base_token_program).await;
+    let trader = env.new_trader(TRADER_FUNDS).await;
+    env.create_token(&trader, 0, "doge", 1_000_000)
+        .await
+        .unwrap();
+    env
+}
+
+async fn pays_fee_wallets(base_token_program: Pubkey) {
+    let mut env = traded_env(base_token_program).await;
+    let root = env.root_account().await;
+    assert!(root.fees > 0 && root.holder_fees > 0);
+    let vault = env.vault.pubkey();
+    let vault_balance = env.balance(&vault).await;
+
+    // The fee wallet ATAs are created under the base currency's own token program
+    env.withdraw_operator_funds().await.unwrap();
+    let fee_wallet_ata = env.base_ata(&env.fee_wallet.pubkey());
+    assert_eq!(
+        env.account(&fee_wallet_ata).await.unwrap().owner,
+        base_token_program
+    );
+    assert_eq!(env.balance(&fee_wallet_ata).await, root.fees);
+
+    env.withdraw_holder_funds().await.unwrap();
+    let holder_wallet_ata = env.base_ata(&env.holder_wallet.pubkey());
+    assert_eq!(
+        env.account(&holder_wallet_ata).await.unwrap().owner,
+        base_token_program
+    );
+    assert_eq!(env.balance(&holder_wallet_ata).await, root.holder_fees);
+
+    let root_after = env.root_account().await;
+    assert_eq!((root_after.fees, root_after.holder_fees), (0, 0));
+    assert_eq!(
+        env.balance(&vault).await,
+        vault_balance - root.fees - root.holder_fees
+    );
+}
+
+#[tokio::test]
+async fn withdrawals_pay_spl_token_fee_wallets() {
+    pays_fee_wallets(spl_token::id()).await;
+}
+
+#[tokio::test]
+async fn withdrawals_pay_token_2022_fee_wallets() {
+    pays_fee_wallets(spl_token_2022::id()).await;
+}
+
+#[tokio::test]
+async fn withdrawals_reject_foreign_vault() {
+    let mut env = traded_env(spl_token_2022::id()).await;
+    let other = env.new_trader(TRADER_FUNDS).await;
+    let foreign = env.base_ata(&other.pubkey());
+
+    let mut ix = env.withdraw_operator_funds_ix();
+    ix.accounts[3] = AccountMeta::new(foreign, false);
+    let operator = env.operator.insecure_clone();
+    assert_hype_error(
+        env.process(&[ix], &[&operator]).await,
+        HypeError::InvalidBaseCrncyProgramAddress,
+    );
+
+    let mut ix = env.withdraw_holder_funds_ix();
+    ix.accounts[4] = AccountMeta::new(foreign, false);
+    let admin = env.holder_admin.insecure_clone();
+    assert_hype_error(
+        env.process(&[ix], &[&admin]).await,
+        HypeError::InvalidBaseCrncyProgramAddress,
+    );
+}
+
+#[tokio::test]
+async fn withdrawals_reject_other_token_program() {
+    let mut env = traded_env(spl_token_2022::id()).await;
+
+    let mut ix = env.withdraw_operator_funds_ix();
+    ix.accounts[6] = AccountMeta::new_readonly(spl_token::id(), false);
+    let operator = env.operator.insecure_clone();
+    assert_hype_error(
+        env.process(&[ix], &[&operator]).await,
+        HypeError::InvalidTokenProgramId,
+    );
+
+    let mut ix = env.withdraw_holder_funds_ix();
+    ix.accounts[7] = AccountMeta::new_readonly(spl_token::id(), false);
+    let admin = env.holder_admin.insecure_clone();
+    assert_hype_error(
+        env.process(&[ix], &[&admin]).await,
+        HypeError::InvalidTokenProgramId,
+    );
+    assert!(env.root_account().await.fees > 0);
+}